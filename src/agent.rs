@@ -0,0 +1,5402 @@
+use crate::{
+    command::{
+        self, host, management,
+        ndnsec::{self, key::PublicKeyInfo},
+        nfdc, Command, Configured, LimitedRunner, ProcessRunner, Runner, StatusSource,
+        WrappedRunner,
+    },
+    digest::sha256_hex,
+    policy::{NanHandling, Policy},
+    rules::{
+        all_of, all_of_tasks, chain_depth, chains_to_anchor, in_range, median, percentile,
+        sustained_growth, trailing_repeats, weighted_score,
+    },
+    task::{
+        Drift, Error, Evaluation, Logging, Logs, Measurement, Numeric, PacketStatistics, Table,
+    },
+};
+use async_std::prelude::{FutureExt as AsyncStdFutureExt, *};
+use chrono::{self, DateTime, Utc};
+use futures::future::{join_all, try_join, try_join_all, BoxFuture, Shared};
+use futures::stream::{FuturesUnordered, Stream};
+use futures::FutureExt;
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, BTreeSet, HashMap, HashSet};
+use std::io::{self, Write};
+use std::str::FromStr;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex, RwLock};
+use std::time::{Duration, Instant};
+use systemstat::{Platform, System};
+
+/// Time limit of each command run by the agent
+pub const TIMEOUT: Duration = Duration::from_millis(1000);
+/// Number of recent ticks whose duration is watched
+const LATENCY_WINDOW: usize = 10;
+/// Minimum number of measurements to tell a trend
+const TREND_MIN_SAMPLES: usize = 3;
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub enum Data {
+    /// Total memory in Bytes
+    HostTotalMemory(u64),
+    NfdStatus(Box<nfdc::NfdcStatus>),
+    CertificateList(Box<ndnsec::list::CertificateList>),
+    CertificateInfo(Box<ndnsec::dump::CertificateInfo>),
+
+    /// M1: CS policy name
+    ///
+    /// Which CS policy is enabled
+    M1(String),
+    /// M2: Maximum size of the CS
+    ///
+    /// How many entries can be stored in the CS
+    M2(u64),
+    /// M3: CS usage
+    ///
+    ///How many entries are stored in the CS
+    M3(u64),
+    /// M4: CS entry syze  statistics
+    ///
+    /// Minimum, maximum, mean and standard deviation of the CS entries memory usage
+    M4(PacketStatistics),
+    /// M5: Interest forwarding policy
+    ///
+    /// Which forwarding policy is used for each interest
+    M5(HashMap<String, String>),
+    /// PIT entries
+    ///
+    /// Number of pending interests stored in the PIT
+    M6(HashMap<u64, i64>),
+
+    /// Interest packet size statistics
+    ///
+    /// Minimum, maximum, mean and standard deviation of the incoming interest packets size
+    M7(HashMap<u64, PacketStatistics>),
+    /// Data packet size statistics
+    ///
+    /// Minimum, maximum, mean and standard deviation of the outgoing data packets size
+    M8(HashMap<u64, PacketStatistics>),
+    /// Interest packets components statistics
+    ///
+    /// Minimum, maximum, mean and standard deviation of the incoming interest packets number of components
+    M9(HashMap<u64, PacketStatistics>),
+    /// Data packets components statistics
+    ///
+    /// Minimum, maximum, mean and standard deviation of the outgoing data packets number of components
+    M10(HashMap<u64, PacketStatistics>),
+
+    /// Contents certifi cates validity
+    ///
+    /// Time interval of validity of the stored contents certificates, by certificate name
+    M11(HashMap<String, (DateTime<Utc>, DateTime<Utc>)>),
+    /// Default content certificate
+    ///
+    /// If and which default content certificate is set
+    M12(Option<String>),
+    /// Node memory
+    ///
+    /// The total amount of system memory of the NFD node
+    M13(u64),
+    /// CS packet signature statistics
+    ///
+    /// Number of valid and invalid signatures found in CS stored packets
+    M14(u64, u64),
+    /// Incoming nack ratio
+    ///
+    /// Ratio of incoming nacks over incoming interests of the whole forwarder
+    M15(f64),
+    /// Congestion marking intervals
+    ///
+    /// Base congestion marking interval of the faces with congestion marking enabled
+    M16(HashMap<u64, Duration>),
+    /// Content certificates issuers
+    ///
+    /// Identity that signed the certificate of each identity
+    M17(HashMap<String, String>),
+    /// Tick durations
+    ///
+    /// Execution time in nanoseconds of the most recent ticks of the agent
+    M18(Vec<i64>),
+    /// Content store lookups
+    ///
+    /// Cumulative hits and misses of the CS
+    M19(CsCounters),
+    /// Keychain identities
+    ///
+    /// Number of distinct identities holding a certificate
+    M20(u64),
+    /// Face count
+    ///
+    /// Number of faces of the forwarder
+    M21(u64),
+    /// NFD clock
+    ///
+    /// Current time reported by the forwarder
+    M22(DateTime<Utc>),
+    /// Content certificates keys
+    ///
+    /// Algorithm and size of the key of each certificate, by certificate name
+    M23(HashMap<String, PublicKeyInfo>),
+    /// Default certificate validity
+    ///
+    /// Time interval of validity of the default certificate of the default key of the default
+    /// identity, if any
+    M24(Option<(DateTime<Utc>, DateTime<Utc>)>),
+    /// Parse errors
+    ///
+    /// Number of command outputs of the tick that could not be parsed
+    M25(u64),
+    /// Route origins
+    ///
+    /// Origin of each route of every RIB prefix, e.g. `app`, `static` or `nlsr`
+    M26(HashMap<String, Vec<String>>),
+    /// CS usage
+    ///
+    /// Percentage of the CS capacity taken by its entries, `None` for a CS without capacity
+    M27(Option<f64>),
+    /// Required namespace strategies
+    ///
+    /// Strategy in effect for each namespace with a strategy required by the policy, by longest
+    /// prefix, `None` without any strategy choice covering it
+    M28(HashMap<String, Option<String>>),
+    /// CS admit and serve
+    ///
+    /// Whether the CS admits new Data packets and whether it serves cached ones
+    M29(bool, bool),
+    /// NFD memory
+    ///
+    /// Resident memory of the `nfd` process in bytes, `None` when the process is not running
+    M30(Option<u64>),
+    /// Channel schemes
+    ///
+    /// Transport schemes of the channels NFD listens on, e.g. `udp6`, `ws` or `unix`
+    M31(BTreeSet<String>),
+    /// Certificate chain depths
+    ///
+    /// Number of certificates in the signing chain of the certificate of each identity, up to a
+    /// self-signed certificate or an issuer outside of the keychain
+    M32(HashMap<String, u64>),
+    /// Face MTUs
+    ///
+    /// Link type and MTU of each face, the faces with an unlimited MTU left out
+    M33(HashMap<u64, (String, u64)>),
+    /// Face congestion settings
+    ///
+    /// Base marking interval and default threshold of the faces with congestion marking
+    /// enabled, both missing for the faces without congestion settings
+    M34(HashMap<u64, nfdc::Congestion>),
+    /// Unsatisfied interests rate
+    ///
+    /// Cumulative number of unsatisfied interests, with their rate since the previous reading
+    M35(UnsatisfiedInterests),
+    /// FIB next hops
+    ///
+    /// Number of next hops of each FIB prefix
+    M36(BTreeMap<String, usize>),
+    /// Face remote URIs
+    ///
+    /// Remote URI of each face, labelling the faces of the per-face metrics at the same index
+    M37(HashMap<u64, String>),
+    /// Output of the command behind the measurement that could not be parsed, in lenient mode
+    ParseError(String),
+}
+
+/// Cumulative CS lookup counters, with the start time of the forwarder reporting them
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct CsCounters {
+    pub start_time: String,
+    pub n_hits: u64,
+    pub n_misses: u64,
+}
+
+/// Cumulative unsatisfied interests counter, with the start time of the forwarder reporting it
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct UnsatisfiedInterests {
+    pub start_time: String,
+    pub n_unsatisfied_interests: u64,
+    /// Interests per second since the previous reading, `None` for the first reading or across
+    /// a counter reset
+    pub rate: Option<f64>,
+}
+
+impl UnsatisfiedInterests {
+    /// Interests per second since `previous`, read `elapsed` earlier, `None` if the counter was
+    /// reset in between
+    pub fn rate_since(
+        &self,
+        previous: &UnsatisfiedInterests,
+        elapsed: chrono::Duration,
+    ) -> Option<f64> {
+        let elapsed = elapsed.to_std().ok()?;
+        if self.start_time != previous.start_time
+            || self.n_unsatisfied_interests < previous.n_unsatisfied_interests
+            || elapsed.is_zero()
+        {
+            return None;
+        }
+        let delta = self.n_unsatisfied_interests - previous.n_unsatisfied_interests;
+        Some(delta as f64 / elapsed.as_secs_f64())
+    }
+}
+
+impl CsCounters {
+    /// Hits and lookups since `previous`, `None` if the counters were reset in between
+    pub fn since(&self, previous: &CsCounters) -> Option<(u64, u64)> {
+        if self.start_time != previous.start_time
+            || self.n_hits < previous.n_hits
+            || self.n_misses < previous.n_misses
+        {
+            return None;
+        }
+        let hits = self.n_hits - previous.n_hits;
+        Some((hits, hits + self.n_misses - previous.n_misses))
+    }
+
+    /// Hits and lookups over consecutive samples, summing their deltas
+    ///
+    /// Deltas across a counter reset are left out, `None` if no two consecutive samples compare.
+    pub fn windowed(samples: &[CsCounters]) -> Option<(u64, u64)> {
+        samples
+            .windows(2)
+            .filter_map(|pair| pair[1].since(&pair[0]))
+            .fold(None, |total, (hits, lookups)| {
+                let (total_hits, total_lookups) = total.unwrap_or((0, 0));
+                Some((total_hits + hits, total_lookups + lookups))
+            })
+    }
+}
+
+impl Data {
+    /// Whether the measurement holds a NaN value, e.g. the `-nan` average of an idle face
+    pub fn has_nan(&self) -> bool {
+        let statistics_nan = |s: &PacketStatistics| s.avg.is_nan() || s.std_dev.is_nan();
+        match self {
+            Data::M4(statistics) => statistics_nan(statistics),
+            Data::M7(faces) | Data::M8(faces) | Data::M9(faces) | Data::M10(faces) => {
+                faces.values().any(statistics_nan)
+            }
+            Data::M15(v) => v.is_nan(),
+            Data::M27(v) => v.is_some_and(f64::is_nan),
+            _ => false,
+        }
+    }
+
+    /// Value of the scalar metrics
+    pub fn as_f64(&self) -> Option<f64> {
+        match self {
+            Data::M2(v) | Data::M3(v) | Data::M13(v) | Data::M20(v) | Data::M21(v) => {
+                Some(*v as f64)
+            }
+            Data::M15(v) => Some(*v),
+            Data::M27(v) => *v,
+            Data::M30(v) => v.map(|v| v as f64),
+            _ => None,
+        }
+    }
+}
+
+impl Numeric for Data {
+    fn as_f64(&self) -> Option<f64> {
+        Data::as_f64(self)
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
+pub enum Metrics {
+    M1,
+    M2,
+    M3,
+    M4,
+    M5,
+    M6,
+    M7,
+    M8,
+    M9,
+    M10,
+    M11,
+    M12,
+    M13,
+    M14,
+    M15,
+    M16,
+    M17,
+    M18,
+    M19,
+    M20,
+    M21,
+    M22,
+    M23,
+    M24,
+    M25,
+    M26,
+    M27,
+    M28,
+    M29,
+    M30,
+    M31,
+    M32,
+    M33,
+    M34,
+    M35,
+    M36,
+    M37,
+}
+
+impl Metrics {
+    /// Every metric, in order
+    pub const ALL: &'static [Metrics] = &[
+        Metrics::M1,
+        Metrics::M2,
+        Metrics::M3,
+        Metrics::M4,
+        Metrics::M5,
+        Metrics::M6,
+        Metrics::M7,
+        Metrics::M8,
+        Metrics::M9,
+        Metrics::M10,
+        Metrics::M11,
+        Metrics::M12,
+        Metrics::M13,
+        Metrics::M14,
+        Metrics::M15,
+        Metrics::M16,
+        Metrics::M17,
+        Metrics::M18,
+        Metrics::M19,
+        Metrics::M20,
+        Metrics::M21,
+        Metrics::M22,
+        Metrics::M23,
+        Metrics::M24,
+        Metrics::M25,
+        Metrics::M26,
+        Metrics::M27,
+        Metrics::M28,
+        Metrics::M29,
+        Metrics::M30,
+        Metrics::M31,
+        Metrics::M32,
+        Metrics::M33,
+        Metrics::M34,
+        Metrics::M35,
+        Metrics::M36,
+        Metrics::M37,
+    ];
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
+pub enum Tasks {
+    C1,
+    C2,
+    C3,
+    C4,
+    C5,
+    C6,
+    C7,
+    C8,
+    C9,
+    C10,
+    C11,
+    C12,
+    C13,
+    C14,
+    C15,
+    C16,
+    C17,
+    C18,
+    C19,
+    C20,
+    C21,
+    C22,
+    C23,
+    C24,
+    C25,
+    C26,
+    C27,
+    C28,
+    C29,
+    C30,
+    C31,
+    C32,
+    C33,
+    C34,
+    C35,
+    C36,
+    C37,
+    C38,
+
+    R1,
+    R2,
+    R3,
+    R4,
+    R5,
+    R6,
+    R7,
+    R8,
+    R9,
+    R10,
+    R11,
+    R12,
+    R13,
+    R14,
+    R15,
+    R16,
+    R17,
+    R18,
+    R19,
+    R20,
+    R21,
+    R22,
+    R23,
+    R24,
+    R25,
+    R26,
+    R27,
+    R28,
+    R29,
+    R30,
+    R31,
+    R32,
+
+    P1,
+    P2,
+    P3,
+}
+
+impl Tasks {
+    /// Metrics a constraint is evaluated on, none for rules and properties
+    pub fn metrics(&self) -> &'static [Metrics] {
+        match self {
+            Tasks::C1 => &[Metrics::M1],
+            Tasks::C2 => &[Metrics::M2, Metrics::M13],
+            Tasks::C3 => &[Metrics::M2],
+            Tasks::C4 => &[Metrics::M2, Metrics::M3],
+            Tasks::C5 => &[Metrics::M3],
+            Tasks::C6 | Tasks::C7 => &[Metrics::M4],
+            Tasks::C8 => &[Metrics::M6, Metrics::M37],
+            Tasks::C9 => &[Metrics::M7],
+            Tasks::C10 => &[Metrics::M9],
+            Tasks::C11 => &[Metrics::M8],
+            Tasks::C12 => &[Metrics::M10],
+            Tasks::C13 => &[Metrics::M11],
+            Tasks::C14 => &[Metrics::M12],
+            Tasks::C15 => &[Metrics::M14],
+            Tasks::C16 => &[Metrics::M15],
+            Tasks::C17 => &[Metrics::M16],
+            Tasks::C18 => &[Metrics::M17],
+            Tasks::C19 => &[Metrics::M18],
+            Tasks::C20 => &[Metrics::M19],
+            Tasks::C21 => &[Metrics::M20],
+            Tasks::C22 | Tasks::C27 => &[Metrics::M21],
+            Tasks::C23 => &[Metrics::M22],
+            Tasks::C24 => &[Metrics::M23],
+            Tasks::C25 => &[Metrics::M24],
+            Tasks::C26 => &[Metrics::M25],
+            Tasks::C28 => &[Metrics::M26],
+            Tasks::C29 => &[Metrics::M27],
+            Tasks::C30 => &[Metrics::M28],
+            Tasks::C31 => &[Metrics::M29],
+            Tasks::C32 => &[Metrics::M30],
+            Tasks::C33 => &[Metrics::M31],
+            Tasks::C34 => &[Metrics::M32],
+            Tasks::C35 => &[Metrics::M33],
+            Tasks::C36 => &[Metrics::M34],
+            Tasks::C37 => &[Metrics::M35],
+            Tasks::C38 => &[Metrics::M36],
+            _ => &[],
+        }
+    }
+
+    /// Tasks a rule or property is evaluated on, none for constraints
+    pub fn dependencies(&self) -> &'static [Tasks] {
+        match self {
+            Tasks::R1 => &[Tasks::C1, Tasks::C2, Tasks::C3],
+            Tasks::R2 => &[Tasks::C4, Tasks::C5, Tasks::C6, Tasks::C7],
+            Tasks::R3 => &[Tasks::C8],
+            Tasks::R4 => &[Tasks::C9, Tasks::C10],
+            Tasks::R5 => &[Tasks::C11, Tasks::C12],
+            Tasks::R6 => &[Tasks::C13],
+            Tasks::R7 => &[Tasks::C14],
+            Tasks::R8 => &[Tasks::C15],
+            Tasks::R9 => &[Tasks::C16],
+            Tasks::R10 => &[
+                Tasks::C1,
+                Tasks::C2,
+                Tasks::C3,
+                Tasks::C4,
+                Tasks::C5,
+                Tasks::C6,
+                Tasks::C7,
+                Tasks::C8,
+                Tasks::C9,
+                Tasks::C10,
+                Tasks::C11,
+                Tasks::C12,
+                Tasks::C16,
+                Tasks::C17,
+            ],
+            Tasks::R11 => &[Tasks::C17],
+            Tasks::R12 => &[Tasks::C18],
+            Tasks::R13 => &[Tasks::C19],
+            Tasks::R14 => &[Tasks::C20],
+            Tasks::R15 => &[Tasks::C21],
+            Tasks::R16 => &[Tasks::C22],
+            Tasks::R17 => &[Tasks::C23],
+            Tasks::R18 => &[Tasks::C24],
+            Tasks::R19 => &[Tasks::C25],
+            Tasks::R20 => &[Tasks::C26],
+            Tasks::R21 => &[Tasks::C27],
+            Tasks::R22 => &[Tasks::C28],
+            Tasks::R23 => &[Tasks::C29],
+            Tasks::R24 => &[Tasks::C30],
+            Tasks::R25 => &[Tasks::C31],
+            Tasks::R26 => &[Tasks::C32],
+            Tasks::R27 => &[Tasks::C33],
+            Tasks::R28 => &[Tasks::C34],
+            Tasks::R29 => &[Tasks::C35],
+            Tasks::R30 => &[Tasks::C36],
+            Tasks::R31 => &[Tasks::C37],
+            Tasks::R32 => &[Tasks::C38],
+            Tasks::P1 => &[
+                Tasks::R1,
+                Tasks::R2,
+                Tasks::R3,
+                Tasks::R4,
+                Tasks::R5,
+                Tasks::R9,
+                Tasks::R10,
+                Tasks::R11,
+                Tasks::R13,
+                Tasks::R14,
+                Tasks::R16,
+                Tasks::R17,
+                Tasks::R20,
+                Tasks::R21,
+                Tasks::R22,
+                Tasks::R23,
+                Tasks::R24,
+                Tasks::R25,
+                Tasks::R26,
+                Tasks::R27,
+                Tasks::R29,
+                Tasks::R30,
+                Tasks::R31,
+                Tasks::R32,
+            ],
+            Tasks::P2 => &[
+                Tasks::R6,
+                Tasks::R7,
+                Tasks::R12,
+                Tasks::R15,
+                Tasks::R18,
+                Tasks::R19,
+                Tasks::R28,
+            ],
+            Tasks::P3 => &[Tasks::R6, Tasks::R7, Tasks::R8],
+            _ => &[],
+        }
+    }
+}
+
+impl Logs<Metrics, Tasks, Data> {
+    /// Percentile `p` of the average statistics of a face measured since the given timestamp
+    ///
+    /// Only the per-face statistics metrics, M7 to M10, are supported. Faces without a
+    /// measured average have no percentile.
+    pub fn rolling_percentile(
+        &self,
+        metric: &Metrics,
+        face: u64,
+        p: f64,
+        since: DateTime<Utc>,
+    ) -> Option<f64> {
+        let averages = self
+            .measurements_timestamp
+            .get(metric)?
+            .iter()
+            .filter(|(timestamp, _)| *timestamp >= since)
+            .filter_map(|(_, data)| match data {
+                Data::M7(v) | Data::M8(v) | Data::M9(v) | Data::M10(v) => v.get(&face),
+                _ => None,
+            })
+            .map(|statistics| statistics.avg)
+            .filter(|avg| !avg.is_nan())
+            .collect::<Vec<_>>();
+        percentile(&averages, p)
+    }
+}
+
+/// Whether the packets of every face are large enough
+///
+/// With a percentile set by the policy, the average sizes within the window are checked instead
+/// of the minimum size of the tick.
+fn packet_sizes_hold(
+    statistics: &HashMap<u64, PacketStatistics>,
+    metric: &Metrics,
+    logs: &Logs<Metrics, Tasks, Data>,
+    policy: &Policy,
+    now: DateTime<Utc>,
+) -> bool {
+    match policy.packet_size_percentile {
+        None => statistics
+            .values()
+            .all(|s| in_range(s.min, policy.min_packet_size, u64::MAX, true)),
+        Some(p) => {
+            let since = now - chrono::Duration::minutes(2);
+            statistics.keys().all(|face| {
+                logs.rolling_percentile(metric, *face, p, since)
+                    .is_none_or(|size| size >= policy.min_packet_size as f64)
+            })
+        }
+    }
+}
+
+/// Whether the latest samples of a scalar metric have a standard deviation below the policy
+/// threshold, false until the stability window is filled
+fn is_stable(
+    logs: &Logs<Metrics, Tasks, Data>,
+    metric: &Metrics,
+    index: u64,
+    policy: &Policy,
+) -> bool {
+    let window = policy.stability_window;
+    if index + 1 < window as u64 {
+        return false;
+    }
+    let samples = logs
+        .measurements_index
+        .get(metric)
+        .into_iter()
+        .flatten()
+        .rev()
+        .take(window)
+        .filter_map(|(_, data)| data.as_f64())
+        .collect::<Vec<_>>();
+    if samples.len() < 2 {
+        return false;
+    }
+    let mean = samples.iter().sum::<f64>() / samples.len() as f64;
+    let variance = samples
+        .iter()
+        .fold(0_f64, |acc, new| acc + (new - mean).powi(2))
+        / (samples.len() - 1) as f64;
+    variance.sqrt() < policy.max_stability_std_dev
+}
+
+pub type MeasurementResult = Result<Logging<Measurement<Data>, Metrics, Tasks, Data>, Error>;
+pub type EvaluationResult = Result<Logging<Evaluation, Metrics, Tasks, Data>, Error>;
+
+async fn nfdc_status(runner: &dyn Runner, policy: &Policy) -> Result<nfdc::NfdcStatus, Error> {
+    let output = nfdc::NfdcCommand::Status
+        .with_config(policy.commands.clone())
+        .run_with_retry(runner, &policy.commands.retry, TIMEOUT)
+        .await?;
+    let completed_at = Utc::now();
+    let mut res =
+        serde_xml_rs::from_str::<nfdc::NfdcStatus>(&output).map_err(command::Error::from)?;
+    res.completed_at = Some(completed_at);
+    res.check_face_ids()?;
+    if policy.exclude_internal_faces {
+        res.exclude_internal_faces();
+    }
+    res.exclude_faces(&policy.excluded_face_ids, &policy.excluded_remote_uris);
+    if policy.record_source_digests {
+        res.source_digest = Some(sha256_hex(output.as_bytes()));
+    }
+    Ok(res)
+}
+
+/// General status of the forwarder, with the digest and completion time of its source
+#[derive(Debug, Clone)]
+struct GeneralStatusReading {
+    status: nfdc::GeneralStatus,
+    source_digest: Option<String>,
+    completed_at: Option<DateTime<Utc>>,
+}
+
+impl From<nfdc::NfdcStatus> for GeneralStatusReading {
+    fn from(status: nfdc::NfdcStatus) -> Self {
+        Self {
+            status: status.general_status,
+            source_digest: status.source_digest,
+            completed_at: status.completed_at,
+        }
+    }
+}
+
+/// General status of the forwarder, read from the source selected by the policy
+///
+/// Over the management socket the dataset is fetched within [`TIMEOUT`], without digest.
+async fn general_status<D1>(
+    nfd_status_f: D1,
+    policy: &Policy,
+) -> Result<GeneralStatusReading, Error>
+where
+    D1: Future<Output = Result<nfdc::NfdcStatus, Error>>,
+{
+    match policy.commands.status_source {
+        StatusSource::Nfdc => Ok(nfd_status_f.await?.into()),
+        StatusSource::Management => {
+            let status = management::general_status(&policy.commands.nfd_socket, TIMEOUT).await?;
+            Ok(GeneralStatusReading {
+                status,
+                source_digest: None,
+                completed_at: Some(Utc::now()),
+            })
+        }
+    }
+}
+
+async fn ndnsec_list(
+    runner: &dyn Runner,
+    policy: &Policy,
+) -> Result<ndnsec::list::CertificateList, Error> {
+    let output = ndnsec::NdnSecCommand::List
+        .with_config(policy.commands.clone())
+        .run_with_retry(runner, &policy.commands.retry, TIMEOUT)
+        .await?;
+    let completed_at = Utc::now();
+    let mut res = ndnsec::list::CertificateList::from_str(&output)?;
+    res.completed_at = Some(completed_at);
+    Ok(res)
+}
+
+async fn ndnsec_info(
+    command: Configured<ndnsec::NdnSecCommand>,
+    runner: &dyn Runner,
+) -> Result<ndnsec::dump::CertificateInfo, Error> {
+    let output = command.run_with(runner).await?;
+    let completed_at = Utc::now();
+    let mut res = ndnsec::dump::CertificateInfo::from_str(&output)?;
+    res.completed_at = Some(completed_at);
+    Ok(res)
+}
+
+async fn ndnsec_infos<D1>(
+    certificate_list_f: D1,
+    runner: &dyn Runner,
+    policy: &Policy,
+) -> Result<Vec<(String, ndnsec::dump::CertificateInfo)>, Error>
+where
+    D1: Future<Output = Result<ndnsec::list::CertificateList, Error>>,
+{
+    let certificate_list: ndnsec::list::CertificateList = certificate_list_f.await?;
+    let duplicated = certificate_list.duplicated_identities();
+    let infos = try_join_all(certificate_list.certificates.into_iter().map(|c| {
+        // Dumping an identity yields its default certificate only, so the certificates of an
+        // identity listed more than once, e.g. by split keychains, are dumped by name
+        let command = if duplicated.contains(&c.identity) {
+            ndnsec::NdnSecCommand::DumpCertificate(c.certificate)
+        } else {
+            ndnsec::NdnSecCommand::Dump(c.identity.clone())
+        }
+        .with_config(policy.commands.clone());
+        let i = c.identity;
+        async move {
+            // Dumps can be given a longer budget than the other commands, on slow keychains,
+            // that they enforce themselves
+            let info = match command.timeout() {
+                Some(_) => Ok(ndnsec_info(command, runner).await),
+                None => ndnsec_info(command, runner).timeout(TIMEOUT).await,
+            };
+            match info {
+                Err(t) => Err(Error::TimeoutError(t)),
+                // Identities without a default certificate have nothing to dump
+                Ok(Err(Error::TaskError(e))) if ndnsec::is_missing_default_certificate(&e) => {
+                    Ok(None)
+                }
+                Ok(Err(e)) => Err(e),
+                Ok(Ok(d)) => Ok(Some((i, d))),
+            }
+        }
+    }))
+    .await?;
+    Ok(infos.into_iter().flatten().collect())
+}
+
+pub fn host_total_memory() -> Result<u64, Error> {
+    let sys = System::new();
+    sys.memory()
+        .map(|m| m.total.as_u64())
+        .map_err(|e| command::Error::OutputError(format!("Could not read memory info: {}", e)))
+        .map_err(Error::TaskError)
+}
+
+/// Reads a value of the host, e.g. its memory
+type HostRead<T> = Box<dyn Fn() -> BoxFuture<'static, Result<T, Error>> + Send + Sync>;
+
+/// Host read running the blocking `read` on a thread of its own, so that it neither stalls the
+/// other tasks nor escapes their timeouts
+fn blocking_read<T, F>(read: F) -> HostRead<T>
+where
+    T: Send + 'static,
+    F: Fn() -> Result<T, Error> + Send + Sync + 'static,
+{
+    let read = Arc::new(read);
+    Box::new(move || {
+        let read = read.clone();
+        async_std::task::spawn_blocking(move || read()).boxed()
+    })
+}
+
+/// Total memory of the host, read once as it does not change during a run
+///
+/// A failed read is not cached, the next tick reads again.
+pub struct TotalMemory {
+    read: HostRead<u64>,
+    total: Mutex<Option<u64>>,
+}
+
+impl TotalMemory {
+    /// Total memory read by the blocking `read`, run on a thread of its own
+    pub fn new<F: Fn() -> Result<u64, Error> + Send + Sync + 'static>(read: F) -> Self {
+        Self::with_read(blocking_read(read))
+    }
+
+    /// Total memory of the node `runner` runs its commands on, e.g. a remote node
+    pub fn through<R: Runner + 'static>(runner: R) -> Self {
+        let runner = Arc::new(runner);
+        Self::with_read(Box::new(move || {
+            let runner = runner.clone();
+            async move { Ok(host::total_memory(runner.as_ref()).await?) }.boxed()
+        }))
+    }
+
+    fn with_read(read: HostRead<u64>) -> Self {
+        Self {
+            read,
+            total: Mutex::new(None),
+        }
+    }
+
+    pub async fn get(&self) -> Result<u64, Error> {
+        let total = *self.total.lock().unwrap();
+        match total {
+            Some(bytes) => Ok(bytes),
+            None => {
+                let bytes = (self.read)().await?;
+                *self.total.lock().unwrap() = Some(bytes);
+                Ok(bytes)
+            }
+        }
+    }
+}
+
+impl Default for TotalMemory {
+    fn default() -> Self {
+        Self::new(host_total_memory)
+    }
+}
+
+/// Name of the forwarder process
+pub const NFD_PROCESS: &str = "nfd";
+
+/// Resident memory of the forwarder process, `None` when it is not running
+///
+/// Blocks while the processes are listed, reading their memory only.
+pub fn nfd_resident_memory() -> Result<Option<u64>, Error> {
+    let mut sys = sysinfo::System::new();
+    sys.refresh_processes_specifics(sysinfo::ProcessRefreshKind::new().with_memory());
+    Ok(sys
+        .processes_by_exact_name(NFD_PROCESS)
+        .map(|process| process.memory())
+        .max())
+}
+
+/// Resident memory of the forwarder process, read at every tick
+pub struct ProcessMemory {
+    read: HostRead<Option<u64>>,
+}
+
+impl ProcessMemory {
+    /// Resident memory read by the blocking `read`, run on a thread of its own
+    pub fn new<F: Fn() -> Result<Option<u64>, Error> + Send + Sync + 'static>(read: F) -> Self {
+        Self {
+            read: blocking_read(read),
+        }
+    }
+
+    /// Resident memory of the forwarder of the node `runner` runs its commands on, e.g. a remote
+    /// node
+    pub fn through<R: Runner + 'static>(runner: R) -> Self {
+        let runner = Arc::new(runner);
+        Self {
+            read: Box::new(move || {
+                let runner = runner.clone();
+                async move { Ok(host::resident_memory(NFD_PROCESS, runner.as_ref()).await?) }
+                    .boxed()
+            }),
+        }
+    }
+
+    pub async fn get(&self) -> Result<Option<u64>, Error> {
+        (self.read)().await
+    }
+}
+
+impl Default for ProcessMemory {
+    fn default() -> Self {
+        Self::new(nfd_resident_memory)
+    }
+}
+
+/// Time the last of the certificate dumps completed
+fn dumps_completed_at(
+    certificate_info: &[(String, ndnsec::dump::CertificateInfo)],
+) -> Option<DateTime<Utc>> {
+    certificate_info
+        .iter()
+        .filter_map(|(_, info)| info.completed_at)
+        .max()
+}
+
+async fn m1<D1>(
+    nfd_status_f: D1,
+    index: u64,
+    mut logs: Logs<Metrics, Tasks, Data>,
+) -> MeasurementResult
+where
+    D1: Future<Output = Result<nfdc::NfdcStatus, Error>>,
+{
+    let res: nfdc::NfdcStatus = nfd_status_f.await?;
+    let source_digest = res.source_digest.clone();
+    let completed_at = res.completed_at;
+    let data = Data::M1(res.cs.policy_name);
+    let measurement = Measurement::new(data, index)
+        .with_source_digest(source_digest)
+        .with_completed_at(completed_at);
+    logs.insert_measurement(measurement.clone(), Metrics::M1);
+    Ok(Logging(measurement, logs))
+}
+
+async fn m2<D1>(
+    nfd_status_f: D1,
+    index: u64,
+    mut logs: Logs<Metrics, Tasks, Data>,
+) -> MeasurementResult
+where
+    D1: Future<Output = Result<nfdc::NfdcStatus, Error>>,
+{
+    let res: nfdc::NfdcStatus = nfd_status_f.await?;
+    let source_digest = res.source_digest.clone();
+    let completed_at = res.completed_at;
+    let data = Data::M2(res.cs.capacity);
+    let measurement = Measurement::new(data, index)
+        .with_source_digest(source_digest)
+        .with_completed_at(completed_at);
+    logs.insert_measurement(measurement.clone(), Metrics::M2);
+    Ok(Logging(measurement, logs))
+}
+
+async fn m3<D1>(
+    nfd_status_f: D1,
+    index: u64,
+    mut logs: Logs<Metrics, Tasks, Data>,
+) -> MeasurementResult
+where
+    D1: Future<Output = Result<nfdc::NfdcStatus, Error>>,
+{
+    let res: nfdc::NfdcStatus = nfd_status_f.await?;
+    let source_digest = res.source_digest.clone();
+    let completed_at = res.completed_at;
+    let data = Data::M3(res.cs.n_entries);
+    let measurement = Measurement::new(data, index)
+        .with_source_digest(source_digest)
+        .with_completed_at(completed_at);
+    logs.insert_measurement(measurement.clone(), Metrics::M3);
+    Ok(Logging(measurement, logs))
+}
+
+async fn m4<D1>(
+    nfd_status_f: D1,
+    index: u64,
+    mut logs: Logs<Metrics, Tasks, Data>,
+) -> MeasurementResult
+where
+    D1: Future<Output = Result<nfdc::NfdcStatus, Error>>,
+{
+    let res: nfdc::NfdcStatus = nfd_status_f.await?;
+    let source_digest = res.source_digest.clone();
+    let completed_at = res.completed_at;
+    let data = Data::M4(PacketStatistics {
+        min: res.cs.min_size,
+        max: res.cs.max_size,
+        avg: res.cs.average_size,
+        std_dev: res.cs.std_dev_size,
+    });
+    let measurement = Measurement::new(data, index)
+        .with_source_digest(source_digest)
+        .with_completed_at(completed_at);
+    logs.insert_measurement(measurement.clone(), Metrics::M4);
+    Ok(Logging(measurement, logs))
+}
+
+async fn m5<D1>(
+    nfd_status_f: D1,
+    index: u64,
+    mut logs: Logs<Metrics, Tasks, Data>,
+) -> MeasurementResult
+where
+    D1: Future<Output = Result<nfdc::NfdcStatus, Error>>,
+{
+    let res: nfdc::NfdcStatus = nfd_status_f.await?;
+    let source_digest = res.source_digest.clone();
+    let completed_at = res.completed_at;
+    let data = Data::M5(
+        res.strategy_choices
+            .strategy_choice
+            .into_iter()
+            .map(|sc| (sc.namespace.into(), sc.strategy.name))
+            .collect(),
+    );
+    let measurement = Measurement::new(data, index)
+        .with_source_digest(source_digest)
+        .with_completed_at(completed_at);
+    logs.insert_measurement(measurement.clone(), Metrics::M5);
+    Ok(Logging(measurement, logs))
+}
+
+async fn m6<D1>(
+    nfd_status_f: D1,
+    index: u64,
+    mut logs: Logs<Metrics, Tasks, Data>,
+) -> MeasurementResult
+where
+    D1: Future<Output = Result<nfdc::NfdcStatus, Error>>,
+{
+    let res: nfdc::NfdcStatus = nfd_status_f.await?;
+    let source_digest = res.source_digest.clone();
+    let completed_at = res.completed_at;
+    let data = Data::M6(
+        res.faces
+            .face
+            .into_iter()
+            .map(|f| (f.face_id, f.packet_counters.unanswered_interests()))
+            .collect(),
+    );
+    let measurement = Measurement::new(data, index)
+        .with_source_digest(source_digest)
+        .with_completed_at(completed_at);
+    logs.insert_measurement(measurement.clone(), Metrics::M6);
+    Ok(Logging(measurement, logs))
+}
+
+async fn m37<D1>(
+    nfd_status_f: D1,
+    index: u64,
+    mut logs: Logs<Metrics, Tasks, Data>,
+) -> MeasurementResult
+where
+    D1: Future<Output = Result<nfdc::NfdcStatus, Error>>,
+{
+    let res: nfdc::NfdcStatus = nfd_status_f.await?;
+    let source_digest = res.source_digest.clone();
+    let completed_at = res.completed_at;
+    let data = Data::M37(
+        res.faces
+            .face
+            .into_iter()
+            .map(|f| (f.face_id, f.remote_uri))
+            .collect(),
+    );
+    let measurement = Measurement::new(data, index)
+        .with_source_digest(source_digest)
+        .with_completed_at(completed_at);
+    logs.insert_measurement(measurement.clone(), Metrics::M37);
+    Ok(Logging(measurement, logs))
+}
+
+async fn m7<D1>(
+    nfd_status_f: D1,
+    index: u64,
+    mut logs: Logs<Metrics, Tasks, Data>,
+) -> MeasurementResult
+where
+    D1: Future<Output = Result<nfdc::NfdcStatus, Error>>,
+{
+    let res: nfdc::NfdcStatus = nfd_status_f.await?;
+    let source_digest = res.source_digest.clone();
+    let completed_at = res.completed_at;
+    let data = Data::M7(
+        res.faces
+            .face
+            .into_iter()
+            .map(|f| (f.face_id, f.interest_packet_size))
+            .collect(),
+    );
+    let measurement = Measurement::new(data, index)
+        .with_source_digest(source_digest)
+        .with_completed_at(completed_at);
+    logs.insert_measurement(measurement.clone(), Metrics::M7);
+    Ok(Logging(measurement, logs))
+}
+
+async fn m8<D1>(
+    nfd_status_f: D1,
+    index: u64,
+    mut logs: Logs<Metrics, Tasks, Data>,
+) -> MeasurementResult
+where
+    D1: Future<Output = Result<nfdc::NfdcStatus, Error>>,
+{
+    let res: nfdc::NfdcStatus = nfd_status_f.await?;
+    let source_digest = res.source_digest.clone();
+    let completed_at = res.completed_at;
+    let data = Data::M8(
+        res.faces
+            .face
+            .into_iter()
+            .map(|f| (f.face_id, f.data_packet_size))
+            .collect(),
+    );
+    let measurement = Measurement::new(data, index)
+        .with_source_digest(source_digest)
+        .with_completed_at(completed_at);
+    logs.insert_measurement(measurement.clone(), Metrics::M8);
+    Ok(Logging(measurement, logs))
+}
+
+async fn m9<D1>(
+    nfd_status_f: D1,
+    index: u64,
+    mut logs: Logs<Metrics, Tasks, Data>,
+) -> MeasurementResult
+where
+    D1: Future<Output = Result<nfdc::NfdcStatus, Error>>,
+{
+    let res: nfdc::NfdcStatus = nfd_status_f.await?;
+    let source_digest = res.source_digest.clone();
+    let completed_at = res.completed_at;
+    let data = Data::M9(
+        res.faces
+            .face
+            .into_iter()
+            .map(|f| (f.face_id, f.interest_packet_components))
+            .collect(),
+    );
+    let measurement = Measurement::new(data, index)
+        .with_source_digest(source_digest)
+        .with_completed_at(completed_at);
+    logs.insert_measurement(measurement.clone(), Metrics::M9);
+    Ok(Logging(measurement, logs))
+}
+
+async fn m10<D1>(
+    nfd_status_f: D1,
+    index: u64,
+    mut logs: Logs<Metrics, Tasks, Data>,
+) -> MeasurementResult
+where
+    D1: Future<Output = Result<nfdc::NfdcStatus, Error>>,
+{
+    let res: nfdc::NfdcStatus = nfd_status_f.await?;
+    let source_digest = res.source_digest.clone();
+    let completed_at = res.completed_at;
+    let data = Data::M10(
+        res.faces
+            .face
+            .into_iter()
+            .map(|f| (f.face_id, f.data_packet_components))
+            .collect(),
+    );
+    let measurement = Measurement::new(data, index)
+        .with_source_digest(source_digest)
+        .with_completed_at(completed_at);
+    logs.insert_measurement(measurement.clone(), Metrics::M10);
+    Ok(Logging(measurement, logs))
+}
+
+/// Validity of the certificates of the identities subject to the certificate constraints
+async fn m11<D1>(
+    certificate_infos_f: D1,
+    policy: &Policy,
+    index: u64,
+    mut logs: Logs<Metrics, Tasks, Data>,
+) -> MeasurementResult
+where
+    D1: Future<Output = Result<Vec<(String, ndnsec::dump::CertificateInfo)>, Error>>,
+{
+    let certificate_info = certificate_infos_f.await?;
+    let completed_at = dumps_completed_at(&certificate_info);
+    let data = Data::M11(
+        certificate_info
+            .into_iter()
+            .filter(|(i, _)| policy.certifies_identity(i))
+            .map(|(_, d)| {
+                let validity = (d.validity_not_before, d.validity_not_after);
+                (d.certificate_name, validity)
+            })
+            .collect(),
+    );
+    let measurement = Measurement::new(data, index).with_completed_at(completed_at);
+    logs.insert_measurement(measurement.clone(), Metrics::M11);
+    Ok(Logging(measurement, logs))
+}
+
+async fn m23<D1>(
+    certificate_infos_f: D1,
+    index: u64,
+    mut logs: Logs<Metrics, Tasks, Data>,
+) -> MeasurementResult
+where
+    D1: Future<Output = Result<Vec<(String, ndnsec::dump::CertificateInfo)>, Error>>,
+{
+    let certificate_info = certificate_infos_f.await?;
+    let completed_at = dumps_completed_at(&certificate_info);
+    let data = Data::M23(
+        certificate_info
+            .into_iter()
+            .map(|(_, d)| d.public_key_info().map(|key| (d.certificate_name, key)))
+            .collect::<Result<_, _>>()?,
+    );
+    let measurement = Measurement::new(data, index).with_completed_at(completed_at);
+    logs.insert_measurement(measurement.clone(), Metrics::M23);
+    Ok(Logging(measurement, logs))
+}
+
+async fn m24<D1, D2>(
+    certificate_list_f: D1,
+    certificate_infos_f: D2,
+    index: u64,
+    mut logs: Logs<Metrics, Tasks, Data>,
+) -> MeasurementResult
+where
+    D1: Future<Output = Result<ndnsec::list::CertificateList, Error>>,
+    D2: Future<Output = Result<Vec<(String, ndnsec::dump::CertificateInfo)>, Error>>,
+{
+    let res: ndnsec::list::CertificateList = certificate_list_f.await?;
+    let certificate_info = certificate_infos_f.await?;
+    let completed_at = res.completed_at.max(dumps_completed_at(&certificate_info));
+    let data = Data::M24(
+        res.certificates
+            .into_iter()
+            .find(|c| c.is_default)
+            .and_then(|default| {
+                certificate_info.into_iter().find(|(identity, info)| {
+                    *identity == default.identity && info.certificate_name == default.certificate
+                })
+            })
+            .map(|(_, info)| (info.validity_not_before, info.validity_not_after)),
+    );
+    let measurement = Measurement::new(data, index).with_completed_at(completed_at);
+    logs.insert_measurement(measurement.clone(), Metrics::M24);
+    Ok(Logging(measurement, logs))
+}
+
+async fn m25<D1, D2, D3>(
+    nfd_status_f: D1,
+    certificate_list_f: D2,
+    certificate_infos_f: D3,
+    index: u64,
+    mut logs: Logs<Metrics, Tasks, Data>,
+) -> MeasurementResult
+where
+    D1: Future<Output = Result<nfdc::NfdcStatus, Error>>,
+    D2: Future<Output = Result<ndnsec::list::CertificateList, Error>>,
+    D3: Future<Output = Result<Vec<(String, ndnsec::dump::CertificateInfo)>, Error>>,
+{
+    let sources = [
+        nfd_status_f.await.map(drop),
+        certificate_list_f.await.map(drop),
+        certificate_infos_f.await.map(drop),
+    ];
+    let mut parse_errors = 0;
+    for source in sources.iter() {
+        match source {
+            Err(e) if e.is_parse_error() => parse_errors += 1,
+            Err(e) => return Err(e.clone()),
+            Ok(()) => {}
+        }
+    }
+    let data = Data::M25(parse_errors);
+    let measurement = Measurement::new(data, index);
+    logs.insert_measurement(measurement.clone(), Metrics::M25);
+    Ok(Logging(measurement, logs))
+}
+
+/// Record a measurement whose command output cannot be parsed as a [`Data::ParseError`]
+///
+/// Only in lenient mode, otherwise the parse error fails the measurement.
+async fn leniently(
+    measurement: BoxFuture<'_, MeasurementResult>,
+    metric: Metrics,
+    index: u64,
+    mut logs: Logs<Metrics, Tasks, Data>,
+    policy: &Policy,
+) -> MeasurementResult {
+    match measurement.await {
+        Err(e) if policy.lenient_parsing && e.is_parse_error() => {
+            let measurement = Measurement::new(Data::ParseError(e.to_string()), index);
+            logs.insert_measurement(measurement.clone(), metric);
+            Ok(Logging(measurement, logs))
+        }
+        result => result,
+    }
+}
+
+/// Default certificate of the default identity, `None` as well when the policy ignores it
+async fn m12<D1>(
+    certificate_list_f: D1,
+    policy: &Policy,
+    index: u64,
+    mut logs: Logs<Metrics, Tasks, Data>,
+) -> MeasurementResult
+where
+    D1: Future<Output = Result<ndnsec::list::CertificateList, Error>>,
+{
+    let res: ndnsec::list::CertificateList = certificate_list_f.await?;
+    let completed_at = res.completed_at;
+    let data = Data::M12(
+        res.certificates
+            .into_iter()
+            .filter_map(|c| {
+                if c.is_default && policy.certifies_identity(&c.identity) {
+                    Some(c.certificate)
+                } else {
+                    None
+                }
+            })
+            .next(),
+    );
+    let measurement = Measurement::new(data, index).with_completed_at(completed_at);
+    logs.insert_measurement(measurement.clone(), Metrics::M12);
+    Ok(Logging(measurement, logs))
+}
+
+async fn m13<D1>(
+    host_total_memory_f: D1,
+    index: u64,
+    mut logs: Logs<Metrics, Tasks, Data>,
+) -> MeasurementResult
+where
+    D1: Future<Output = Result<u64, Error>>,
+{
+    let res = host_total_memory_f.timeout(TIMEOUT).await??;
+    let data = Data::M13(res);
+    let measurement = Measurement::new(data, index);
+    logs.insert_measurement(measurement.clone(), Metrics::M13);
+    Ok(Logging(measurement, logs))
+}
+
+async fn m30<D1>(
+    nfd_memory_f: D1,
+    index: u64,
+    mut logs: Logs<Metrics, Tasks, Data>,
+) -> MeasurementResult
+where
+    D1: Future<Output = Result<Option<u64>, Error>>,
+{
+    let res = nfd_memory_f.timeout(TIMEOUT).await??;
+    let measurement = Measurement::new(Data::M30(res), index);
+    logs.insert_measurement(measurement.clone(), Metrics::M30);
+    Ok(Logging(measurement, logs))
+}
+
+async fn m14<D1>(
+    nfd_status_f: D1,
+    index: u64,
+    mut logs: Logs<Metrics, Tasks, Data>,
+) -> MeasurementResult
+where
+    D1: Future<Output = Result<nfdc::NfdcStatus, Error>>,
+{
+    let res: nfdc::NfdcStatus = nfd_status_f.await?;
+    let source_digest = res.source_digest.clone();
+    let completed_at = res.completed_at;
+    let data = Data::M14(
+        res.cs.valid_signature_packets,
+        res.cs.invalid_signature_packets,
+    );
+    let measurement = Measurement::new(data, index)
+        .with_source_digest(source_digest)
+        .with_completed_at(completed_at);
+    logs.insert_measurement(measurement.clone(), Metrics::M14);
+    Ok(Logging(measurement, logs))
+}
+
+async fn m15<D1>(
+    general_status_f: D1,
+    index: u64,
+    mut logs: Logs<Metrics, Tasks, Data>,
+) -> MeasurementResult
+where
+    D1: Future<Output = Result<GeneralStatusReading, Error>>,
+{
+    let res = general_status_f.await?;
+    let source_digest = res.source_digest.clone();
+    let completed_at = res.completed_at;
+    let data = Data::M15(res.status.packet_counters.incoming_packets.nack_ratio());
+    let measurement = Measurement::new(data, index)
+        .with_source_digest(source_digest)
+        .with_completed_at(completed_at);
+    logs.insert_measurement(measurement.clone(), Metrics::M15);
+    Ok(Logging(measurement, logs))
+}
+
+async fn m16<D1>(
+    nfd_status_f: D1,
+    index: u64,
+    mut logs: Logs<Metrics, Tasks, Data>,
+) -> MeasurementResult
+where
+    D1: Future<Output = Result<nfdc::NfdcStatus, Error>>,
+{
+    let res: nfdc::NfdcStatus = nfd_status_f.await?;
+    let source_digest = res.source_digest.clone();
+    let completed_at = res.completed_at;
+    let data = Data::M16(
+        res.faces
+            .face
+            .iter()
+            .filter_map(|f| f.marking_interval().map(|i| Ok((f.face_id, i?))))
+            .collect::<Result<_, command::Error>>()?,
+    );
+    let measurement = Measurement::new(data, index)
+        .with_source_digest(source_digest)
+        .with_completed_at(completed_at);
+    logs.insert_measurement(measurement.clone(), Metrics::M16);
+    Ok(Logging(measurement, logs))
+}
+
+async fn m17<D1>(
+    certificate_infos_f: D1,
+    index: u64,
+    mut logs: Logs<Metrics, Tasks, Data>,
+) -> MeasurementResult
+where
+    D1: Future<Output = Result<Vec<(String, ndnsec::dump::CertificateInfo)>, Error>>,
+{
+    let certificate_info = certificate_infos_f.await?;
+    let completed_at = dumps_completed_at(&certificate_info);
+    let data = Data::M17(
+        certificate_info
+            .into_iter()
+            .filter_map(|(i, d)| d.issuer_identity().map(|s| (i, s.to_string())))
+            .collect(),
+    );
+    let measurement = Measurement::new(data, index).with_completed_at(completed_at);
+    logs.insert_measurement(measurement.clone(), Metrics::M17);
+    Ok(Logging(measurement, logs))
+}
+
+async fn m32<D1>(
+    certificate_infos_f: D1,
+    index: u64,
+    mut logs: Logs<Metrics, Tasks, Data>,
+) -> MeasurementResult
+where
+    D1: Future<Output = Result<Vec<(String, ndnsec::dump::CertificateInfo)>, Error>>,
+{
+    let certificate_info = certificate_infos_f.await?;
+    let completed_at = dumps_completed_at(&certificate_info);
+    let issuers = certificate_info
+        .iter()
+        .filter_map(|(i, d)| d.issuer_identity().map(|s| (i.clone(), s.to_string())))
+        .collect::<HashMap<_, _>>();
+    let data = Data::M32(
+        certificate_info
+            .into_iter()
+            .map(|(i, _)| {
+                let depth = chain_depth(&i, &issuers) as u64;
+                (i, depth)
+            })
+            .collect(),
+    );
+    let measurement = Measurement::new(data, index).with_completed_at(completed_at);
+    logs.insert_measurement(measurement.clone(), Metrics::M32);
+    Ok(Logging(measurement, logs))
+}
+
+async fn m19<D1>(
+    nfd_status_f: D1,
+    index: u64,
+    mut logs: Logs<Metrics, Tasks, Data>,
+) -> MeasurementResult
+where
+    D1: Future<Output = Result<nfdc::NfdcStatus, Error>>,
+{
+    let res: nfdc::NfdcStatus = nfd_status_f.await?;
+    let source_digest = res.source_digest.clone();
+    let completed_at = res.completed_at;
+    let data = Data::M19(CsCounters {
+        start_time: res.general_status.start_time,
+        n_hits: res.cs.n_hits,
+        n_misses: res.cs.n_misses,
+    });
+    let measurement = Measurement::new(data, index)
+        .with_source_digest(source_digest)
+        .with_completed_at(completed_at);
+    logs.insert_measurement(measurement.clone(), Metrics::M19);
+    Ok(Logging(measurement, logs))
+}
+
+async fn m20<D1>(
+    certificate_list_f: D1,
+    index: u64,
+    mut logs: Logs<Metrics, Tasks, Data>,
+) -> MeasurementResult
+where
+    D1: Future<Output = Result<ndnsec::list::CertificateList, Error>>,
+{
+    let res: ndnsec::list::CertificateList = certificate_list_f.await?;
+    let completed_at = res.completed_at;
+    let identities = res
+        .certificates
+        .iter()
+        .map(|c| c.identity.as_str())
+        .collect::<HashSet<_>>();
+    let data = Data::M20(identities.len() as u64);
+    let measurement = Measurement::new(data, index).with_completed_at(completed_at);
+    logs.insert_measurement(measurement.clone(), Metrics::M20);
+    Ok(Logging(measurement, logs))
+}
+
+async fn m21<D1>(
+    nfd_status_f: D1,
+    index: u64,
+    mut logs: Logs<Metrics, Tasks, Data>,
+) -> MeasurementResult
+where
+    D1: Future<Output = Result<nfdc::NfdcStatus, Error>>,
+{
+    let res: nfdc::NfdcStatus = nfd_status_f.await?;
+    let source_digest = res.source_digest.clone();
+    let completed_at = res.completed_at;
+    let data = Data::M21(res.faces.face.len() as u64);
+    let measurement = Measurement::new(data, index)
+        .with_source_digest(source_digest)
+        .with_completed_at(completed_at);
+    logs.insert_measurement(measurement.clone(), Metrics::M21);
+    Ok(Logging(measurement, logs))
+}
+
+async fn m22<D1>(
+    general_status_f: D1,
+    index: u64,
+    mut logs: Logs<Metrics, Tasks, Data>,
+) -> MeasurementResult
+where
+    D1: Future<Output = Result<GeneralStatusReading, Error>>,
+{
+    let res = general_status_f.await?;
+    let source_digest = res.source_digest.clone();
+    let completed_at = res.completed_at;
+    let data = Data::M22(res.status.current_time_utc()?);
+    let measurement = Measurement::new(data, index)
+        .with_source_digest(source_digest)
+        .with_completed_at(completed_at);
+    logs.insert_measurement(measurement.clone(), Metrics::M22);
+    Ok(Logging(measurement, logs))
+}
+
+async fn m26<D1>(
+    nfd_status_f: D1,
+    index: u64,
+    mut logs: Logs<Metrics, Tasks, Data>,
+) -> MeasurementResult
+where
+    D1: Future<Output = Result<nfdc::NfdcStatus, Error>>,
+{
+    let res: nfdc::NfdcStatus = nfd_status_f.await?;
+    let source_digest = res.source_digest.clone();
+    let completed_at = res.completed_at;
+    let origins = res
+        .rib
+        .rib_entry
+        .iter()
+        .map(|entry| {
+            let origins = entry.routes.route.iter().map(|r| r.origin.clone());
+            (entry.prefix.clone(), origins.collect())
+        })
+        .collect();
+    let measurement = Measurement::new(Data::M26(origins), index)
+        .with_source_digest(source_digest)
+        .with_completed_at(completed_at);
+    logs.insert_measurement(measurement.clone(), Metrics::M26);
+    Ok(Logging(measurement, logs))
+}
+
+async fn m27<D1>(
+    nfd_status_f: D1,
+    index: u64,
+    mut logs: Logs<Metrics, Tasks, Data>,
+) -> MeasurementResult
+where
+    D1: Future<Output = Result<nfdc::NfdcStatus, Error>>,
+{
+    let res: nfdc::NfdcStatus = nfd_status_f.await?;
+    let source_digest = res.source_digest.clone();
+    let completed_at = res.completed_at;
+    let data = Data::M27(res.cs.usage_percent());
+    let measurement = Measurement::new(data, index)
+        .with_source_digest(source_digest)
+        .with_completed_at(completed_at);
+    logs.insert_measurement(measurement.clone(), Metrics::M27);
+    Ok(Logging(measurement, logs))
+}
+
+async fn m28<D1>(
+    nfd_status_f: D1,
+    policy: &Policy,
+    index: u64,
+    mut logs: Logs<Metrics, Tasks, Data>,
+) -> MeasurementResult
+where
+    D1: Future<Output = Result<nfdc::NfdcStatus, Error>>,
+{
+    let res: nfdc::NfdcStatus = nfd_status_f.await?;
+    let source_digest = res.source_digest.clone();
+    let completed_at = res.completed_at;
+    let strategies = policy
+        .required_strategies
+        .keys()
+        .map(|namespace| {
+            let strategy = res
+                .strategy_choices
+                .strategy_for(&namespace.as_str().into());
+            (namespace.clone(), strategy.map(|s| s.name.clone()))
+        })
+        .collect();
+    let measurement = Measurement::new(Data::M28(strategies), index)
+        .with_source_digest(source_digest)
+        .with_completed_at(completed_at);
+    logs.insert_measurement(measurement.clone(), Metrics::M28);
+    Ok(Logging(measurement, logs))
+}
+
+async fn m29<D1>(
+    nfd_status_f: D1,
+    index: u64,
+    mut logs: Logs<Metrics, Tasks, Data>,
+) -> MeasurementResult
+where
+    D1: Future<Output = Result<nfdc::NfdcStatus, Error>>,
+{
+    let res: nfdc::NfdcStatus = nfd_status_f.await?;
+    let source_digest = res.source_digest.clone();
+    let completed_at = res.completed_at;
+    let data = Data::M29(
+        res.cs.admit_enabled.is_some(),
+        res.cs.serve_enabled.is_some(),
+    );
+    let measurement = Measurement::new(data, index)
+        .with_source_digest(source_digest)
+        .with_completed_at(completed_at);
+    logs.insert_measurement(measurement.clone(), Metrics::M29);
+    Ok(Logging(measurement, logs))
+}
+
+async fn m31<D1>(
+    nfd_status_f: D1,
+    index: u64,
+    mut logs: Logs<Metrics, Tasks, Data>,
+) -> MeasurementResult
+where
+    D1: Future<Output = Result<nfdc::NfdcStatus, Error>>,
+{
+    let res: nfdc::NfdcStatus = nfd_status_f.await?;
+    let source_digest = res.source_digest.clone();
+    let completed_at = res.completed_at;
+    let measurement = Measurement::new(Data::M31(res.channels.schemes()), index)
+        .with_source_digest(source_digest)
+        .with_completed_at(completed_at);
+    logs.insert_measurement(measurement.clone(), Metrics::M31);
+    Ok(Logging(measurement, logs))
+}
+
+async fn m33<D1>(
+    nfd_status_f: D1,
+    index: u64,
+    mut logs: Logs<Metrics, Tasks, Data>,
+) -> MeasurementResult
+where
+    D1: Future<Output = Result<nfdc::NfdcStatus, Error>>,
+{
+    let res: nfdc::NfdcStatus = nfd_status_f.await?;
+    let source_digest = res.source_digest.clone();
+    let completed_at = res.completed_at;
+    let data = Data::M33(
+        res.faces
+            .face
+            .into_iter()
+            .filter_map(|f| f.mtu.map(|mtu| (f.face_id, (f.link_type, mtu))))
+            .collect(),
+    );
+    let measurement = Measurement::new(data, index)
+        .with_source_digest(source_digest)
+        .with_completed_at(completed_at);
+    logs.insert_measurement(measurement.clone(), Metrics::M33);
+    Ok(Logging(measurement, logs))
+}
+
+async fn m34<D1>(
+    nfd_status_f: D1,
+    index: u64,
+    mut logs: Logs<Metrics, Tasks, Data>,
+) -> MeasurementResult
+where
+    D1: Future<Output = Result<nfdc::NfdcStatus, Error>>,
+{
+    let res: nfdc::NfdcStatus = nfd_status_f.await?;
+    let source_digest = res.source_digest.clone();
+    let completed_at = res.completed_at;
+    let data = Data::M34(
+        res.faces
+            .face
+            .into_iter()
+            .filter(|f| f.flags.congestion_marking_enabled.is_some())
+            .map(|f| (f.face_id, f.congestion))
+            .collect(),
+    );
+    let measurement = Measurement::new(data, index)
+        .with_source_digest(source_digest)
+        .with_completed_at(completed_at);
+    logs.insert_measurement(measurement.clone(), Metrics::M34);
+    Ok(Logging(measurement, logs))
+}
+
+async fn m35<D1>(
+    general_status_f: D1,
+    now: DateTime<Utc>,
+    index: u64,
+    mut logs: Logs<Metrics, Tasks, Data>,
+) -> MeasurementResult
+where
+    D1: Future<Output = Result<GeneralStatusReading, Error>>,
+{
+    let res = general_status_f.await?;
+    let source_digest = res.source_digest.clone();
+    let completed_at = res.completed_at;
+    let mut unsatisfied = UnsatisfiedInterests {
+        start_time: res.status.start_time,
+        n_unsatisfied_interests: res.status.n_unsatisfied_interests,
+        rate: None,
+    };
+    let previous = logs
+        .measurements_timestamp
+        .get(&Metrics::M35)
+        .and_then(|entries| entries.back());
+    if let Some((timestamp, Data::M35(previous))) = previous {
+        unsatisfied.rate = unsatisfied.rate_since(previous, now - *timestamp);
+    }
+    let mut measurement = Measurement::new(Data::M35(unsatisfied), index)
+        .with_source_digest(source_digest)
+        .with_completed_at(completed_at);
+    measurement.timestamp = now;
+    logs.insert_measurement(measurement.clone(), Metrics::M35);
+    Ok(Logging(measurement, logs))
+}
+
+async fn m36<D1>(
+    nfd_status_f: D1,
+    index: u64,
+    mut logs: Logs<Metrics, Tasks, Data>,
+) -> MeasurementResult
+where
+    D1: Future<Output = Result<nfdc::NfdcStatus, Error>>,
+{
+    let res: nfdc::NfdcStatus = nfd_status_f.await?;
+    let source_digest = res.source_digest.clone();
+    let completed_at = res.completed_at;
+    let next_hops = res
+        .fib
+        .fib_entry
+        .into_iter()
+        .map(|entry| (entry.prefix, entry.next_hops.next_hop.len()))
+        .collect();
+    let measurement = Measurement::new(Data::M36(next_hops), index)
+        .with_source_digest(source_digest)
+        .with_completed_at(completed_at);
+    logs.insert_measurement(measurement.clone(), Metrics::M36);
+    Ok(Logging(measurement, logs))
+}
+
+async fn m18(index: u64, mut logs: Logs<Metrics, Tasks, Data>) -> MeasurementResult {
+    let data = Data::M18(logs.recent_durations(LATENCY_WINDOW));
+    let measurement = Measurement::new(data, index);
+    logs.insert_measurement(measurement.clone(), Metrics::M18);
+    Ok(Logging(measurement, logs))
+}
+
+async fn c1<M1>(m1: M1, policy: &Policy, index: u64) -> EvaluationResult
+where
+    M1: Future<Output = MeasurementResult>,
+{
+    let Logging(meas_m1, mut logs_m1) = m1.await?;
+    let value = match meas_m1.data {
+        Data::M1(cs_policy_name) => Ok(policy
+            .cs_policies
+            .iter()
+            .any(|allowed| allowed.trim().eq_ignore_ascii_case(cs_policy_name.trim()))),
+        _ => Err(Error::EvaluationError(
+            "Wrong dependency task provided".to_string(),
+        )),
+    }?;
+    let evaluation = Evaluation::new(value, index);
+    logs_m1.insert_evaluation(evaluation.clone(), Tasks::C1);
+    Ok(Logging(evaluation, logs_m1))
+}
+
+async fn c2<M2, M13>(m2: M2, m13: M13, policy: &Policy, index: u64) -> EvaluationResult
+where
+    M2: Future<Output = MeasurementResult>,
+    M13: Future<Output = MeasurementResult>,
+{
+    let (Logging(meas_m2, mut logs_m2), Logging(m13_measurement, logs_m13)) =
+        try_join(m2, m13).await?;
+    let value = match (meas_m2.data, m13_measurement.data) {
+        (Data::M2(cs_entries), Data::M13(total_memory)) => {
+            // Widened, as the sizes of the node and of the policy may overflow 64 bits
+            let available = (u128::from(total_memory) * 80 / 100)
+                .saturating_sub(u128::from(policy.memory_headroom.as_u64()));
+            Ok(available >= u128::from(cs_entries) * u128::from(policy.cs_entry_size.as_u64()))
+        }
+        _ => Err(Error::EvaluationError(
+            "Wrong dependency tasks provided".to_string(),
+        )),
+    }?;
+    let evaluation = Evaluation::new(value, index);
+    logs_m2
+        .mut_merge(&logs_m13)
+        .insert_evaluation(evaluation.clone(), Tasks::C2);
+    Ok(Logging(evaluation, logs_m2))
+}
+
+async fn c3<M2>(m2: M2, index: u64) -> EvaluationResult
+where
+    M2: Future<Output = MeasurementResult>,
+{
+    let Logging(meas_m2, mut logs_m2) = m2.await?;
+    let value = match meas_m2.data {
+        Data::M2(cs_entries) => Ok(cs_entries <= 100000),
+        _ => Err(Error::EvaluationError(
+            "Wrong dependency task provided".to_string(),
+        )),
+    }?;
+    let evaluation = Evaluation::new(value, index);
+    logs_m2.insert_evaluation(evaluation.clone(), Tasks::C3);
+    Ok(Logging(evaluation, logs_m2))
+}
+
+async fn c4<M2, M3>(m2: M2, m3: M3, index: u64) -> EvaluationResult
+where
+    M2: Future<Output = MeasurementResult>,
+    M3: Future<Output = MeasurementResult>,
+{
+    let (Logging(meas_m2, mut logs_m2), Logging(meas_m3, logs_m3)) = try_join(m2, m3).await?;
+    let value = match (meas_m2.data, meas_m3.data) {
+        (Data::M2(cs_entries), Data::M3(cs_usage)) if cs_usage >= cs_entries * 80 / 100 => Ok(true),
+        (Data::M2(_), Data::M3(_)) => Ok(false),
+        _ => Err(Error::EvaluationError(
+            "Wrong dependency tasks provided".to_string(),
+        )),
+    }?;
+    let evaluation = Evaluation::new(value, index);
+    logs_m2
+        .mut_merge(&logs_m3)
+        .insert_evaluation(evaluation.clone(), Tasks::C4);
+    Ok(Logging(evaluation, logs_m2))
+}
+
+/// Whether the CS holds no more entries than its capacity, undecided without capacity
+async fn c29<M27>(m27: M27, index: u64) -> EvaluationResult
+where
+    M27: Future<Output = MeasurementResult>,
+{
+    let Logging(meas_m27, mut logs_m27) = m27.await?;
+    let value = match meas_m27.data {
+        Data::M27(usage) => Ok(usage.map(|usage| usage <= 100.0)),
+        _ => Err(Error::EvaluationError(
+            "Wrong dependency task provided".to_string(),
+        )),
+    }?;
+    let evaluation = Evaluation::new(value, index);
+    logs_m27.insert_evaluation(evaluation.clone(), Tasks::C29);
+    Ok(Logging(evaluation, logs_m27))
+}
+
+/// Whether every namespace is forwarded with the strategy the policy requires for it
+async fn c30<M28>(m28: M28, policy: &Policy, index: u64) -> EvaluationResult
+where
+    M28: Future<Output = MeasurementResult>,
+{
+    let Logging(meas_m28, mut logs_m28) = m28.await?;
+    let value = match meas_m28.data {
+        Data::M28(strategies) => {
+            Ok(policy
+                .required_strategies
+                .iter()
+                .all(|(namespace, required)| {
+                    strategies
+                        .get(namespace)
+                        .and_then(Option::as_ref)
+                        .is_some_and(|name| nfdc::Strategy { name: name.clone() }.is(required))
+                }))
+        }
+        _ => Err(Error::EvaluationError(
+            "Wrong dependency task provided".to_string(),
+        )),
+    }?;
+    let evaluation = Evaluation::new(value, index);
+    logs_m28.insert_evaluation(evaluation.clone(), Tasks::C30);
+    Ok(Logging(evaluation, logs_m28))
+}
+
+/// Whether the CS both admits and serves Data packets
+async fn c31<M29>(m29: M29, index: u64) -> EvaluationResult
+where
+    M29: Future<Output = MeasurementResult>,
+{
+    let Logging(meas_m29, mut logs_m29) = m29.await?;
+    let value = match meas_m29.data {
+        Data::M29(admit, serve) => Ok(admit && serve),
+        _ => Err(Error::EvaluationError(
+            "Wrong dependency task provided".to_string(),
+        )),
+    }?;
+    let evaluation = Evaluation::new(value, index);
+    logs_m29.insert_evaluation(evaluation.clone(), Tasks::C31);
+    Ok(Logging(evaluation, logs_m29))
+}
+
+/// Whether the resident memory of NFD is within the policy bound
+///
+/// Undecided without an `nfd` process, as there is no memory to bound.
+async fn c32<M30>(m30: M30, policy: &Policy, index: u64) -> EvaluationResult
+where
+    M30: Future<Output = MeasurementResult>,
+{
+    let Logging(meas_m30, mut logs_m30) = m30.await?;
+    let value = match meas_m30.data {
+        Data::M30(memory) => Ok(memory.map(|memory| memory <= policy.max_nfd_memory.as_u64())),
+        _ => Err(Error::EvaluationError(
+            "Wrong dependency task provided".to_string(),
+        )),
+    }?;
+    let evaluation = Evaluation::new(value, index);
+    logs_m30.insert_evaluation(evaluation.clone(), Tasks::C32);
+    Ok(Logging(evaluation, logs_m30))
+}
+
+/// Whether NFD listens on a channel of every transport scheme the policy requires
+async fn c33<M31>(m31: M31, policy: &Policy, index: u64) -> EvaluationResult
+where
+    M31: Future<Output = MeasurementResult>,
+{
+    let Logging(meas_m31, mut logs_m31) = m31.await?;
+    let value = match meas_m31.data {
+        Data::M31(schemes) => Ok(policy
+            .required_channel_schemes
+            .iter()
+            .all(|scheme| schemes.contains(scheme))),
+        _ => Err(Error::EvaluationError(
+            "Wrong dependency task provided".to_string(),
+        )),
+    }?;
+    let evaluation = Evaluation::new(value, index);
+    logs_m31.insert_evaluation(evaluation.clone(), Tasks::C33);
+    Ok(Logging(evaluation, logs_m31))
+}
+
+/// Whether every face has the MTU the policy expects for its link type
+async fn c35<M33>(m33: M33, policy: &Policy, index: u64) -> EvaluationResult
+where
+    M33: Future<Output = MeasurementResult>,
+{
+    let Logging(meas_m33, mut logs_m33) = m33.await?;
+    let value = match meas_m33.data {
+        Data::M33(faces) => Ok(faces.values().all(|(link_type, mtu)| {
+            policy
+                .expected_mtus
+                .get(link_type)
+                .is_none_or(|expected| expected == mtu)
+        })),
+        _ => Err(Error::EvaluationError(
+            "Wrong dependency task provided".to_string(),
+        )),
+    }?;
+    let evaluation = Evaluation::new(value, index);
+    logs_m33.insert_evaluation(evaluation.clone(), Tasks::C35);
+    Ok(Logging(evaluation, logs_m33))
+}
+
+async fn c5<M3>(m3: M3, policy: &Policy, index: u64) -> EvaluationResult
+where
+    M3: Future<Output = MeasurementResult>,
+{
+    let Logging(meas_m3, mut logs_m3) = m3.await?;
+
+    let value = match meas_m3.data {
+        Data::M3(_) => Ok(is_stable(&logs_m3, &Metrics::M3, index, policy)),
+        _ => Err(Error::EvaluationError(
+            "Wrong dependency tasks provided".to_string(),
+        )),
+    }?;
+    let evaluation = Evaluation::new(value, index);
+    logs_m3.insert_evaluation(evaluation.clone(), Tasks::C5);
+    Ok(Logging(evaluation, logs_m3))
+}
+
+async fn c6<M4>(m4: M4, index: u64) -> EvaluationResult
+where
+    M4: Future<Output = MeasurementResult>,
+{
+    let Logging(meas_m4, mut logs_m4) = m4.await?;
+    let value = match meas_m4.data {
+        Data::M4(v) => Ok(v.std_dev <= 5.0_f64),
+        _ => Err(Error::EvaluationError(
+            "Wrong dependency task provided".to_string(),
+        )),
+    }?;
+    let evaluation = Evaluation::new(value, index);
+    logs_m4.insert_evaluation(evaluation.clone(), Tasks::C6);
+    Ok(Logging(evaluation, logs_m4))
+}
+
+async fn c7<M4>(m4: M4, index: u64) -> EvaluationResult
+where
+    M4: Future<Output = MeasurementResult>,
+{
+    let Logging(meas_m4, mut logs_m4) = m4.await?;
+    let value = match meas_m4.data {
+        Data::M4(v) => Ok(v.avg >= 20_f64),
+        _ => Err(Error::EvaluationError(
+            "Wrong dependency task provided".to_string(),
+        )),
+    }?;
+    let evaluation = Evaluation::new(value, index);
+    logs_m4.insert_evaluation(evaluation.clone(), Tasks::C7);
+    Ok(Logging(evaluation, logs_m4))
+}
+
+/// Whether no face has many unanswered Interests, logging the labels of the faces along
+async fn c8<M6, M37>(m6: M6, m37: M37, index: u64) -> EvaluationResult
+where
+    M6: Future<Output = MeasurementResult>,
+    M37: Future<Output = MeasurementResult>,
+{
+    let (Logging(meas_m6, mut logs_m6), Logging(_, logs_m37)) = try_join(m6, m37).await?;
+    let value = match meas_m6.data {
+        // Without faces there is nothing to check, see C27
+        Data::M6(v) => Ok((!v.is_empty()).then(|| v.values().all(|v| *v < 100))),
+        _ => Err(Error::EvaluationError(
+            "Wrong dependency task provided".to_string(),
+        )),
+    }?;
+    let evaluation = Evaluation::new(value, index);
+    logs_m6
+        .mut_merge(&logs_m37)
+        .insert_evaluation(evaluation.clone(), Tasks::C8);
+    Ok(Logging(evaluation, logs_m6))
+}
+
+async fn c9<M7>(m7: M7, policy: &Policy, now: DateTime<Utc>, index: u64) -> EvaluationResult
+where
+    M7: Future<Output = MeasurementResult>,
+{
+    let Logging(meas_m7, mut logs_m7) = m7.await?;
+    let value = match meas_m7.data {
+        Data::M7(v) => {
+            Ok((!v.is_empty()).then(|| packet_sizes_hold(&v, &Metrics::M7, &logs_m7, policy, now)))
+        }
+        _ => Err(Error::EvaluationError(
+            "Wrong dependency task provided".to_string(),
+        )),
+    }?;
+    let evaluation = Evaluation::new(value, index);
+    logs_m7.insert_evaluation(evaluation.clone(), Tasks::C9);
+    Ok(Logging(evaluation, logs_m7))
+}
+
+async fn c10<M9>(m9: M9, policy: &Policy, index: u64) -> EvaluationResult
+where
+    M9: Future<Output = MeasurementResult>,
+{
+    let Logging(meas_m9, mut logs_m9) = m9.await?;
+    let value = match meas_m9.data {
+        Data::M9(v) => Ok((!v.is_empty()).then(|| {
+            v.values().filter(|s| !s.avg.is_nan()).all(|s| {
+                in_range(
+                    s.avg,
+                    policy.min_name_components,
+                    policy.max_name_components,
+                    false,
+                )
+            })
+        })),
+        _ => Err(Error::EvaluationError(
+            "Wrong dependency task provided".to_string(),
+        )),
+    }?;
+    let evaluation = Evaluation::new(value, index);
+    logs_m9.insert_evaluation(evaluation.clone(), Tasks::C10);
+    Ok(Logging(evaluation, logs_m9))
+}
+
+async fn c11<M8>(m8: M8, policy: &Policy, now: DateTime<Utc>, index: u64) -> EvaluationResult
+where
+    M8: Future<Output = MeasurementResult>,
+{
+    let Logging(meas_m8, mut logs_m8) = m8.await?;
+    let value = match meas_m8.data {
+        Data::M8(v) => {
+            Ok((!v.is_empty()).then(|| packet_sizes_hold(&v, &Metrics::M8, &logs_m8, policy, now)))
+        }
+        _ => Err(Error::EvaluationError(
+            "Wrong dependency task provided".to_string(),
+        )),
+    }?;
+    let evaluation = Evaluation::new(value, index);
+    logs_m8.insert_evaluation(evaluation.clone(), Tasks::C11);
+    Ok(Logging(evaluation, logs_m8))
+}
+
+async fn c12<M10>(m10: M10, policy: &Policy, index: u64) -> EvaluationResult
+where
+    M10: Future<Output = MeasurementResult>,
+{
+    let Logging(meas_m10, mut logs_m10) = m10.await?;
+    let value = match meas_m10.data {
+        Data::M10(v) => Ok((!v.is_empty()).then(|| {
+            v.values().filter(|s| !s.avg.is_nan()).all(|s| {
+                in_range(
+                    s.avg,
+                    policy.min_name_components,
+                    policy.max_name_components,
+                    false,
+                )
+            })
+        })),
+        _ => Err(Error::EvaluationError(
+            "Wrong dependency task provided".to_string(),
+        )),
+    }?;
+    let evaluation = Evaluation::new(value, index);
+    logs_m10.insert_evaluation(evaluation.clone(), Tasks::C12);
+    Ok(Logging(evaluation, logs_m10))
+}
+
+async fn c13<M11>(m11: M11, now: DateTime<Utc>, index: u64) -> EvaluationResult
+where
+    M11: Future<Output = MeasurementResult>,
+{
+    let Logging(meas_m11, mut logs_m11) = m11.await?;
+    let value = match meas_m11.data {
+        Data::M11(v) => Ok(v.values().all(|s| s.0 < now && now < s.1)),
+        _ => Err(Error::EvaluationError(
+            "Wrong dependency task provided".to_string(),
+        )),
+    }?;
+    let evaluation = Evaluation::new(value, index);
+    logs_m11.insert_evaluation(evaluation.clone(), Tasks::C13);
+    Ok(Logging(evaluation, logs_m11))
+}
+
+async fn c14<M12>(m12: M12, index: u64) -> EvaluationResult
+where
+    M12: Future<Output = MeasurementResult>,
+{
+    let Logging(meas_m12, mut logs_m12) = m12.await?;
+    let value = match meas_m12.data {
+        Data::M12(v) => Ok(v.is_some()),
+        _ => Err(Error::EvaluationError(
+            "Wrong dependency task provided".to_string(),
+        )),
+    }?;
+    let evaluation = Evaluation::new(value, index);
+    logs_m12.insert_evaluation(evaluation.clone(), Tasks::C14);
+    Ok(Logging(evaluation, logs_m12))
+}
+
+/// Whether the default identity has a default key whose default certificate is valid now
+async fn c25<M24>(m24: M24, now: DateTime<Utc>, index: u64) -> EvaluationResult
+where
+    M24: Future<Output = MeasurementResult>,
+{
+    let Logging(meas_m24, mut logs_m24) = m24.await?;
+    let value = match meas_m24.data {
+        Data::M24(validity) => Ok(validity
+            .is_some_and(|(not_before, not_after)| in_range(now, not_before, not_after, true))),
+        _ => Err(Error::EvaluationError(
+            "Wrong dependency task provided".to_string(),
+        )),
+    }?;
+    let evaluation = Evaluation::new(value, index);
+    logs_m24.insert_evaluation(evaluation.clone(), Tasks::C25);
+    Ok(Logging(evaluation, logs_m24))
+}
+
+/// Whether the routes of the protected prefixes all have an allowed origin
+async fn c28<M26>(m26: M26, policy: &Policy, index: u64) -> EvaluationResult
+where
+    M26: Future<Output = MeasurementResult>,
+{
+    let Logging(meas_m26, mut logs_m26) = m26.await?;
+    let value = match meas_m26.data {
+        Data::M26(origins) => Ok(origins
+            .iter()
+            .filter(|(prefix, _)| policy.protects_prefix(prefix))
+            .flat_map(|(_, origins)| origins)
+            .all(|origin| policy.allows_route_origin(origin))),
+        _ => Err(Error::EvaluationError(
+            "Wrong dependency task provided".to_string(),
+        )),
+    }?;
+    let evaluation = Evaluation::new(value, index);
+    logs_m26.insert_evaluation(evaluation.clone(), Tasks::C28);
+    Ok(Logging(evaluation, logs_m26))
+}
+
+async fn c15<M14>(m14: M14, index: u64) -> EvaluationResult
+where
+    M14: Future<Output = MeasurementResult>,
+{
+    let Logging(meas_14, mut logs_m14) = m14.await?;
+    let value = match meas_14.data {
+        Data::M14(_valid, invalid) => Ok(invalid == 0),
+        _ => Err(Error::EvaluationError(
+            "Wrong dependency task provided".to_string(),
+        )),
+    }?;
+    let evaluation = Evaluation::new(value, index);
+    logs_m14.insert_evaluation(evaluation.clone(), Tasks::C15);
+    Ok(Logging(evaluation, logs_m14))
+}
+
+async fn c16<M15>(m15: M15, policy: &Policy, index: u64) -> EvaluationResult
+where
+    M15: Future<Output = MeasurementResult>,
+{
+    let Logging(meas_m15, mut logs_m15) = m15.await?;
+    let value = match meas_m15.data {
+        Data::M15(nack_ratio) => Ok(nack_ratio <= policy.max_nack_ratio),
+        _ => Err(Error::EvaluationError(
+            "Wrong dependency task provided".to_string(),
+        )),
+    }?;
+    let evaluation = Evaluation::new(value, index);
+    logs_m15.insert_evaluation(evaluation.clone(), Tasks::C16);
+    Ok(Logging(evaluation, logs_m15))
+}
+
+/// Properties decide as soon as each of their rules has reached a verdict
+const PROPERTY_MIN_SAMPLES: usize = 1;
+
+async fn c17<M16>(m16: M16, policy: &Policy, index: u64) -> EvaluationResult
+where
+    M16: Future<Output = MeasurementResult>,
+{
+    let Logging(meas_m16, mut logs_m16) = m16.await?;
+    let range = Duration::from_millis(policy.min_marking_interval_ms)
+        ..=Duration::from_millis(policy.max_marking_interval_ms);
+    let value = match meas_m16.data {
+        Data::M16(v) => Ok(v.values().all(|i| range.contains(i))),
+        _ => Err(Error::EvaluationError(
+            "Wrong dependency task provided".to_string(),
+        )),
+    }?;
+    let evaluation = Evaluation::new(value, index);
+    logs_m16.insert_evaluation(evaluation.clone(), Tasks::C17);
+    Ok(Logging(evaluation, logs_m16))
+}
+
+/// Whether the default congestion threshold of every marked face is within the policy bounds
+///
+/// The report holds no forwarder-wide threshold, so the faces without congestion settings are
+/// not checked.
+async fn c36<M34>(m34: M34, policy: &Policy, index: u64) -> EvaluationResult
+where
+    M34: Future<Output = MeasurementResult>,
+{
+    let Logging(meas_m34, mut logs_m34) = m34.await?;
+    let range = policy.min_congestion_threshold.as_u64()..=policy.max_congestion_threshold.as_u64();
+    let value = match meas_m34.data {
+        Data::M34(faces) => Ok(faces.values().all(|congestion| {
+            congestion
+                .default_threshold
+                .is_none_or(|threshold| range.contains(&threshold))
+        })),
+        _ => Err(Error::EvaluationError(
+            "Wrong dependency task provided".to_string(),
+        )),
+    }?;
+    let evaluation = Evaluation::new(value, index);
+    logs_m34.insert_evaluation(evaluation.clone(), Tasks::C36);
+    Ok(Logging(evaluation, logs_m34))
+}
+
+/// Whether NFD fails to satisfy interests at most at the rate the policy allows, undecided
+/// without a previous reading of the counter to compare
+async fn c37<M35>(m35: M35, policy: &Policy, index: u64) -> EvaluationResult
+where
+    M35: Future<Output = MeasurementResult>,
+{
+    let Logging(meas_m35, mut logs_m35) = m35.await?;
+    let value = match meas_m35.data {
+        Data::M35(unsatisfied) => Ok(unsatisfied
+            .rate
+            .map(|rate| rate <= policy.max_unsatisfied_interest_rate)),
+        _ => Err(Error::EvaluationError(
+            "Wrong dependency task provided".to_string(),
+        )),
+    }?;
+    let evaluation = Evaluation::new(value, index);
+    logs_m35.insert_evaluation(evaluation.clone(), Tasks::C37);
+    Ok(Logging(evaluation, logs_m35))
+}
+
+/// Whether the FIB has a next hop for every management prefix the policy requires, compared
+/// regardless of their encoding
+async fn c38<M36>(m36: M36, policy: &Policy, index: u64) -> EvaluationResult
+where
+    M36: Future<Output = MeasurementResult>,
+{
+    let Logging(meas_m36, mut logs_m36) = m36.await?;
+    let value = match meas_m36.data {
+        Data::M36(next_hops) => Ok(policy.required_fib_prefixes.iter().all(|required| {
+            next_hops
+                .iter()
+                .any(|(prefix, n)| nfdc::name_eq(prefix, required) && *n > 0)
+        })),
+        _ => Err(Error::EvaluationError(
+            "Wrong dependency task provided".to_string(),
+        )),
+    }?;
+    let evaluation = Evaluation::new(value, index);
+    logs_m36.insert_evaluation(evaluation.clone(), Tasks::C38);
+    Ok(Logging(evaluation, logs_m36))
+}
+
+async fn c18<M17>(m17: M17, policy: &Policy, index: u64) -> EvaluationResult
+where
+    M17: Future<Output = MeasurementResult>,
+{
+    let Logging(meas_m17, mut logs_m17) = m17.await?;
+    let value = match meas_m17.data {
+        _ if policy.trust_anchors.is_empty() => Ok(true),
+        Data::M17(issuers) => Ok(issuers
+            .keys()
+            .all(|identity| chains_to_anchor(identity, &issuers, &policy.trust_anchors))),
+        _ => Err(Error::EvaluationError(
+            "Wrong dependency task provided".to_string(),
+        )),
+    }?;
+    let evaluation = Evaluation::new(value, index);
+    logs_m17.insert_evaluation(evaluation.clone(), Tasks::C18);
+    Ok(Logging(evaluation, logs_m17))
+}
+
+/// Whether the signing chain of every certificate is at most as deep as the policy allows
+async fn c34<M32>(m32: M32, policy: &Policy, index: u64) -> EvaluationResult
+where
+    M32: Future<Output = MeasurementResult>,
+{
+    let Logging(meas_m32, mut logs_m32) = m32.await?;
+    let value = match meas_m32.data {
+        Data::M32(depths) => Ok(depths
+            .values()
+            .all(|depth| *depth <= policy.max_chain_depth)),
+        _ => Err(Error::EvaluationError(
+            "Wrong dependency task provided".to_string(),
+        )),
+    }?;
+    let evaluation = Evaluation::new(value, index);
+    logs_m32.insert_evaluation(evaluation.clone(), Tasks::C34);
+    Ok(Logging(evaluation, logs_m32))
+}
+
+/// Whether every content certificate key uses an approved algorithm and size
+async fn c24<M23>(m23: M23, policy: &Policy, index: u64) -> EvaluationResult
+where
+    M23: Future<Output = MeasurementResult>,
+{
+    let Logging(meas_m23, mut logs_m23) = m23.await?;
+    let value = match meas_m23.data {
+        Data::M23(keys) => Ok(keys.values().all(|key| policy.approves_key(key))),
+        _ => Err(Error::EvaluationError(
+            "Wrong dependency task provided".to_string(),
+        )),
+    }?;
+    let evaluation = Evaluation::new(value, index);
+    logs_m23.insert_evaluation(evaluation.clone(), Tasks::C24);
+    Ok(Logging(evaluation, logs_m23))
+}
+
+async fn c19<M18>(m18: M18, policy: &Policy, index: u64) -> EvaluationResult
+where
+    M18: Future<Output = MeasurementResult>,
+{
+    let Logging(meas_m18, mut logs_m18) = m18.await?;
+    let interval = Duration::from_millis(policy.tick_interval_ms).as_nanos() as f64;
+    let value = match meas_m18.data {
+        Data::M18(durations) => Ok(median(&durations).map(|median| median <= interval)),
+        _ => Err(Error::EvaluationError(
+            "Wrong dependency task provided".to_string(),
+        )),
+    }?;
+    let evaluation = Evaluation::new(value, index);
+    logs_m18.insert_evaluation(evaluation.clone(), Tasks::C19);
+    Ok(Logging(evaluation, logs_m18))
+}
+
+/// Whether the CS serves the interests reaching it within the window
+///
+/// Hits and lookups are summed over consecutive samples, leaving out counter resets. Without
+/// lookups there is nothing to serve, while without comparable samples the constraint is
+/// undecided.
+async fn c20<M19>(m19: M19, policy: &Policy, now: DateTime<Utc>, index: u64) -> EvaluationResult
+where
+    M19: Future<Output = MeasurementResult>,
+{
+    let Logging(meas_m19, mut logs_m19) = m19.await?;
+    if !matches!(meas_m19.data, Data::M19(_)) {
+        return Err(Error::EvaluationError(
+            "Wrong dependency task provided".to_string(),
+        ));
+    }
+    let since = now - chrono::Duration::minutes(2);
+    let samples = logs_m19
+        .measurements_timestamp
+        .get(&Metrics::M19)
+        .map(|entries| {
+            entries
+                .iter()
+                .filter(|(timestamp, _)| *timestamp >= since)
+                .filter_map(|(_, data)| match data {
+                    Data::M19(counters) => Some(counters.clone()),
+                    _ => None,
+                })
+                .collect::<Vec<_>>()
+        })
+        .unwrap_or_default();
+    let value = match CsCounters::windowed(&samples) {
+        None => None,
+        Some((_, 0)) => Some(true),
+        Some((hits, lookups)) => {
+            Some(hits > 0 && hits as f64 / lookups as f64 >= policy.min_cs_hit_ratio)
+        }
+    };
+    let evaluation = Evaluation::new(value, index);
+    logs_m19.insert_evaluation(evaluation.clone(), Tasks::C20);
+    Ok(Logging(evaluation, logs_m19))
+}
+
+async fn c21<M20>(m20: M20, policy: &Policy, index: u64) -> EvaluationResult
+where
+    M20: Future<Output = MeasurementResult>,
+{
+    let Logging(meas_m20, mut logs_m20) = m20.await?;
+    let value = match meas_m20.data {
+        Data::M20(identities) => Ok(in_range(
+            identities,
+            policy.min_identities,
+            policy.max_identities,
+            true,
+        )),
+        _ => Err(Error::EvaluationError(
+            "Wrong dependency task provided".to_string(),
+        )),
+    }?;
+    let evaluation = Evaluation::new(value, index);
+    logs_m20.insert_evaluation(evaluation.clone(), Tasks::C21);
+    Ok(Logging(evaluation, logs_m20))
+}
+
+/// Skip a constraint evaluated on a measurement that could not be parsed, see [`leniently`],
+/// and apply the NaN handling of the policy to the measurements holding NaN values
+async fn screen_measurements<M>(
+    constraint: BoxFuture<'_, EvaluationResult>,
+    measurements: Vec<M>,
+    task: Tasks,
+    index: u64,
+    nan_handling: NanHandling,
+) -> EvaluationResult
+where
+    M: Future<Output = MeasurementResult>,
+{
+    for measurement in measurements {
+        let (evaluation, mut logs) = match measurement.await {
+            Ok(Logging(
+                Measurement {
+                    data: Data::ParseError(_),
+                    ..
+                },
+                logs,
+            )) => (Evaluation::skipped(index), logs),
+            Ok(Logging(measurement, logs)) if measurement.data.has_nan() => match nan_handling {
+                NanHandling::Evaluate => continue,
+                NanHandling::Skip => (Evaluation::skipped(index), logs),
+                NanHandling::Fail => (Evaluation::new(false, index), logs),
+                NanHandling::Error => {
+                    return Err(Error::EvaluationError(format!(
+                        "NaN in a measurement of {:?}",
+                        task
+                    )))
+                }
+            },
+            _ => continue,
+        };
+        logs.insert_evaluation(evaluation.clone(), task);
+        return Ok(Logging(evaluation, logs));
+    }
+    constraint.await
+}
+
+/// Whether the share of ticks with parse errors within the window is acceptable
+async fn c26<M25>(m25: M25, policy: &Policy, now: DateTime<Utc>, index: u64) -> EvaluationResult
+where
+    M25: Future<Output = MeasurementResult>,
+{
+    let Logging(meas_m25, mut logs_m25) = m25.await?;
+    if !matches!(meas_m25.data, Data::M25(_)) {
+        return Err(Error::EvaluationError(
+            "Wrong dependency task provided".to_string(),
+        ));
+    }
+    let since = now - chrono::Duration::minutes(2);
+    let ticks = logs_m25
+        .measurements_timestamp
+        .get(&Metrics::M25)
+        .map(|entries| {
+            entries
+                .iter()
+                .filter(|(timestamp, _)| *timestamp >= since)
+                .filter_map(|(_, data)| match data {
+                    Data::M25(parse_errors) => Some(*parse_errors),
+                    _ => None,
+                })
+                .collect::<Vec<_>>()
+        })
+        .unwrap_or_default();
+    let failing = ticks
+        .iter()
+        .filter(|parse_errors| **parse_errors > 0)
+        .count();
+    let value = match ticks.len() {
+        0 => None,
+        n => Some(failing as f64 / n as f64 <= policy.max_parse_error_rate),
+    };
+    let evaluation = Evaluation::new(value, index);
+    logs_m25.insert_evaluation(evaluation.clone(), Tasks::C26);
+    Ok(Logging(evaluation, logs_m25))
+}
+
+/// Whether the forwarder has faces, a node without any being unable to forward
+async fn c27<M21>(m21: M21, index: u64) -> EvaluationResult
+where
+    M21: Future<Output = MeasurementResult>,
+{
+    let Logging(meas_m21, mut logs_m21) = m21.await?;
+    let value = match meas_m21.data {
+        Data::M21(count) => Ok(count > 0),
+        _ => Err(Error::EvaluationError(
+            "Wrong dependency task provided".to_string(),
+        )),
+    }?;
+    let evaluation = Evaluation::new(value, index);
+    logs_m21.insert_evaluation(evaluation.clone(), Tasks::C27);
+    Ok(Logging(evaluation, logs_m21))
+}
+
+/// Whether the face count is free of leaks, growing steadily within the window
+async fn c22<M21>(m21: M21, policy: &Policy, now: DateTime<Utc>, index: u64) -> EvaluationResult
+where
+    M21: Future<Output = MeasurementResult>,
+{
+    let Logging(meas_m21, mut logs_m21) = m21.await?;
+    if !matches!(meas_m21.data, Data::M21(_)) {
+        return Err(Error::EvaluationError(
+            "Wrong dependency task provided".to_string(),
+        ));
+    }
+    let since = now - chrono::Duration::minutes(2);
+    let counts = logs_m21
+        .measurements_timestamp
+        .get(&Metrics::M21)
+        .map(|entries| {
+            entries
+                .iter()
+                .filter(|(timestamp, _)| *timestamp >= since)
+                .filter_map(|(_, data)| match data {
+                    Data::M21(count) => Some(*count),
+                    _ => None,
+                })
+                .collect::<Vec<_>>()
+        })
+        .unwrap_or_default();
+    let value = if counts.len() < TREND_MIN_SAMPLES {
+        None
+    } else {
+        Some(!sustained_growth(&counts, policy.max_face_growth))
+    };
+    let evaluation = Evaluation::new(value, index);
+    logs_m21.insert_evaluation(evaluation.clone(), Tasks::C22);
+    Ok(Logging(evaluation, logs_m21))
+}
+
+/// Whether the NFD clock advances, a forwarder reporting the same time for more than
+/// `max_stale_ticks` consecutive ticks being hung even if `nfdc` still answers
+async fn c23<M22>(m22: M22, policy: &Policy, index: u64) -> EvaluationResult
+where
+    M22: Future<Output = MeasurementResult>,
+{
+    let Logging(meas_m22, mut logs_m22) = m22.await?;
+    if !matches!(meas_m22.data, Data::M22(_)) {
+        return Err(Error::EvaluationError(
+            "Wrong dependency task provided".to_string(),
+        ));
+    }
+    let times = logs_m22
+        .measurements_index
+        .get(&Metrics::M22)
+        .map(|entries| {
+            entries
+                .iter()
+                .filter_map(|(_, data)| match data {
+                    Data::M22(time) => Some(*time),
+                    _ => None,
+                })
+                .collect::<Vec<_>>()
+        })
+        .unwrap_or_default();
+    let value = trailing_repeats(&times) <= policy.max_stale_ticks;
+    let evaluation = Evaluation::new(value, index);
+    logs_m22.insert_evaluation(evaluation.clone(), Tasks::C23);
+    Ok(Logging(evaluation, logs_m22))
+}
+
+/// Value of a windowed rule or property, withheld while the agent warms up
+fn warmed_up(value: Option<bool>, policy: &Policy, index: u64) -> Option<bool> {
+    value.filter(|_| index >= policy.warm_up_ticks)
+}
+
+/// Logs of the evaluations of some dependencies merged together
+fn merged_logs(
+    dependencies: Vec<Logging<Evaluation, Metrics, Tasks, Data>>,
+) -> Logs<Metrics, Tasks, Data> {
+    let mut dependencies = dependencies.into_iter().map(|Logging(_, logs)| logs);
+    let mut logs = dependencies.next().unwrap_or_default();
+    for other in dependencies {
+        logs.mut_merge(&other);
+    }
+    logs
+}
+
+/// Whether the constraints of R1 hold in the tick, regardless of the previous ones
+async fn r1(constraints: Vec<BoxFuture<'_, EvaluationResult>>, index: u64) -> EvaluationResult {
+    let constraints = try_join_all(constraints).await?;
+    let values = Tasks::R1
+        .dependencies()
+        .iter()
+        .zip(constraints.iter())
+        .map(|(task, Logging(evaluation, _))| (task.clone(), evaluation.value))
+        .collect::<HashMap<_, _>>();
+    let (value, failed) = all_of_tasks(Tasks::R1.dependencies(), |t| values[t]);
+    let evaluation = Evaluation::new(value, index).with_failed_dependencies(&failed);
+    let mut logs = merged_logs(constraints);
+    logs.insert_evaluation(evaluation.clone(), Tasks::R1);
+    Ok(Logging(evaluation, logs))
+}
+
+/// Whether each constraint of `rule`, see [`Tasks::dependencies`], held within the two minutes
+/// before `now`, as aggregated by the policy
+///
+/// `constraints` are the evaluations of the constraints in the order of the dependencies.
+async fn windowed_rule(
+    rule: Tasks,
+    constraints: Vec<BoxFuture<'_, EvaluationResult>>,
+    policy: &Policy,
+    now: DateTime<Utc>,
+    index: u64,
+) -> EvaluationResult {
+    let mut logs = merged_logs(try_join_all(constraints).await?);
+    let duration = chrono::Duration::minutes(-2);
+    let (value, failed) = all_of_tasks(rule.dependencies(), |t| {
+        logs.holds_since(
+            t,
+            now + duration,
+            policy.min_samples,
+            policy.aggregation(&rule),
+        )
+    });
+    let evaluation =
+        Evaluation::new(warmed_up(value, policy, index), index).with_failed_dependencies(&failed);
+    logs.insert_evaluation(evaluation.clone(), rule);
+    Ok(Logging(evaluation, logs))
+}
+
+/// Whether the constraints of R10 changed value at most as often as the policy allows within
+/// the two minutes before `now`
+async fn r10(
+    constraints: Vec<BoxFuture<'_, EvaluationResult>>,
+    policy: &Policy,
+    now: DateTime<Utc>,
+    index: u64,
+) -> EvaluationResult {
+    let mut logs = merged_logs(try_join_all(constraints).await?);
+    let duration = chrono::Duration::minutes(-2);
+    let tasks = Tasks::R10.dependencies();
+    let enough_samples = tasks
+        .iter()
+        .all(|t| logs.samples_since(t, now + duration) >= policy.min_samples);
+    let value = tasks
+        .iter()
+        .all(|t| logs.transitions_since(t, now + duration) <= policy.max_transitions);
+    let value = Some(value).filter(|_| enough_samples);
+    let evaluation = Evaluation::new(warmed_up(value, policy, index), index);
+    logs.insert_evaluation(evaluation.clone(), Tasks::R10);
+    Ok(Logging(evaluation, logs))
+}
+
+/// Whether each rule of `property`, see [`Tasks::dependencies`], held within the two minutes
+/// before `now`, as aggregated by the policy
+///
+/// `rules` are the evaluations of the rules in the order of the dependencies.
+async fn property(
+    property: Tasks,
+    rules: Vec<BoxFuture<'_, EvaluationResult>>,
+    policy: &Policy,
+    now: DateTime<Utc>,
+    index: u64,
+) -> EvaluationResult {
+    let mut logs = merged_logs(try_join_all(rules).await?);
+    let duration = chrono::Duration::minutes(-2);
+    let (value, failed) = all_of_tasks(property.dependencies(), |t| {
+        logs.holds_since(
+            t,
+            now + duration,
+            PROPERTY_MIN_SAMPLES,
+            policy.aggregation(&property),
+        )
+    });
+    let evaluation =
+        Evaluation::new(warmed_up(value, policy, index), index).with_failed_dependencies(&failed);
+    logs.insert_evaluation(evaluation.clone(), property);
+    Ok(Logging(evaluation, logs))
+}
+
+/// Evaluations of a tick
+#[derive(Debug, Clone)]
+pub struct TickReport {
+    pub index: u64,
+    /// Evaluation of each task, in completion order
+    pub evaluations: Vec<(Tasks, Result<Evaluation, Error>)>,
+    /// Value of each property, empty when some property could not be evaluated
+    pub properties: BTreeMap<Tasks, Option<bool>>,
+    /// Whether all the required properties hold, `None` while there is not enough data to
+    /// decide, see [`Policy::required_properties`]
+    pub verdict: Result<Option<bool>, Error>,
+    /// Weighted percentage of the evaluations that hold, see [`Policy::weight`]
+    pub score: Option<f64>,
+}
+
+impl TickReport {
+    /// Overall compliance of the node in this tick, from its properties
+    pub fn compliance(&self) -> Compliance {
+        match self.verdict {
+            Ok(Some(true)) => Compliance::Compliant,
+            Ok(Some(false)) => Compliance::NonCompliant,
+            Ok(None) => Compliance::InsufficientData,
+            Err(_) => Compliance::Error,
+        }
+    }
+}
+
+/// Overall compliance verdict of a tick, for scripts branching on the exit code of `--once`
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Compliance {
+    /// All the properties hold
+    Compliant,
+    /// At least one property does not hold
+    NonCompliant,
+    /// Some property could not decide, e.g. with too few samples in its windows
+    InsufficientData,
+    /// Some property could not be evaluated
+    Error,
+}
+
+impl Compliance {
+    /// Process exit code reporting the verdict
+    pub fn exit_code(&self) -> i32 {
+        match self {
+            Compliance::Compliant => 0,
+            Compliance::NonCompliant => 1,
+            Compliance::InsufficientData => 2,
+            Compliance::Error => 3,
+        }
+    }
+}
+
+/// How much the agent prints while evaluating
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, PartialOrd, Ord)]
+pub enum Verbosity {
+    /// Nothing is printed, for agents embedded in other applications
+    Silent,
+    /// The value of every task is printed as soon as it is evaluated
+    #[default]
+    Evaluations,
+}
+
+/// Policy in force, along with the settings of the commands run by the agent
+///
+/// Serialized with the policy fields at the top level, so that it can be loaded back as a policy.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct EffectiveConfig {
+    #[serde(flatten)]
+    pub policy: Policy,
+    pub nfdc_binary: String,
+    pub ndnsec_binary: String,
+    /// Time in milliseconds the measurement commands may run for
+    pub command_timeout_ms: u64,
+    /// Time in milliseconds the certificate dumps may run for, the measurement one when unset
+    pub dump_timeout_ms: u64,
+}
+
+impl From<Policy> for EffectiveConfig {
+    fn from(policy: Policy) -> Self {
+        Self {
+            nfdc_binary: policy.commands.nfdc.clone(),
+            ndnsec_binary: policy.commands.ndnsec.clone(),
+            command_timeout_ms: TIMEOUT.as_millis() as u64,
+            dump_timeout_ms: policy
+                .commands
+                .dump_timeout_ms
+                .unwrap_or(TIMEOUT.as_millis() as u64),
+            policy,
+        }
+    }
+}
+
+/// Logs shared between the agent and its readers
+pub type SharedLogs = Arc<RwLock<Logs<Metrics, Tasks, Data>>>;
+
+/// Switch pausing the ticks of an agent, e.g. during a maintenance window
+///
+/// Clones share the same switch, so that signal handlers and control endpoints can flip it.
+#[derive(Debug, Clone, Default)]
+pub struct Pause(Arc<AtomicBool>);
+
+impl Pause {
+    pub fn pause(&self) {
+        self.0.store(true, Ordering::SeqCst);
+    }
+
+    pub fn resume(&self) {
+        self.0.store(false, Ordering::SeqCst);
+    }
+
+    pub fn is_paused(&self) -> bool {
+        self.0.load(Ordering::SeqCst)
+    }
+}
+
+/// Switch asking an agent to stop, cancelling its tick in progress
+///
+/// Clones share the same switch. Requesting the shutdown closes a channel, waking all the
+/// waiters at once.
+#[derive(Debug, Clone)]
+pub struct Shutdown {
+    sender: async_std::channel::Sender<()>,
+    receiver: async_std::channel::Receiver<()>,
+}
+
+impl Default for Shutdown {
+    fn default() -> Self {
+        let (sender, receiver) = async_std::channel::bounded(1);
+        Self { sender, receiver }
+    }
+}
+
+impl Shutdown {
+    pub fn request(&self) {
+        self.sender.close();
+    }
+
+    pub fn is_requested(&self) -> bool {
+        self.sender.is_closed()
+    }
+
+    /// Wait until the shutdown is requested
+    pub async fn requested(&self) {
+        // Nothing is ever sent, the channel only ends when closed
+        let _ = self.receiver.recv().await;
+    }
+}
+
+/// Outcome of [`Agent::next_tick`]
+#[derive(Debug, Clone)]
+pub enum TickOutcome {
+    Completed(TickReport),
+    /// The agent is paused, the tick did not run
+    Paused,
+    /// The shutdown was requested before the tick could complete
+    Cancelled,
+}
+
+/// Certification agent
+///
+/// Measures the node each tick and evaluates the constraints, rules and properties over the
+/// accumulated logs.
+pub struct Agent {
+    pub policy: Policy,
+    logs: SharedLogs,
+    runner: Box<dyn Runner>,
+    verbosity: Verbosity,
+    output: Mutex<Box<dyn Write + Send>>,
+    total_memory: TotalMemory,
+    nfd_memory: ProcessMemory,
+    pause: Pause,
+    shutdown: Shutdown,
+}
+
+impl Agent {
+    pub fn new(policy: Policy) -> Self {
+        Self::with_runner(policy, ProcessRunner)
+    }
+
+    /// Agent running its commands with `runner`, as many at the same time as the policy allows
+    ///
+    /// Silent until given a verbosity with [`Agent::with_verbosity`].
+    pub fn with_runner<R: Runner + 'static>(policy: Policy, runner: R) -> Self {
+        let runner: Box<dyn Runner> = match policy.max_concurrent_commands {
+            Some(limit) => Box::new(LimitedRunner::new(runner, limit.max(1))),
+            None => Box::new(runner),
+        };
+        Self {
+            policy,
+            logs: Arc::new(RwLock::new(Logs::default())),
+            runner,
+            verbosity: Verbosity::Silent,
+            output: Mutex::new(Box::new(io::stdout())),
+            total_memory: TotalMemory::default(),
+            nfd_memory: ProcessMemory::default(),
+            pause: Pause::default(),
+            shutdown: Shutdown::default(),
+        }
+    }
+
+    pub fn with_verbosity(mut self, verbosity: Verbosity) -> Self {
+        self.verbosity = verbosity;
+        self
+    }
+
+    /// Read the total memory of the host with `total_memory` instead of querying the system
+    pub fn with_total_memory(mut self, total_memory: TotalMemory) -> Self {
+        self.total_memory = total_memory;
+        self
+    }
+
+    /// Read the resident memory of NFD with `nfd_memory` instead of querying the system
+    pub fn with_nfd_memory(mut self, nfd_memory: ProcessMemory) -> Self {
+        self.nfd_memory = nfd_memory;
+        self
+    }
+
+    /// Print to `output` instead of the standard output
+    pub fn with_output<W: Write + Send + 'static>(mut self, output: W) -> Self {
+        self.output = Mutex::new(Box::new(output));
+        self
+    }
+
+    /// Shared handle to the accumulated logs
+    pub fn logs(&self) -> SharedLogs {
+        self.logs.clone()
+    }
+
+    /// Metrics whose latest measurement drifted from the one of a known-good `baseline` run,
+    /// among the ones and within the tolerance of the policy
+    pub fn drift_from(
+        &self,
+        baseline: &Table<Metrics, Tasks, Data>,
+    ) -> BTreeMap<Metrics, Drift<Data>> {
+        let current = self.logs.read().unwrap().to_table();
+        baseline.drift(
+            &current,
+            &self.policy.drift_metrics,
+            self.policy.drift_tolerance,
+        )
+    }
+
+    /// Shared switch pausing the ticks, see [`Agent::next_tick`]
+    pub fn pause(&self) -> Pause {
+        self.pause.clone()
+    }
+
+    /// Shared switch stopping the agent, see [`Agent::next_tick`]
+    pub fn shutdown(&self) -> Shutdown {
+        self.shutdown.clone()
+    }
+
+    /// Evaluations of the tick `index`, yielded as soon as each task completes
+    ///
+    /// The logs are not updated, see [`Agent::tick`].
+    pub fn evaluations(
+        &self,
+        index: u64,
+    ) -> impl Stream<Item = (Tasks, EvaluationResult)> + Send + '_ {
+        self.evaluations_at(index, Utc::now())
+    }
+
+    /// Evaluations of the tick `index` as if it ran at `now`, which ends the windows of the rules
+    pub fn evaluations_at(
+        &self,
+        index: u64,
+        now: DateTime<Utc>,
+    ) -> impl Stream<Item = (Tasks, EvaluationResult)> + Send + '_ {
+        let logs = self.logs.read().unwrap().clone();
+        let runner = self.runner.as_ref();
+        let policy = &self.policy;
+
+        let host_total_memory_f = self.total_memory.get().shared();
+        let nfd_memory_f = self.nfd_memory.get();
+        // Fetch phase: the status and the certificate list run concurrently, the dumps of the
+        // listed certificates are chained after the list, all within the command bound of the
+        // policy
+        let nfd_status_f = nfdc_status(runner, policy).shared();
+        let general_status_f = general_status(nfd_status_f.clone(), policy).shared();
+        let certificate_list_f = ndnsec_list(runner, policy).shared();
+        let certificate_infos_f = ndnsec_infos(certificate_list_f.clone(), runner, policy).shared();
+        let lenient = |measurement, metric| {
+            leniently(measurement, metric, index, logs.clone(), policy).shared()
+        };
+
+        let m1_f = lenient(
+            m1(nfd_status_f.clone(), index, logs.clone()).boxed(),
+            Metrics::M1,
+        );
+        let m2_f = lenient(
+            m2(nfd_status_f.clone(), index, logs.clone()).boxed(),
+            Metrics::M2,
+        );
+        let m3_f = lenient(
+            m3(nfd_status_f.clone(), index, logs.clone()).boxed(),
+            Metrics::M3,
+        );
+        let m4_f = lenient(
+            m4(nfd_status_f.clone(), index, logs.clone()).boxed(),
+            Metrics::M4,
+        );
+        let _m5_f = lenient(
+            m5(nfd_status_f.clone(), index, logs.clone()).boxed(),
+            Metrics::M5,
+        );
+        let m6_f = lenient(
+            m6(nfd_status_f.clone(), index, logs.clone()).boxed(),
+            Metrics::M6,
+        );
+        let m37_f = lenient(
+            m37(nfd_status_f.clone(), index, logs.clone()).boxed(),
+            Metrics::M37,
+        );
+        let m7_f = lenient(
+            m7(nfd_status_f.clone(), index, logs.clone()).boxed(),
+            Metrics::M7,
+        );
+        let m8_f = lenient(
+            m8(nfd_status_f.clone(), index, logs.clone()).boxed(),
+            Metrics::M8,
+        );
+        let m9_f = lenient(
+            m9(nfd_status_f.clone(), index, logs.clone()).boxed(),
+            Metrics::M9,
+        );
+        let m10_f = lenient(
+            m10(nfd_status_f.clone(), index, logs.clone()).boxed(),
+            Metrics::M10,
+        );
+        let m11_f = lenient(
+            m11(certificate_infos_f.clone(), policy, index, logs.clone()).boxed(),
+            Metrics::M11,
+        );
+        let m17_f = lenient(
+            m17(certificate_infos_f.clone(), index, logs.clone()).boxed(),
+            Metrics::M17,
+        );
+        let m32_f = lenient(
+            m32(certificate_infos_f.clone(), index, logs.clone()).boxed(),
+            Metrics::M32,
+        );
+        let m23_f = lenient(
+            m23(certificate_infos_f.clone(), index, logs.clone()).boxed(),
+            Metrics::M23,
+        );
+        let m24_f = lenient(
+            m24(
+                certificate_list_f.clone(),
+                certificate_infos_f.clone(),
+                index,
+                logs.clone(),
+            )
+            .boxed(),
+            Metrics::M24,
+        );
+        let m25_f = m25(
+            nfd_status_f.clone(),
+            certificate_list_f.clone(),
+            certificate_infos_f,
+            index,
+            logs.clone(),
+        )
+        .shared();
+        let m12_f = lenient(
+            m12(certificate_list_f.clone(), policy, index, logs.clone()).boxed(),
+            Metrics::M12,
+        );
+        let m13_f = lenient(
+            m13(host_total_memory_f, index, logs.clone()).boxed(),
+            Metrics::M13,
+        );
+        let m30_f = lenient(m30(nfd_memory_f, index, logs.clone()).boxed(), Metrics::M30);
+        let m14_f = lenient(
+            m14(nfd_status_f.clone(), index, logs.clone()).boxed(),
+            Metrics::M14,
+        );
+        let m15_f = lenient(
+            m15(general_status_f.clone(), index, logs.clone()).boxed(),
+            Metrics::M15,
+        );
+        let m16_f = lenient(
+            m16(nfd_status_f.clone(), index, logs.clone()).boxed(),
+            Metrics::M16,
+        );
+        let m19_f = lenient(
+            m19(nfd_status_f.clone(), index, logs.clone()).boxed(),
+            Metrics::M19,
+        );
+        let m22_f = lenient(
+            m22(general_status_f.clone(), index, logs.clone()).boxed(),
+            Metrics::M22,
+        );
+        let m21_f = lenient(
+            m21(nfd_status_f.clone(), index, logs.clone()).boxed(),
+            Metrics::M21,
+        );
+        let m26_f = lenient(
+            m26(nfd_status_f.clone(), index, logs.clone()).boxed(),
+            Metrics::M26,
+        );
+        let m27_f = lenient(
+            m27(nfd_status_f.clone(), index, logs.clone()).boxed(),
+            Metrics::M27,
+        );
+        let m28_f = lenient(
+            m28(nfd_status_f.clone(), policy, index, logs.clone()).boxed(),
+            Metrics::M28,
+        );
+        let m29_f = lenient(
+            m29(nfd_status_f.clone(), index, logs.clone()).boxed(),
+            Metrics::M29,
+        );
+        let m31_f = lenient(
+            m31(nfd_status_f.clone(), index, logs.clone()).boxed(),
+            Metrics::M31,
+        );
+        let m33_f = lenient(
+            m33(nfd_status_f.clone(), index, logs.clone()).boxed(),
+            Metrics::M33,
+        );
+        let m34_f = lenient(
+            m34(nfd_status_f.clone(), index, logs.clone()).boxed(),
+            Metrics::M34,
+        );
+        let m35_f = lenient(
+            m35(general_status_f.clone(), now, index, logs.clone()).boxed(),
+            Metrics::M35,
+        );
+        let m36_f = lenient(m36(nfd_status_f, index, logs.clone()).boxed(), Metrics::M36);
+        let m20_f = lenient(
+            m20(certificate_list_f, index, logs.clone()).boxed(),
+            Metrics::M20,
+        );
+        let m18_f = m18(index, logs).shared();
+
+        let measurements = vec![
+            (Metrics::M1, m1_f.clone()),
+            (Metrics::M2, m2_f.clone()),
+            (Metrics::M3, m3_f.clone()),
+            (Metrics::M4, m4_f.clone()),
+            (Metrics::M6, m6_f.clone()),
+            (Metrics::M7, m7_f.clone()),
+            (Metrics::M8, m8_f.clone()),
+            (Metrics::M9, m9_f.clone()),
+            (Metrics::M10, m10_f.clone()),
+            (Metrics::M11, m11_f.clone()),
+            (Metrics::M12, m12_f.clone()),
+            (Metrics::M13, m13_f.clone()),
+            (Metrics::M14, m14_f.clone()),
+            (Metrics::M15, m15_f.clone()),
+            (Metrics::M16, m16_f.clone()),
+            (Metrics::M17, m17_f.clone()),
+            (Metrics::M19, m19_f.clone()),
+            (Metrics::M20, m20_f.clone()),
+            (Metrics::M21, m21_f.clone()),
+            (Metrics::M22, m22_f.clone()),
+            (Metrics::M23, m23_f.clone()),
+            (Metrics::M24, m24_f.clone()),
+            (Metrics::M26, m26_f.clone()),
+            (Metrics::M27, m27_f.clone()),
+            (Metrics::M28, m28_f.clone()),
+            (Metrics::M29, m29_f.clone()),
+            (Metrics::M30, m30_f.clone()),
+            (Metrics::M31, m31_f.clone()),
+            (Metrics::M32, m32_f.clone()),
+            (Metrics::M33, m33_f.clone()),
+            (Metrics::M34, m34_f.clone()),
+            (Metrics::M35, m35_f.clone()),
+            (Metrics::M36, m36_f.clone()),
+            (Metrics::M37, m37_f.clone()),
+        ]
+        .into_iter()
+        .collect::<HashMap<_, _>>();
+        let unparsed = |task: Tasks, constraint| {
+            let sources = task
+                .metrics()
+                .iter()
+                .filter_map(|metric| measurements.get(metric).cloned())
+                .collect();
+            screen_measurements(constraint, sources, task, index, policy.nan_handling)
+                .boxed()
+                .shared()
+        };
+
+        let mut constraints = vec![
+            (Tasks::C1, c1(m1_f, policy, index).boxed()),
+            (Tasks::C2, c2(m2_f.clone(), m13_f, policy, index).boxed()),
+            (Tasks::C3, c3(m2_f.clone(), index).boxed()),
+            (Tasks::C4, c4(m2_f, m3_f.clone(), index).boxed()),
+            (Tasks::C5, c5(m3_f, policy, index).boxed()),
+            (Tasks::C6, c6(m4_f.clone(), index).boxed()),
+            (Tasks::C7, c7(m4_f, index).boxed()),
+            (Tasks::C8, c8(m6_f, m37_f, index).boxed()),
+            (Tasks::C9, c9(m7_f, policy, now, index).boxed()),
+            (Tasks::C10, c10(m9_f, policy, index).boxed()),
+            (Tasks::C11, c11(m8_f, policy, now, index).boxed()),
+            (Tasks::C12, c12(m10_f, policy, index).boxed()),
+            (Tasks::C13, c13(m11_f, now, index).boxed()),
+            (Tasks::C14, c14(m12_f, index).boxed()),
+            (Tasks::C15, c15(m14_f, index).boxed()),
+            (Tasks::C16, c16(m15_f, policy, index).boxed()),
+            (Tasks::C17, c17(m16_f, policy, index).boxed()),
+            (Tasks::C18, c18(m17_f, policy, index).boxed()),
+            (Tasks::C19, c19(m18_f, policy, index).boxed()),
+            (Tasks::C20, c20(m19_f, policy, now, index).boxed()),
+            (Tasks::C21, c21(m20_f, policy, index).boxed()),
+            (Tasks::C22, c22(m21_f.clone(), policy, now, index).boxed()),
+            (Tasks::C27, c27(m21_f, index).boxed()),
+            (Tasks::C23, c23(m22_f, policy, index).boxed()),
+            (Tasks::C24, c24(m23_f, policy, index).boxed()),
+            (Tasks::C25, c25(m24_f, now, index).boxed()),
+            (Tasks::C28, c28(m26_f, policy, index).boxed()),
+            (Tasks::C29, c29(m27_f, index).boxed()),
+            (Tasks::C30, c30(m28_f, policy, index).boxed()),
+            (Tasks::C31, c31(m29_f, index).boxed()),
+            (Tasks::C32, c32(m30_f, policy, index).boxed()),
+            (Tasks::C33, c33(m31_f, policy, index).boxed()),
+            (Tasks::C34, c34(m32_f, policy, index).boxed()),
+            (Tasks::C35, c35(m33_f, policy, index).boxed()),
+            (Tasks::C36, c36(m34_f, policy, index).boxed()),
+            (Tasks::C37, c37(m35_f, policy, index).boxed()),
+            (Tasks::C38, c38(m36_f, policy, index).boxed()),
+        ]
+        .into_iter()
+        .map(|(task, constraint)| (task.clone(), unparsed(task, constraint)))
+        .collect::<BTreeMap<_, _>>();
+        // The parse errors are the measurement of C26, which has no source to screen
+        constraints.insert(Tasks::C26, c26(m25_f, policy, now, index).boxed().shared());
+
+        // Rules and properties are wired by their dependencies, the rules other than R1 and R10
+        // aggregating their constraints over the window
+        let dependencies_of = |task: &Tasks, tasks: &BTreeMap<Tasks, Shared<_>>| {
+            task.dependencies()
+                .iter()
+                .map(|dependency| tasks[dependency].clone().boxed())
+                .collect::<Vec<_>>()
+        };
+        let properties = [Tasks::P1, Tasks::P2, Tasks::P3];
+        let mut rules = BTreeMap::new();
+        for rule in properties.iter().flat_map(Tasks::dependencies) {
+            if rules.contains_key(rule) {
+                continue;
+            }
+            let constraints_of = dependencies_of(rule, &constraints);
+            let evaluation = match rule {
+                Tasks::R1 => r1(constraints_of, index).boxed(),
+                Tasks::R10 => r10(constraints_of, policy, now, index).boxed(),
+                _ => windowed_rule(rule.clone(), constraints_of, policy, now, index).boxed(),
+            };
+            rules.insert(rule.clone(), evaluation.shared());
+        }
+        let properties = properties
+            .iter()
+            .map(|task| {
+                let rules_of = dependencies_of(task, &rules);
+                let evaluation = property(task.clone(), rules_of, policy, now, index);
+                (task.clone(), evaluation.boxed())
+            })
+            .collect::<Vec<_>>();
+
+        let tasks = FuturesUnordered::new();
+        for (task, evaluation) in constraints.into_iter().chain(rules) {
+            tasks.push(evaluation.map(move |e| (task, e)).boxed());
+        }
+        for (task, evaluation) in properties {
+            tasks.push(evaluation.map(move |e| (task, e)).boxed());
+        }
+        tasks
+    }
+
+    /// Run the tick `index`, collecting the evaluation of every task
+    ///
+    /// The logs of the properties are merged into the agent logs only if all of them were
+    /// evaluated, together with the tick duration and score. Otherwise only the error is logged.
+    pub async fn tick(&self, index: u64) -> TickReport {
+        // The wall clock stamps the logs, while the monotonic clock times the tick, as NTP may
+        // move the former backwards in the meantime
+        let execution_start = Utc::now();
+        let started = Instant::now();
+        let mut evaluations = Vec::new();
+        let mut properties = HashMap::new();
+        let mut stream = self.evaluations_at(index, execution_start);
+        while let Some((task, result)) = stream.next().await {
+            self.print_evaluation(&task, &result);
+            evaluations.push((task.clone(), result.clone().map(|Logging(e, _)| e)));
+            if let Tasks::P1 | Tasks::P2 | Tasks::P3 = task {
+                properties.insert(task, result);
+            }
+        }
+        let score = weighted_score(evaluations.iter().map(|(task, evaluation)| {
+            let value = evaluation.as_ref().ok().and_then(|e| e.value);
+            (self.policy.weight(task), value)
+        }));
+        let properties = self.merge_properties(properties, index, started, score);
+        let verdict = properties.as_ref().map_err(Clone::clone).map(|properties| {
+            all_of(
+                self.policy
+                    .required_properties
+                    .iter()
+                    .map(|task| properties.get(task).cloned().flatten()),
+            )
+        });
+        if let Err(error) = &verdict {
+            let mut logs = self.logs.write().unwrap();
+            logs.insert_error(error.to_string(), index);
+        }
+        TickReport {
+            index,
+            evaluations,
+            properties: properties.unwrap_or_default(),
+            verdict,
+            score,
+        }
+    }
+
+    /// Run the tick `index`, unless the agent is paused or shutting down
+    ///
+    /// A paused agent neither measures nor logs, so the caller should not move on to the next
+    /// index. The accumulated logs are kept across the pause. Requesting the shutdown cancels
+    /// the tick, which leaves the logs as they were before it: they are only updated once all
+    /// the properties are evaluated, without awaiting anything else.
+    pub async fn next_tick(&self, index: u64) -> TickOutcome {
+        if self.shutdown.is_requested() {
+            return TickOutcome::Cancelled;
+        }
+        if self.pause.is_paused() {
+            return TickOutcome::Paused;
+        }
+        let tick = self.tick(index).map(TickOutcome::Completed);
+        let cancelled = self.shutdown.requested().map(|_| TickOutcome::Cancelled);
+        tick.race(cancelled).await
+    }
+
+    fn print_evaluation(&self, task: &Tasks, result: &EvaluationResult) {
+        if self.verbosity < Verbosity::Evaluations {
+            return;
+        }
+        if let Ok(Logging(evaluation, _)) = result {
+            let mut output = self.output.lock().unwrap();
+            let failed = &evaluation.failed_dependencies;
+            let _ = match evaluation.value {
+                Some(false) if !failed.is_empty() => {
+                    writeln!(output, "{:?}: false, failed {}", task, failed.join(", "))
+                }
+                Some(value) => writeln!(output, "{:?}: {}", task, value),
+                None => writeln!(output, "{:?}: insufficient data", task),
+            };
+        }
+    }
+
+    fn merge_properties(
+        &self,
+        mut properties: HashMap<Tasks, EvaluationResult>,
+        index: u64,
+        started: Instant,
+        score: Option<f64>,
+    ) -> Result<BTreeMap<Tasks, Option<bool>>, Error> {
+        let mut results = Vec::new();
+        for task in [Tasks::P1, Tasks::P2, Tasks::P3].iter() {
+            results.push((task.clone(), properties.remove(task).unwrap()?));
+        }
+        let mut logs = self.logs.write().unwrap();
+        for (_, Logging(_, property_logs)) in results.iter() {
+            logs.mut_merge(property_logs);
+        }
+        let duration = started.elapsed().as_nanos().min(i64::MAX as u128) as i64;
+        logs.insert_duration(duration, index);
+        if let Some(score) = score {
+            logs.insert_score(score, index);
+        }
+        if self.policy.hash_chain {
+            logs.insert_chain_link(index);
+        }
+        if let Some(after) = self.policy.downsample_after_secs {
+            logs.downsample_older_than(
+                Utc::now() - chrono::Duration::seconds(after as i64),
+                chrono::Duration::seconds(self.policy.downsample_bucket_secs as i64),
+            );
+        }
+        Ok(results
+            .into_iter()
+            .map(|(task, Logging(evaluation, _))| (task, evaluation.value))
+            .collect())
+    }
+}
+
+/// Agents certifying several nodes, each one with its own logs
+pub struct Testbed {
+    agents: BTreeMap<String, Agent>,
+    shutdown: Shutdown,
+}
+
+impl Testbed {
+    /// Agent for each node of the policy, running its commands through the node wrapper
+    ///
+    /// The agents print nothing, their reports are returned by [`Testbed::tick`].
+    pub fn new(policy: Policy) -> Self {
+        let agents = policy
+            .nodes
+            .iter()
+            .map(|node| {
+                let runner = WrappedRunner::new(node.wrapper.iter());
+                (node.id.clone(), Self::node_agent(policy.clone(), runner))
+            })
+            .collect::<Vec<_>>();
+        Self::with_agents(agents)
+    }
+
+    /// Agent of a node reached by `runner`, which reads the memory of the node as well
+    pub fn node_agent<R: Runner + Clone + 'static>(policy: Policy, runner: R) -> Agent {
+        Agent::with_runner(policy, runner.clone())
+            .with_verbosity(Verbosity::Silent)
+            .with_total_memory(TotalMemory::through(runner.clone()))
+            .with_nfd_memory(ProcessMemory::through(runner))
+    }
+
+    /// Testbed of the agents, sharing the shutdown of the testbed
+    pub fn with_agents<I: IntoIterator<Item = (String, Agent)>>(agents: I) -> Self {
+        let shutdown = Shutdown::default();
+        let agents = agents
+            .into_iter()
+            .map(|(id, mut agent)| {
+                agent.shutdown = shutdown.clone();
+                (id, agent)
+            })
+            .collect();
+        Self { agents, shutdown }
+    }
+
+    /// Handle to request the shutdown of all the nodes, e.g. from a signal handler
+    pub fn shutdown(&self) -> Shutdown {
+        self.shutdown.clone()
+    }
+
+    /// Shared handles to the logs of each node
+    pub fn logs(&self) -> BTreeMap<String, SharedLogs> {
+        self.agents
+            .iter()
+            .map(|(id, agent)| (id.clone(), agent.logs()))
+            .collect()
+    }
+
+    /// Tick all the nodes concurrently
+    pub async fn tick(&self, index: u64) -> BTreeMap<String, TickReport> {
+        let reports = join_all(self.agents.values().map(|agent| agent.tick(index))).await;
+        self.agents.keys().cloned().zip(reports).collect()
+    }
+
+    /// Tick all the nodes concurrently, unless the shutdown is requested first
+    ///
+    /// As with [`Agent::next_tick`], the logs of a node whose tick is cancelled hold its
+    /// complete ticks only.
+    pub async fn next_tick(&self, index: u64) -> BTreeMap<String, TickOutcome> {
+        let outcomes = join_all(self.agents.values().map(|agent| agent.next_tick(index))).await;
+        self.agents.keys().cloned().zip(outcomes).collect()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::command::host::HostCommand;
+    use crate::command::record::{RecordingRunner, ReplayRunner};
+    use crate::command::{CommandConfig, CountingRunner, FlakyRunner, MockRunner, RetryPolicy};
+    use crate::output::OutputFormat;
+    use crate::size::ByteSize;
+    use chrono::TimeZone;
+    use std::collections::HashSet;
+
+    /// Dump of the `/test` certificate of the list, the dump fixture being the `/bertof` one
+    fn test_dump() -> String {
+        include_str!("command/ndnsec/dump.txt").replacen(
+            "/bertof/KEY/U%A8%3E%D3%D7%EF%F37/self/%FD%00%00%01p%99%1E%FC%F7",
+            "/test/KEY/%A8C%0C%13%ADd%3B%9B/self/%FD%00%00%01s%BF%E4U%3C",
+            1,
+        )
+    }
+
+    fn mock_runner() -> MockRunner {
+        configured_mock_runner(&CommandConfig::default())
+    }
+
+    /// Runner replying to the commands running the binaries of `commands`
+    fn configured_mock_runner(commands: &CommandConfig) -> MockRunner {
+        let dump = include_str!("command/ndnsec/dump.txt").to_string();
+        MockRunner::default()
+            .with_output(
+                &nfdc::NfdcCommand::Status.with_config(commands.clone()),
+                Ok(include_str!("command/nfdc_report.xml").to_string()),
+            )
+            .with_output(
+                &ndnsec::NdnSecCommand::List.with_config(commands.clone()),
+                Ok(include_str!("command/ndnsec/list.txt").to_string()),
+            )
+            .with_output(
+                &ndnsec::NdnSecCommand::Dump("/test".to_string()).with_config(commands.clone()),
+                Ok(test_dump()),
+            )
+            .with_output(
+                &ndnsec::NdnSecCommand::Dump("/bertof".to_string()).with_config(commands.clone()),
+                Ok(dump),
+            )
+    }
+
+    async fn value_of(agent: &Agent, task: Tasks) -> Option<bool> {
+        let evaluations = agent.evaluations(0).collect::<Vec<_>>().await;
+        let (_, evaluation) = evaluations.into_iter().find(|(t, _)| *t == task).unwrap();
+        evaluation.unwrap().0.value
+    }
+
+    #[async_std::test]
+    async fn configured_binaries() {
+        // Binaries looked up in `PATH`, e.g. installed under `/usr/local/bin` or a Nix store
+        let commands = CommandConfig {
+            nfdc: "nfdc".to_string(),
+            ndnsec: "ndnsec".to_string(),
+            env: Some("/usr/bin/env".to_string()),
+            ..CommandConfig::default()
+        };
+        let policy = Policy {
+            commands: commands.clone(),
+            ..Policy::default()
+        };
+        let agent = Agent::with_runner(policy.clone(), configured_mock_runner(&commands));
+        let evaluations = agent.evaluations(0).collect::<Vec<_>>().await;
+        assert!(evaluations.iter().all(|(_, e)| e.is_ok()));
+        assert_eq!(value_of(&agent, Tasks::C1).await, Some(true));
+
+        // The default paths are not run anymore
+        let agent = Agent::with_runner(policy.clone(), mock_runner());
+        let report = agent.tick(0).await;
+        assert!(report.verdict.is_err());
+        let config = EffectiveConfig::from(policy);
+        assert_eq!(config.nfdc_binary, "nfdc");
+        assert_eq!(config.ndnsec_binary, "ndnsec");
+    }
+
+    #[async_std::test]
+    async fn dump_timeout() {
+        let policy = Policy {
+            commands: CommandConfig {
+                dump_timeout_ms: Some(1),
+                ..CommandConfig::default()
+            },
+            ..Policy::default()
+        };
+        // Every command takes 10ms, longer than the dumps may run
+        let agent = Agent::with_runner(policy, CountingRunner::new(mock_runner()));
+        let evaluations = agent.evaluations(0).collect::<Vec<_>>().await;
+        let (_, c1) = evaluations.iter().find(|(t, _)| *t == Tasks::C1).unwrap();
+        assert!(c1.is_ok());
+        assert!(evaluations
+            .iter()
+            .any(|(_, e)| matches!(e, Err(Error::TaskError(command::Error::Timeout(_))))));
+    }
+
+    #[async_std::test]
+    async fn stream_all_evaluations() {
+        let agent = Agent::with_runner(Policy::default(), mock_runner());
+        let evaluations = agent.evaluations(0).collect::<Vec<_>>().await;
+        assert!(evaluations.iter().all(|(_, e)| e.is_ok()));
+        let tasks = evaluations
+            .into_iter()
+            .map(|(task, _)| task)
+            .collect::<HashSet<_>>();
+        assert_eq!(tasks.len(), 73);
+        for task in [
+            Tasks::C1,
+            Tasks::C16,
+            Tasks::R1,
+            Tasks::R9,
+            Tasks::P1,
+            Tasks::P3,
+        ]
+        .iter()
+        {
+            assert!(tasks.contains(task));
+        }
+    }
+
+    #[async_std::test]
+    async fn tick_merges_logs() {
+        let agent = Agent::with_runner(Policy::default(), mock_runner());
+        agent.tick(0).await.verdict.unwrap();
+        agent.tick(1).await.verdict.unwrap();
+        let logs = agent.logs();
+        let logs = logs.read().unwrap();
+        assert_eq!(logs.evaluations_index[&Tasks::P1].len(), 2);
+        assert_eq!(logs.measurements_index[&Metrics::M1].len(), 2);
+        assert_eq!(logs.duration_index.len(), 2);
+    }
+
+    #[async_std::test]
+    async fn duration_survives_clock_jump() {
+        let agent = Agent::with_runner(Policy::default(), mock_runner());
+        let started = Instant::now();
+        // The wall clock is set an hour back while the tick runs
+        let execution_start = Utc::now() + chrono::Duration::hours(1);
+        let mut properties = HashMap::new();
+        let mut stream = agent.evaluations_at(0, execution_start);
+        while let Some((task, result)) = stream.next().await {
+            if let Tasks::P1 | Tasks::P2 | Tasks::P3 = task {
+                properties.insert(task, result);
+            }
+        }
+        agent
+            .merge_properties(properties, 0, started, None)
+            .unwrap();
+        let duration = agent.logs().read().unwrap().duration_index[&0];
+        assert!((0..60_000_000_000).contains(&duration), "{}", duration);
+    }
+
+    #[async_std::test]
+    async fn paused_ticks() {
+        let agent = Agent::with_runner(Policy::default(), mock_runner());
+        let pause = agent.pause();
+        assert!(matches!(
+            agent.next_tick(0).await,
+            TickOutcome::Completed(_)
+        ));
+        pause.pause();
+        for _ in 0..3 {
+            assert!(matches!(agent.next_tick(1).await, TickOutcome::Paused));
+        }
+        assert_eq!(agent.logs().read().unwrap().duration_index.len(), 1);
+        pause.resume();
+        match agent.next_tick(1).await {
+            TickOutcome::Completed(report) => assert_eq!(report.index, 1),
+            other => panic!("{:?}", other),
+        }
+        let logs = agent.logs();
+        let logs = logs.read().unwrap();
+        let mut indexes = logs.duration_index.keys().collect::<Vec<_>>();
+        indexes.sort();
+        assert_eq!(indexes, [&0, &1]);
+    }
+
+    #[async_std::test]
+    async fn strategy_drift_from_baseline() {
+        let policy = Policy {
+            required_strategies: vec![(
+                "/ndn/broadcast".to_string(),
+                "/localhost/nfd/strategy/multicast".to_string(),
+            )]
+            .into_iter()
+            .collect(),
+            ..Policy::default()
+        };
+        let agent = Agent::with_runner(policy.clone(), mock_runner());
+        agent.tick(0).await.verdict.unwrap();
+        let baseline = agent.logs().read().unwrap().to_table();
+        agent.tick(1).await.verdict.unwrap();
+        assert!(agent.drift_from(&baseline).is_empty());
+
+        let report = include_str!("command/nfdc_report.xml").replace(
+            "<namespace>/ndn/broadcast</namespace><strategy><name>/localhost/nfd/strategy/multicast",
+            "<namespace>/ndn/broadcast</namespace><strategy><name>/localhost/nfd/strategy/best-route",
+        );
+        let runner = mock_runner().with_output(&nfdc::NfdcCommand::Status, Ok(report));
+        let agent = Agent::with_runner(policy, runner);
+        agent.tick(0).await.verdict.unwrap();
+        let drift = agent.drift_from(&baseline);
+        assert_eq!(drift.keys().collect::<Vec<_>>(), [&Metrics::M28]);
+        match &drift[&Metrics::M28].current {
+            Data::M28(strategies) => assert_eq!(
+                strategies["/ndn/broadcast"].as_deref(),
+                Some("/localhost/nfd/strategy/best-route/%FD%04")
+            ),
+            other => panic!("{:?}", other),
+        }
+    }
+
+    #[async_std::test]
+    async fn retries_outlast_the_measurement_timeout() {
+        let mut policy = Policy::default();
+        policy.commands.retry = RetryPolicy {
+            max_attempts: 2,
+            initial_delay_ms: 1200,
+            multiplier: 2.0,
+        };
+        // The forwarder is restarting during the first attempt
+        let error = command::Error::IoError("Connection refused".to_string());
+        let runner = FlakyRunner::new(mock_runner(), &nfdc::NfdcCommand::Status, error, 1);
+        let agent = Agent::with_runner(policy, runner.clone());
+        let report = agent.tick(0).await;
+        assert_eq!(runner.attempts(), 2);
+        assert!(report.evaluations.iter().all(|(_, e)| e.is_ok()));
+        assert!(report.verdict.is_ok());
+    }
+
+    #[async_std::test]
+    async fn shutdown_cancels_tick() {
+        // Each command takes 10ms, so that the shutdown lands within the tick
+        let agent = Agent::with_runner(Policy::default(), CountingRunner::new(mock_runner()))
+            .with_total_memory(TotalMemory::new(|| Ok(8 << 30)))
+            .with_nfd_memory(ProcessMemory::new(|| Ok(Some(64 << 20))));
+        let shutdown = agent.shutdown();
+        assert!(matches!(
+            agent.next_tick(0).await,
+            TickOutcome::Completed(_)
+        ));
+        let logs_file = || serde_json::to_value(agent.logs().read().unwrap().to_table()).unwrap();
+        let before = logs_file();
+
+        // Requested from another thread, as by the signal handler
+        let request = std::thread::spawn(move || {
+            std::thread::sleep(Duration::from_millis(5));
+            shutdown.request();
+        });
+        assert!(matches!(agent.next_tick(1).await, TickOutcome::Cancelled));
+        request.join().unwrap();
+        assert!(matches!(agent.next_tick(1).await, TickOutcome::Cancelled));
+
+        // The logs written on shutdown hold the whole first tick and nothing of the second one
+        let written = logs_file();
+        assert_eq!(written, before);
+        let indexes = |values: &serde_json::Value| {
+            let values = values.as_object().unwrap();
+            values.keys().cloned().collect::<Vec<_>>()
+        };
+        assert_eq!(indexes(&written["duration_index"]), ["0"]);
+        let evaluations = written["evaluations_index"].as_object().unwrap();
+        assert!(evaluations.values().all(|values| indexes(values) == ["0"]));
+    }
+
+    #[async_std::test]
+    async fn properties_withheld_during_warm_up() {
+        let policy = Policy {
+            min_samples: 3,
+            ..Policy::default()
+        };
+        // C32 is undecided without a forwarder process on the host running the test
+        let agent = Agent::with_runner(policy, mock_runner())
+            .with_nfd_memory(ProcessMemory::new(|| Ok(Some(64 << 20))));
+        for index in 0..2 {
+            let report = agent.tick(index).await;
+            assert_eq!(report.verdict.unwrap(), None);
+            for (task, evaluation) in report.evaluations {
+                if let Tasks::P1 | Tasks::P2 | Tasks::P3 = task {
+                    assert_eq!(evaluation.unwrap().value, None);
+                }
+            }
+        }
+        // C19 has no tick duration to watch before the first tick is over, while C22 needs
+        // three face counts to tell a trend
+        agent.tick(2).await.verdict.unwrap();
+        agent.tick(3).await.verdict.unwrap();
+        let report = agent.tick(4).await;
+        assert!(report.verdict.unwrap().is_some());
+        assert!(report.evaluations.iter().all(|(_, evaluation)| evaluation
+            .as_ref()
+            .unwrap()
+            .value
+            .is_some()));
+    }
+
+    #[async_std::test]
+    async fn required_properties_only() {
+        // No content store policy is allowed, so the forwarder resources of P1 do not hold
+        let verdicts = |required_properties| async {
+            let policy = Policy {
+                min_samples: 3,
+                cs_policies: vec!["none".to_string()],
+                required_properties,
+                ..Policy::default()
+            };
+            let agent = Agent::with_runner(policy, mock_runner())
+                .with_nfd_memory(ProcessMemory::new(|| Ok(Some(64 << 20))));
+            for index in 0..4 {
+                agent.tick(index).await.verdict.unwrap();
+            }
+            let report = agent.tick(4).await;
+            (report.properties, report.verdict.unwrap())
+        };
+        let (properties, verdict) = verdicts(vec![Tasks::P1, Tasks::P2, Tasks::P3]).await;
+        assert_eq!(properties[&Tasks::P1], Some(false));
+        assert_eq!(properties[&Tasks::P2], Some(true));
+        assert_eq!(verdict, Some(false));
+
+        let (properties, verdict) = verdicts(vec![Tasks::P2]).await;
+        assert_eq!(properties[&Tasks::P1], Some(false));
+        assert_eq!(verdict, Some(true));
+    }
+
+    #[async_std::test]
+    async fn rules_withheld_during_warm_up() {
+        let policy = Policy {
+            min_samples: 0,
+            warm_up_ticks: 3,
+            ..Policy::default()
+        };
+        let agent = Agent::with_runner(policy, mock_runner());
+        let windowed = |task: &Tasks| !task.dependencies().is_empty() && *task != Tasks::R1;
+        for index in 0..3 {
+            let report = agent.tick(index).await;
+            assert_eq!(report.verdict.unwrap(), None);
+            for (task, evaluation) in report.evaluations.iter().filter(|(t, _)| windowed(t)) {
+                assert_eq!(evaluation.as_ref().unwrap().value, None, "{:?}", task);
+            }
+        }
+        let report = agent.tick(3).await;
+        let value = |task| {
+            let (_, evaluation) = report.evaluations.iter().find(|(t, _)| *t == task).unwrap();
+            evaluation.as_ref().unwrap().value
+        };
+        assert!(value(Tasks::R3).is_some());
+        assert!(value(Tasks::R10).is_some());
+
+        // Transitions are not counted over fewer evaluations than the rules need
+        let agent = Agent::with_runner(Policy::default(), mock_runner());
+        assert_eq!(value_of(&agent, Tasks::R10).await, None);
+    }
+
+    #[async_std::test]
+    async fn exclude_faces_by_id_and_uri() {
+        let policy = Policy {
+            excluded_face_ids: vec![256],
+            excluded_remote_uris: vec!["udp4://224.0.23.170:*".to_string()],
+            ..Policy::default()
+        };
+        let agent = Agent::with_runner(policy, mock_runner());
+        agent.tick(0).await.verdict.unwrap();
+        let logs = agent.logs();
+        let logs = logs.read().unwrap();
+        let face_ids = |metric: Metrics| match &logs.measurements_index[&metric][0].1 {
+            Data::M6(v) => v.keys().cloned().collect::<Vec<_>>(),
+            Data::M7(v) | Data::M8(v) | Data::M9(v) | Data::M10(v) => v.keys().cloned().collect(),
+            data => panic!("Unexpected data {:?}", data),
+        };
+        // Faces 265 to 270 are the udp4 multicast ones
+        for metric in [
+            Metrics::M6,
+            Metrics::M7,
+            Metrics::M8,
+            Metrics::M9,
+            Metrics::M10,
+        ]
+        .iter()
+        {
+            let ids = face_ids(metric.clone());
+            assert!(ids.contains(&257));
+            assert!(!ids.contains(&256));
+            assert!(!(265..=270).any(|id| ids.contains(&id)));
+        }
+    }
+
+    #[async_std::test]
+    async fn exclude_internal_faces() {
+        let policy = Policy {
+            exclude_internal_faces: true,
+            ..Policy::default()
+        };
+        let agent = Agent::with_runner(policy, mock_runner());
+        agent.tick(0).await.verdict.unwrap();
+        let logs = agent.logs();
+        let logs = logs.read().unwrap();
+        let face_ids = |metric: Metrics| match &logs.measurements_index[&metric][0].1 {
+            Data::M6(v) => v.keys().cloned().collect::<Vec<_>>(),
+            Data::M7(v) | Data::M8(v) | Data::M9(v) | Data::M10(v) => v.keys().cloned().collect(),
+            Data::M16(v) => v.keys().cloned().collect(),
+            data => panic!("Unexpected data {:?}", data),
+        };
+        // Faces 1, 254 and 255 are internal, contentstore and null
+        for metric in [
+            Metrics::M6,
+            Metrics::M7,
+            Metrics::M8,
+            Metrics::M9,
+            Metrics::M10,
+            Metrics::M16,
+        ]
+        .iter()
+        {
+            let ids = face_ids(metric.clone());
+            assert!(!ids.is_empty());
+            assert!(ids.iter().all(|id| ![1, 254, 255].contains(id)));
+        }
+    }
+
+    #[derive(Clone, Default)]
+    struct SharedBuffer(Arc<Mutex<Vec<u8>>>);
+
+    impl Write for SharedBuffer {
+        fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+            self.0.lock().unwrap().extend_from_slice(buf);
+            Ok(buf.len())
+        }
+
+        fn flush(&mut self) -> io::Result<()> {
+            Ok(())
+        }
+    }
+
+    #[async_std::test]
+    async fn silent_agent_prints_nothing() {
+        let buffer = SharedBuffer::default();
+        let agent = Agent::with_runner(Policy::default(), mock_runner())
+            .with_verbosity(Verbosity::Silent)
+            .with_output(buffer.clone());
+        agent.tick(0).await.verdict.unwrap();
+        assert!(buffer.0.lock().unwrap().is_empty());
+
+        let buffer = SharedBuffer::default();
+        let agent = Agent::with_runner(Policy::default(), mock_runner())
+            .with_verbosity(Verbosity::Evaluations)
+            .with_output(buffer.clone());
+        agent.tick(0).await.verdict.unwrap();
+        let output = String::from_utf8(buffer.0.lock().unwrap().clone()).unwrap();
+        assert_eq!(output.lines().count(), 73);
+        assert!(output.contains("C1: "));
+        assert!(output.contains("P1: insufficient data"));
+    }
+
+    #[async_std::test]
+    async fn bounded_concurrent_commands() {
+        let max_running = |max_concurrent_commands| async move {
+            let runner = CountingRunner::new(mock_runner());
+            let policy = Policy {
+                max_concurrent_commands,
+                ..Policy::default()
+            };
+            let agent = Agent::with_runner(policy, runner.clone());
+            agent.tick(0).await.verdict.unwrap();
+            runner.max_running()
+        };
+        // The status, the certificate list and then the dumps overlap when unbounded
+        assert!(max_running(None).await > 1);
+        assert_eq!(max_running(Some(1)).await, 1);
+    }
+
+    #[async_std::test]
+    async fn compliance_exit_codes() {
+        let report = |verdict| TickReport {
+            index: 0,
+            evaluations: Vec::new(),
+            properties: BTreeMap::new(),
+            verdict,
+            score: None,
+        };
+        let error = Err(Error::EvaluationError("P1 failed".to_string()));
+        for (verdict, compliance, code) in [
+            (Ok(Some(true)), Compliance::Compliant, 0),
+            (Ok(Some(false)), Compliance::NonCompliant, 1),
+            (Ok(None), Compliance::InsufficientData, 2),
+            (error, Compliance::Error, 3),
+        ] {
+            assert_eq!(report(verdict).compliance(), compliance);
+            assert_eq!(compliance.exit_code(), code);
+        }
+
+        // A first tick cannot fill the windows of the rules
+        let agent = Agent::with_runner(Policy::default(), mock_runner());
+        let compliance = agent.tick(0).await.compliance();
+        assert_eq!(compliance, Compliance::InsufficientData);
+    }
+
+    #[async_std::test]
+    async fn pin_trust_anchors() {
+        // Both mocked identities hold a certificate signed by /bertof
+        for (anchor, expected) in [("/bertof", true), ("/ndn", false)].iter() {
+            let policy = Policy {
+                trust_anchors: vec![anchor.to_string()],
+                ..Policy::default()
+            };
+            let agent = Agent::with_runner(policy, mock_runner());
+            assert_eq!(value_of(&agent, Tasks::C18).await, Some(*expected));
+        }
+    }
+
+    #[async_std::test]
+    async fn skip_identities_without_default_certificate() {
+        let runner = mock_runner().with_output(
+            &ndnsec::NdnSecCommand::Dump("/test".to_string()),
+            Err(command::Error::ExitCode(
+                1,
+                "ERROR: No default certificate for key `/test/KEY/%A8C%0C%13%ADd%3B%9B`"
+                    .to_string(),
+            )),
+        );
+        let agent = Agent::with_runner(Policy::default(), runner);
+        agent.tick(0).await.verdict.unwrap();
+        {
+            let logs = agent.logs();
+            let logs = logs.read().unwrap();
+            match &logs.measurements_index[&Metrics::M11][0].1 {
+                Data::M11(validities) => {
+                    let bertof = "/bertof/KEY/U%A8%3E%D3%D7%EF%F37/self/%FD%00%00%01p%99%1E%FC%F7";
+                    assert_eq!(validities.keys().collect::<Vec<_>>(), [bertof])
+                }
+                data => panic!("Unexpected data {:?}", data),
+            }
+        }
+
+        let runner = mock_runner().with_output(
+            &ndnsec::NdnSecCommand::Dump("/test".to_string()),
+            Err(command::Error::ExitCode(
+                1,
+                "ERROR: Cannot open PIB".to_string(),
+            )),
+        );
+        let agent = Agent::with_runner(Policy::default(), runner);
+        assert!(agent.tick(0).await.verdict.is_err());
+    }
+
+    #[async_std::test]
+    async fn allowed_cs_policies() {
+        let report = include_str!("command/nfdc_report.xml").replace(
+            "<policyName>lru</policyName>",
+            "<policyName>priority_fifo</policyName>",
+        );
+        for (allowed, expected) in
+            [(vec!["lru"], false), (vec!["lru", " Priority_FIFO "], true)].iter()
+        {
+            let policy = Policy {
+                cs_policies: allowed.iter().map(|p| p.to_string()).collect(),
+                ..Policy::default()
+            };
+            let runner = mock_runner().with_output(&nfdc::NfdcCommand::Status, Ok(report.clone()));
+            let agent = Agent::with_runner(policy, runner);
+            assert_eq!(value_of(&agent, Tasks::C1).await, Some(*expected));
+        }
+    }
+
+    #[async_std::test]
+    async fn testbed_logs_per_node() {
+        let report = include_str!("command/nfdc_report.xml").replace(
+            "<policyName>lru</policyName>",
+            "<policyName>priority_fifo</policyName>",
+        );
+        let fifo = mock_runner().with_output(&nfdc::NfdcCommand::Status, Ok(report));
+        let testbed = Testbed::with_agents(vec![
+            (
+                "router1".to_string(),
+                Agent::with_runner(Policy::default(), mock_runner()),
+            ),
+            (
+                "router2".to_string(),
+                Agent::with_runner(Policy::default(), fifo),
+            ),
+        ]);
+        testbed.tick(0).await;
+        let reports = testbed.tick(1).await;
+        assert!(reports.values().all(|r| r.index == 1 && r.verdict.is_ok()));
+
+        let logs = testbed.logs();
+        assert_eq!(logs.keys().collect::<Vec<_>>(), ["router1", "router2"]);
+        for (id, policy_name) in [("router1", "lru"), ("router2", "priority_fifo")].iter() {
+            let logs = logs[*id].read().unwrap();
+            let measurements = &logs.measurements_index[&Metrics::M1];
+            assert_eq!(measurements.len(), 2);
+            assert_eq!(measurements[1].1, Data::M1(policy_name.to_string()));
+        }
+    }
+
+    #[async_std::test]
+    async fn testbed_reads_node_memory() {
+        // Odd sizes, that the host running the test cannot have
+        let node = mock_runner()
+            .with_output(
+                &HostCommand::MemInfo,
+                Ok("MemTotal:        4194309 kB\n".to_string()),
+            )
+            .with_output(
+                &HostCommand::ResidentMemory(NFD_PROCESS.to_string()),
+                Ok("  65539\n".to_string()),
+            );
+        let testbed = Testbed::with_agents(vec![(
+            "router1".to_string(),
+            Testbed::node_agent(Policy::default(), node),
+        )]);
+        let reports = testbed.tick(0).await;
+        assert!(reports["router1"].verdict.is_ok());
+
+        let logs = testbed.logs();
+        let logs = logs["router1"].read().unwrap();
+        let total = &logs.measurements_index[&Metrics::M13][0].1;
+        assert_eq!(*total, Data::M13(4194309 << 10));
+        assert_ne!(
+            Some(total),
+            host_total_memory().ok().map(Data::M13).as_ref()
+        );
+        let resident = &logs.measurements_index[&Metrics::M30][0].1;
+        assert_eq!(*resident, Data::M30(Some(65539 << 10)));
+    }
+
+    #[async_std::test]
+    async fn testbed_shutdown_cancels_tick() {
+        let testbed = Testbed::with_agents(vec![
+            (
+                "router1".to_string(),
+                Agent::with_runner(Policy::default(), mock_runner()),
+            ),
+            (
+                "router2".to_string(),
+                Agent::with_runner(Policy::default(), mock_runner()),
+            ),
+        ]);
+        let outcomes = testbed.next_tick(0).await;
+        assert!(outcomes
+            .values()
+            .all(|outcome| matches!(outcome, TickOutcome::Completed(_))));
+
+        testbed.shutdown().request();
+        let outcomes = testbed.next_tick(1).await;
+        assert_eq!(outcomes.len(), 2);
+        assert!(outcomes
+            .values()
+            .all(|outcome| matches!(outcome, TickOutcome::Cancelled)));
+        for logs in testbed.logs().values() {
+            let logs = logs.read().unwrap();
+            assert_eq!(logs.measurements_index[&Metrics::M1].len(), 1);
+        }
+    }
+
+    #[async_std::test]
+    async fn cs_serving_interests() {
+        // The mocked report has 0 hits and 64 misses
+        let previous = |start_time: &str, n_hits, n_misses| {
+            let counters = CsCounters {
+                start_time: start_time.to_string(),
+                n_hits,
+                n_misses,
+            };
+            Measurement::new(Data::M19(counters), 0)
+        };
+        let start_time = "2021-04-21T15:08:41.222000";
+        let report =
+            include_str!("command/nfdc_report.xml").replace("<nHits>0</nHits>", "<nHits>5</nHits>");
+        for (previous, report, expected) in [
+            (None, None, None),
+            (Some(previous(start_time, 0, 64)), None, Some(true)),
+            (Some(previous(start_time, 0, 10)), None, Some(false)),
+            (Some(previous(start_time, 0, 10)), Some(&report), Some(true)),
+            (Some(previous(start_time, 0, 100)), None, None),
+            (Some(previous("2021-04-20T10:00:00", 0, 10)), None, None),
+        ]
+        .iter()
+        {
+            let runner = match report {
+                Some(report) => {
+                    mock_runner().with_output(&nfdc::NfdcCommand::Status, Ok(report.to_string()))
+                }
+                None => mock_runner(),
+            };
+            let agent = Agent::with_runner(Policy::default(), runner);
+            if let Some(previous) = previous {
+                let logs = agent.logs();
+                logs.write()
+                    .unwrap()
+                    .insert_measurement(previous.clone(), Metrics::M19);
+            }
+            assert_eq!(value_of(&agent, Tasks::C20).await, *expected);
+        }
+    }
+
+    #[test]
+    fn windowed_cs_hits() {
+        let sample = |start_time: &str, n_hits, n_misses| CsCounters {
+            start_time: start_time.to_string(),
+            n_hits,
+            n_misses,
+        };
+        let first = "2021-04-21T15:08:41.222000";
+        let restart = "2021-04-21T16:00:00.000000";
+        assert_eq!(CsCounters::windowed(&[]), None);
+        assert_eq!(CsCounters::windowed(&[sample(first, 0, 10)]), None);
+        assert_eq!(
+            CsCounters::windowed(&[sample(first, 0, 10), sample(first, 2, 20)]),
+            Some((2, 12))
+        );
+        let samples = [
+            sample(first, 0, 10),
+            sample(first, 2, 20),
+            sample(restart, 0, 5),
+            sample(restart, 3, 10),
+        ];
+        assert_eq!(CsCounters::windowed(&samples), Some((5, 20)));
+        assert_eq!(
+            CsCounters::windowed(&[sample(first, 2, 20), sample(restart, 0, 5)]),
+            None
+        );
+    }
+
+    #[async_std::test]
+    async fn read_total_memory_once() {
+        use std::sync::atomic::{AtomicUsize, Ordering};
+        let reads = Arc::new(AtomicUsize::new(0));
+        let counter = reads.clone();
+        let total_memory = TotalMemory::new(move || match counter.fetch_add(1, Ordering::SeqCst) {
+            0 => Err(Error::TaskError(command::Error::OutputError(
+                "unavailable".to_string(),
+            ))),
+            _ => Ok(8 << 30),
+        });
+        let agent =
+            Agent::with_runner(Policy::default(), mock_runner()).with_total_memory(total_memory);
+        for index in 0..4 {
+            agent.tick(index).await;
+        }
+        assert_eq!(reads.load(Ordering::SeqCst), 2);
+        let logs = agent.logs();
+        let logs = logs.read().unwrap();
+        let totals = &logs.measurements_index[&Metrics::M13];
+        assert_eq!(totals.len(), 3);
+        assert!(totals.iter().all(|(_, data)| *data == Data::M13(8 << 30)));
+    }
+
+    #[async_std::test]
+    async fn failing_constraints_of_rule() {
+        // Without memory, the CS entries do not fit and only C2 fails
+        let total_memory = TotalMemory::new(|| Ok(0));
+        let agent =
+            Agent::with_runner(Policy::default(), mock_runner()).with_total_memory(total_memory);
+        let report = agent.tick(0).await;
+        let evaluation_of = |task| {
+            report
+                .evaluations
+                .iter()
+                .find(|(t, _)| *t == task)
+                .and_then(|(_, e)| e.as_ref().ok())
+                .cloned()
+                .unwrap()
+        };
+        let r1 = evaluation_of(Tasks::R1);
+        assert_eq!(r1.value, Some(false));
+        assert_eq!(r1.failed_dependencies, vec!["C2".to_string()]);
+        assert!(evaluation_of(Tasks::C1).failed_dependencies.is_empty());
+    }
+
+    #[async_std::test]
+    async fn cs_entries_fit_large_sizes() {
+        let fits = |total_memory: u64, cs_entry_size| async move {
+            let policy = Policy {
+                cs_entry_size,
+                memory_headroom: ByteSize(0),
+                ..Policy::default()
+            };
+            let agent = Agent::with_runner(policy, mock_runner())
+                .with_total_memory(TotalMemory::new(move || Ok(total_memory)));
+            value_of(&agent, Tasks::C2).await
+        };
+        assert_eq!(fits(u64::MAX, ByteSize::from_kib(1)).await, Some(true));
+        assert_eq!(fits(u64::MAX, ByteSize(u64::MAX)).await, Some(false));
+        assert_eq!(fits(8 << 30, ByteSize(u64::MAX)).await, Some(false));
+    }
+
+    #[async_std::test]
+    async fn duplicated_identity_certificates() {
+        // The `/test` identity is listed again with another key, e.g. by a split keychain
+        let first = "/test/KEY/%A8C%0C%13%ADd%3B%9B/self/%FD%00%00%01s%BF%E4U%3C";
+        let second = "/test/KEY/%01/self/%FD%01";
+        let list = format!(
+            "{}\n  /test\n  +->* /test/KEY/%01\n       +->* {}\n",
+            include_str!("command/ndnsec/list.txt"),
+            second
+        );
+        let runner = mock_runner()
+            .with_output(&ndnsec::NdnSecCommand::List, Ok(list))
+            .with_output(
+                &ndnsec::NdnSecCommand::DumpCertificate(first.to_string()),
+                Ok(test_dump()),
+            )
+            .with_output(
+                &ndnsec::NdnSecCommand::DumpCertificate(second.to_string()),
+                Ok(test_dump().replacen(first, second, 1)),
+            );
+        let agent = Agent::with_runner(Policy::default(), runner);
+        agent.tick(0).await.verdict.unwrap();
+        let logs = agent.logs();
+        let logs = logs.read().unwrap();
+        match &logs.measurements_index[&Metrics::M11][0].1 {
+            Data::M11(validities) => {
+                assert_eq!(validities.len(), 3);
+                assert!(validities.contains_key(first) && validities.contains_key(second));
+            }
+            data => panic!("Unexpected data {:?}", data),
+        }
+        match &logs.measurements_index[&Metrics::M23][0].1 {
+            Data::M23(keys) => assert_eq!(keys.len(), 3),
+            data => panic!("Unexpected data {:?}", data),
+        }
+    }
+
+    #[async_std::test]
+    async fn filtered_identities() {
+        let expired = test_dump().replace("NotAfter: 20400226T024149", "NotAfter: 20200226T024149");
+        let runner = || {
+            mock_runner().with_output(
+                &ndnsec::NdnSecCommand::Dump("/test".to_string()),
+                Ok(expired.clone()),
+            )
+        };
+        let agent = Agent::with_runner(Policy::default(), runner());
+        assert_eq!(value_of(&agent, Tasks::C13).await, Some(false));
+
+        // The expired certificate of the excluded test identity is ignored
+        let policy = Policy {
+            excluded_identities: vec!["/test".to_string()],
+            ..Policy::default()
+        };
+        let agent = Agent::with_runner(policy, runner());
+        assert_eq!(value_of(&agent, Tasks::C13).await, Some(true));
+        assert_eq!(value_of(&agent, Tasks::C14).await, Some(true));
+
+        // Only the identities under the included prefixes are considered, here none
+        let policy = Policy {
+            included_identities: vec!["/ndn/app".to_string()],
+            ..Policy::default()
+        };
+        let agent = Agent::with_runner(policy, runner());
+        assert_eq!(value_of(&agent, Tasks::C13).await, Some(true));
+        assert_eq!(value_of(&agent, Tasks::C14).await, Some(false));
+    }
+
+    #[async_std::test]
+    async fn bound_nfd_memory() {
+        for (memory, expected) in [
+            (Some(100 << 20), Some(true)),
+            (Some(3 << 30), Some(false)),
+            (None, None),
+        ]
+        .iter()
+        {
+            let memory = *memory;
+            let agent = Agent::with_runner(Policy::default(), mock_runner())
+                .with_nfd_memory(ProcessMemory::new(move || Ok(memory)));
+            assert_eq!(value_of(&agent, Tasks::C32).await, *expected);
+        }
+        let unreadable = ProcessMemory::new(|| {
+            Err(Error::TaskError(command::Error::OutputError(
+                "unavailable".to_string(),
+            )))
+        });
+        let agent =
+            Agent::with_runner(Policy::default(), mock_runner()).with_nfd_memory(unreadable);
+        let evaluations = agent.evaluations(0).collect::<Vec<_>>().await;
+        let (_, c32) = evaluations
+            .into_iter()
+            .find(|(task, _)| *task == Tasks::C32)
+            .unwrap();
+        assert!(c32.is_err());
+    }
+
+    #[test]
+    fn configurable_stability_window() {
+        let mut logs = Logs::default();
+        for (index, usage) in [0, 20, 100, 101, 103].iter().enumerate() {
+            let measurement = Measurement::new(Data::M3(*usage), index as u64);
+            logs.insert_measurement(measurement, Metrics::M3);
+        }
+        let stable = |policy: &Policy, index| is_stable(&logs, &Metrics::M3, index, policy);
+        let mut policy = Policy::default();
+        assert!(!stable(&policy, 3));
+        assert!(!stable(&policy, 4));
+
+        policy.stability_window = 3;
+        assert!(!stable(&policy, 1));
+        assert!(stable(&policy, 4));
+        assert!(!is_stable(&logs, &Metrics::M4, 4, &policy));
+
+        policy.max_stability_std_dev = 1.5;
+        assert!(!stable(&policy, 4));
+        policy.stability_window = 2;
+        assert!(!stable(&policy, 0));
+        assert!(stable(&policy, 1));
+    }
+
+    #[async_std::test]
+    async fn bound_keychain_identities() {
+        // The mocked keychain holds /test and /bertof
+        for (min, max, expected) in [(1, 16, true), (3, 16, false), (1, 1, false)].iter() {
+            let policy = Policy {
+                min_identities: *min,
+                max_identities: *max,
+                ..Policy::default()
+            };
+            let agent = Agent::with_runner(policy, mock_runner());
+            assert_eq!(value_of(&agent, Tasks::C21).await, Some(*expected));
+        }
+        let agent = Agent::with_runner(Policy::default(), mock_runner());
+        agent.tick(0).await.verdict.unwrap();
+        let logs = agent.logs();
+        let logs = logs.read().unwrap();
+        assert_eq!(logs.measurements_index[&Metrics::M20][0].1, Data::M20(2));
+    }
+
+    #[test]
+    fn dumped_policy_round_trip() {
+        let policy = Policy {
+            trust_anchors: vec!["/ndn".to_string()],
+            ..Policy::default()
+        };
+        let dump = serde_json::to_string(&EffectiveConfig::from(policy.clone())).unwrap();
+        let value = serde_json::from_str::<serde_json::Value>(&dump).unwrap();
+        assert_eq!(value["nfdcBinary"], "/usr/bin/nfdc");
+        assert_eq!(value["commandTimeoutMs"], 1000);
+        assert_eq!(value["dumpTimeoutMs"], 1000);
+        assert_eq!(value["minSamples"], 5);
+        assert_eq!(serde_json::from_str::<Policy>(&dump).unwrap(), policy);
+        let config = serde_json::from_str::<EffectiveConfig>(&dump).unwrap();
+        assert_eq!(config, EffectiveConfig::from(policy.clone()));
+
+        let mut policy = policy;
+        policy.commands.dump_timeout_ms = Some(5000);
+        let config = EffectiveConfig::from(policy);
+        assert_eq!(config.command_timeout_ms, 1000);
+        assert_eq!(config.dump_timeout_ms, 5000);
+    }
+
+    #[async_std::test]
+    async fn weighted_compliance_score() {
+        // The mocked CS runs lru, so C1 fails while C3 holds
+        let policy = Policy {
+            cs_policies: vec!["priority_fifo".to_string()],
+            task_weights: vec![(Tasks::C1, 3.0), (Tasks::C3, 1.0), (Tasks::P1, 4.0)]
+                .into_iter()
+                .collect(),
+            ..Policy::default()
+        };
+        let agent = Agent::with_runner(policy, mock_runner());
+        let report = agent.tick(0).await;
+        let value = |task| {
+            let (_, evaluation) = report.evaluations.iter().find(|(t, _)| *t == task).unwrap();
+            evaluation.as_ref().unwrap().value
+        };
+        assert_eq!(value(Tasks::C1), Some(false));
+        assert_eq!(value(Tasks::C3), Some(true));
+        // P1 is undecided during warm-up and left out
+        assert_eq!(value(Tasks::P1), None);
+        assert_eq!(report.score, Some(25.0));
+        assert_eq!(agent.logs().read().unwrap().scores_index[&0], 25.0);
+
+        let agent = Agent::with_runner(Policy::default(), mock_runner());
+        let report = agent.tick(0).await;
+        let constraints = report
+            .evaluations
+            .iter()
+            .filter(|(task, _)| !task.metrics().is_empty())
+            .filter_map(|(_, e)| e.as_ref().unwrap().value)
+            .collect::<Vec<_>>();
+        let passing = constraints.iter().filter(|value| **value).count();
+        assert_eq!(
+            report.score,
+            Some(100.0 * passing as f64 / constraints.len() as f64)
+        );
+    }
+
+    #[async_std::test]
+    async fn detect_face_leaks() {
+        // The mocked report lists 22 faces
+        for (counts, expected) in [
+            (vec![], None),
+            (vec![22], None),
+            (vec![10, 12, 14, 16, 18, 20], Some(false)),
+            (vec![10, 10, 10, 22, 22, 22], Some(true)),
+            (vec![22, 22, 22, 22, 22, 22], Some(true)),
+        ]
+        .iter()
+        {
+            let agent = Agent::with_runner(Policy::default(), mock_runner());
+            {
+                let logs = agent.logs();
+                let mut logs = logs.write().unwrap();
+                for (index, count) in counts.iter().enumerate() {
+                    let measurement = Measurement::new(Data::M21(*count), index as u64);
+                    logs.insert_measurement(measurement, Metrics::M21);
+                }
+            }
+            assert_eq!(value_of(&agent, Tasks::C22).await, *expected);
+        }
+    }
+
+    #[async_std::test]
+    async fn detect_stale_nfd() {
+        // Every tick of the mocked runner reports the same current time
+        let policy = Policy {
+            max_stale_ticks: 1,
+            ..Policy::default()
+        };
+        let agent = Agent::with_runner(policy, mock_runner());
+        let values = |report: &TickReport| {
+            report
+                .evaluations
+                .iter()
+                .find(|(task, _)| *task == Tasks::C23)
+                .map(|(_, e)| e.as_ref().unwrap().value)
+                .unwrap()
+        };
+        assert_eq!(values(&agent.tick(0).await), Some(true));
+        assert_eq!(values(&agent.tick(1).await), Some(true));
+        assert_eq!(values(&agent.tick(2).await), Some(false));
+
+        let agent = Agent::with_runner(Policy::default(), mock_runner());
+        {
+            let logs = agent.logs();
+            let mut logs = logs.write().unwrap();
+            let start = DateTime::parse_from_rfc3339("2021-04-21T15:11:24Z").unwrap();
+            for index in 0..3 {
+                let time = start.with_timezone(&Utc) + chrono::Duration::seconds(index);
+                let measurement = Measurement::new(Data::M22(time), index as u64);
+                logs.insert_measurement(measurement, Metrics::M22);
+            }
+        }
+        assert_eq!(value_of(&agent, Tasks::C23).await, Some(true));
+    }
+
+    #[async_std::test]
+    async fn approve_key_strength() {
+        // The mocked certificates hold RSA-2048 keys
+        let agent = Agent::with_runner(Policy::default(), mock_runner());
+        assert_eq!(value_of(&agent, Tasks::C24).await, Some(true));
+        let policy = Policy {
+            min_rsa_key_bits: 3072,
+            ..Policy::default()
+        };
+        let agent = Agent::with_runner(policy, mock_runner());
+        assert_eq!(value_of(&agent, Tasks::C24).await, Some(false));
+    }
+
+    #[async_std::test]
+    async fn record_source_digests() {
+        let policy = Policy {
+            record_source_digests: true,
+            ..Policy::default()
+        };
+        let first = nfdc_status(&mock_runner(), &policy).await.unwrap();
+        let second = nfdc_status(&mock_runner(), &policy).await.unwrap();
+        let digest = sha256_hex(include_str!("command/nfdc_report.xml").as_bytes());
+        assert_eq!(first.source_digest, Some(digest.clone()));
+        assert_eq!(first.source_digest, second.source_digest);
+        let unrecorded = nfdc_status(&mock_runner(), &Policy::default()).await;
+        assert_eq!(unrecorded.unwrap().source_digest, None);
+
+        let agent = Agent::with_runner(policy, mock_runner());
+        agent.tick(0).await.verdict.unwrap();
+        let table = agent.logs().read().unwrap().to_table();
+        assert_eq!(table.source_digests_index[&Metrics::M2][&0], digest);
+        let json = serde_json::to_value(&table).unwrap();
+        assert_eq!(json["source_digests_index"]["M2"]["0"], digest);
+        let agent = Agent::with_runner(Policy::default(), mock_runner());
+        agent.tick(0).await.verdict.unwrap();
+        assert!(agent.logs().read().unwrap().source_digests_index.is_empty());
+    }
+
+    #[async_std::test]
+    async fn general_status_over_management_socket() {
+        let path = std::env::temp_dir().join(format!("ca-agent-nfd-{}.sock", std::process::id()));
+        let _ = std::fs::remove_file(&path);
+        let listener = async_std::os::unix::net::UnixListener::bind(&path)
+            .await
+            .unwrap();
+        async_std::task::spawn(async move {
+            let mut incoming = listener.incoming();
+            while let Some(Ok(mut stream)) = incoming.next().await {
+                // Answered once the interest is in, closing before would break the pipe
+                let mut interest = [0; 128];
+                stream.read(&mut interest).await.unwrap();
+                let dataset = include_bytes!("command/nfd_general_status.tlv");
+                stream.write_all(dataset).await.unwrap();
+            }
+        });
+        let commands = CommandConfig {
+            status_source: StatusSource::Management,
+            nfd_socket: path.to_string_lossy().to_string(),
+            ..CommandConfig::default()
+        };
+        let policy = Policy {
+            commands,
+            ..Policy::default()
+        };
+        let agent = Agent::with_runner(policy.clone(), mock_runner());
+        agent.tick(0).await.verdict.unwrap();
+        let nfdc = Agent::with_runner(Policy::default(), mock_runner());
+        nfdc.tick(0).await.verdict.unwrap();
+        for metric in [Metrics::M15, Metrics::M22].iter() {
+            let data =
+                |agent: &Agent| agent.logs().read().unwrap().measurements_index[metric][0].clone();
+            assert_eq!(data(&agent), data(&nfdc));
+        }
+
+        // The general status metrics fail without a forwarder on the socket
+        std::fs::remove_file(&path).unwrap();
+        let agent = Agent::with_runner(policy, mock_runner());
+        let evaluations = agent.evaluations(0).collect::<Vec<_>>().await;
+        assert!(evaluations.iter().any(|(_, e)| e.is_err()));
+    }
+
+    #[async_std::test]
+    async fn face_labels() {
+        let agent = Agent::with_runner(Policy::default(), mock_runner());
+        agent.tick(0).await;
+        let logs = agent.logs();
+        let logs = logs.read().unwrap();
+        let labels = match &logs.measurements_index[&Metrics::M37][0] {
+            (0, Data::M37(labels)) => labels.clone(),
+            other => panic!("{:?}", other),
+        };
+        let snapshot: serde_json::Value =
+            serde_json::from_str(include_str!("command/snapshots/nfdc_report.json")).unwrap();
+        let faces = snapshot["faces"]["face"].as_array().unwrap();
+        let expected = faces
+            .iter()
+            .map(|face| {
+                let face_id = face["faceId"].as_u64().unwrap();
+                (face_id, face["remoteUri"].as_str().unwrap().to_string())
+            })
+            .collect::<HashMap<_, _>>();
+        assert_eq!(labels, expected);
+        assert_eq!(labels[&1], "internal://");
+
+        // The labels join the per-face metrics by face id, from the same status
+        let pit_entries = match &logs.measurements_index[&Metrics::M6][0] {
+            (0, Data::M6(pit_entries)) => pit_entries,
+            other => panic!("{:?}", other),
+        };
+        assert!(pit_entries
+            .keys()
+            .all(|face_id| labels.contains_key(face_id)));
+        assert_eq!(
+            logs.source_digests_index.get(&Metrics::M37),
+            logs.source_digests_index.get(&Metrics::M6)
+        );
+        assert!(logs.records().iter().any(|record| record.metric == "M37"));
+    }
+
+    #[async_std::test]
+    async fn replay_recorded_tick() {
+        let dir = std::env::temp_dir().join(format!("ca-replay-{}", std::process::id()));
+        let _ = std::fs::remove_dir_all(&dir);
+        let runner = RecordingRunner::new(mock_runner(), &dir).unwrap();
+        let recorded = Agent::with_runner(Policy::default(), runner);
+        recorded.tick(0).await;
+        let replayed = Agent::with_runner(Policy::default(), ReplayRunner::open(&dir).unwrap());
+        replayed.tick(0).await;
+        std::fs::remove_dir_all(&dir).unwrap();
+
+        let recorded = recorded.logs().read().unwrap().measurements_index.clone();
+        let replayed = replayed.logs().read().unwrap().measurements_index.clone();
+        assert!(recorded.contains_key(&Metrics::M1) && recorded.contains_key(&Metrics::M23));
+        // The tick durations are the only measurements that are not command outputs. The JSON
+        // values compare the averages of the faces without packets, which are NaN
+        for (metric, measurements) in recorded.iter().filter(|(m, _)| **m != Metrics::M18) {
+            assert_eq!(
+                serde_json::to_value(measurements).unwrap(),
+                serde_json::to_value(&replayed[metric]).unwrap(),
+                "{:?}",
+                metric
+            );
+        }
+    }
+
+    #[async_std::test]
+    async fn command_completion_times() {
+        let before = Utc::now();
+        let mut status = nfdc_status(&mock_runner(), &Policy::default())
+            .await
+            .unwrap();
+        let list = ndnsec_list(&mock_runner(), &Policy::default())
+            .await
+            .unwrap();
+        let (nfdc_completed_at, list_completed_at) =
+            (status.completed_at.unwrap(), list.completed_at.unwrap());
+        assert!(before <= nfdc_completed_at && nfdc_completed_at <= list_completed_at);
+
+        // The nfdc report of the tick is two seconds older than the ndnsec list
+        status.completed_at = Some(list_completed_at - chrono::Duration::seconds(2));
+        let nfdc_status = async { Ok(status.clone()) };
+        let Logging(cs_policy, _) = m1(nfdc_status, 0, Logs::default()).await.unwrap();
+        let ndnsec_list = async { Ok(list.clone()) };
+        let policy = Policy::default();
+        let Logging(default, _) = m12(ndnsec_list, &policy, 0, Logs::default()).await.unwrap();
+        assert_eq!(cs_policy.completed_at, status.completed_at);
+        assert_eq!(default.completed_at, list.completed_at);
+        assert!(cs_policy.completed_at.unwrap() < cs_policy.timestamp);
+
+        let json = serde_json::to_value(&cs_policy).unwrap();
+        assert_eq!(
+            json["completed_at"],
+            serde_json::to_value(status.completed_at).unwrap()
+        );
+        let json = serde_json::to_value(Measurement::new(Data::M2(1), 0)).unwrap();
+        assert!(json.get("completed_at").is_none());
+
+        // The completion times are kept in the logs, and written in every format
+        let completed_at = serde_json::to_value(status.completed_at).unwrap();
+        let mut logs = Logs::default();
+        logs.insert_measurement(cs_policy, Metrics::M1);
+        let written = |format: OutputFormat| {
+            let mut bytes = Vec::new();
+            format.write(&logs, &mut bytes).unwrap();
+            bytes
+        };
+        let json: serde_json::Value = serde_json::from_slice(&written(OutputFormat::Json)).unwrap();
+        assert_eq!(json["completed_at_index"]["M1"]["0"], completed_at);
+        let line = String::from_utf8(written(OutputFormat::JsonLines)).unwrap();
+        let record: serde_json::Value = serde_json::from_str(&line).unwrap();
+        assert_eq!(record["completed_at"], completed_at);
+        let csv = String::from_utf8(written(OutputFormat::Csv)).unwrap();
+        let row = csv.lines().nth(1).unwrap();
+        let rfc3339 = status.completed_at.unwrap().to_rfc3339();
+        assert_eq!(row.split(',').nth(3), Some(rfc3339.as_str()));
+    }
+
+    #[async_std::test]
+    async fn default_identity_with_valid_certificate() {
+        let agent = Agent::with_runner(Policy::default(), mock_runner());
+        assert_eq!(value_of(&agent, Tasks::C25).await, Some(true));
+
+        // The default identity is /bertof, the certificate of /test stays valid
+        let expired = include_str!("command/ndnsec/dump.txt")
+            .replace("NotAfter: 20400226T024149", "NotAfter: 20200226T024149");
+        let runner = mock_runner().with_output(
+            &ndnsec::NdnSecCommand::Dump("/bertof".to_string()),
+            Ok(expired),
+        );
+        let agent = Agent::with_runner(Policy::default(), runner);
+        assert_eq!(value_of(&agent, Tasks::C25).await, Some(false));
+    }
+
+    #[async_std::test]
+    async fn lenient_parsing() {
+        let runner = || {
+            mock_runner().with_output(
+                &nfdc::NfdcCommand::Status,
+                Ok("<nfdStatus><generalStatus>".to_string()),
+            )
+        };
+        let agent = Agent::with_runner(Policy::default(), runner());
+        let report = agent.tick(0).await;
+        assert!(report.verdict.is_err());
+
+        let policy = Policy {
+            lenient_parsing: true,
+            ..Policy::default()
+        };
+        let agent = Agent::with_runner(policy, runner());
+        let report = agent.tick(0).await;
+        assert!(report.verdict.is_ok());
+        let evaluation = |task: Tasks| {
+            report
+                .evaluations
+                .iter()
+                .find(|(t, _)| *t == task)
+                .map(|(_, e)| e.clone().unwrap())
+                .unwrap()
+        };
+        assert!(evaluation(Tasks::C3).is_skipped());
+        assert_eq!(evaluation(Tasks::C14).value, Some(true));
+        assert_eq!(evaluation(Tasks::C26).value, Some(false));
+
+        let logs = agent.logs();
+        let logs = logs.read().unwrap();
+        assert!(matches!(
+            logs.measurements_index[&Metrics::M2][0].1,
+            Data::ParseError(_)
+        ));
+        assert!(matches!(
+            logs.measurements_index[&Metrics::M12][0].1,
+            Data::M12(Some(_))
+        ));
+        assert!(matches!(
+            logs.measurements_index[&Metrics::M25][0].1,
+            Data::M25(1)
+        ));
+    }
+
+    #[async_std::test]
+    async fn empty_faces() {
+        let agent = Agent::with_runner(Policy::default(), mock_runner());
+        assert_eq!(value_of(&agent, Tasks::C27).await, Some(true));
+
+        let runner = mock_runner().with_output(
+            &nfdc::NfdcCommand::Status,
+            Ok(include_str!("command/nfdc_report_no_faces.xml").to_string()),
+        );
+        let agent = Agent::with_runner(Policy::default(), runner);
+        assert_eq!(value_of(&agent, Tasks::C27).await, Some(false));
+        for task in [Tasks::C8, Tasks::C9, Tasks::C10, Tasks::C11, Tasks::C12].iter() {
+            assert_eq!(value_of(&agent, task.clone()).await, None, "{:?}", task);
+        }
+    }
+
+    #[async_std::test]
+    async fn cs_usage_percent() {
+        let usage_of = |report: String| async {
+            let runner = mock_runner().with_output(&nfdc::NfdcCommand::Status, Ok(report));
+            let agent = Agent::with_runner(Policy::default(), runner);
+            agent.tick(0).await;
+            let logs = agent.logs();
+            let logs = logs.read().unwrap();
+            let usage = logs.measurements_index[&Metrics::M27][0].1.clone();
+            let evaluation = logs.evaluations_index[&Tasks::C29][0].1;
+            (usage, evaluation)
+        };
+        let report = include_str!("command/nfdc_report.xml");
+        let (usage, evaluation) = usage_of(report.to_string()).await;
+        assert_eq!(usage, Data::M27(Some(2.0 / 300.0 * 100.0)));
+        assert_eq!(usage.as_f64(), Some(2.0 / 300.0 * 100.0));
+        assert_eq!(evaluation, Some(true));
+
+        let no_capacity = report.replace("<capacity>300</capacity>", "<capacity>0</capacity>");
+        let (usage, evaluation) = usage_of(no_capacity).await;
+        assert_eq!(usage, Data::M27(None));
+        assert_eq!(evaluation, None);
+
+        let overfull = report.replace("<nEntries>2</nEntries>", "<nEntries>301</nEntries>");
+        assert_eq!(usage_of(overfull).await.1, Some(false));
+    }
+
+    #[async_std::test]
+    async fn required_namespace_strategies() {
+        let strategies_of = |report: String| async {
+            let runner = mock_runner().with_output(&nfdc::NfdcCommand::Status, Ok(report));
+            let mut policy = Policy::default();
+            policy.required_strategies.insert(
+                "/ndn/broadcast/sync".to_string(),
+                "/localhost/nfd/strategy/multicast/%FD%03".to_string(),
+            );
+            let agent = Agent::with_runner(policy, runner);
+            agent.tick(0).await;
+            let logs = agent.logs();
+            let logs = logs.read().unwrap();
+            let strategies = logs.measurements_index[&Metrics::M28][0].1.clone();
+            let evaluation = logs.evaluations_index[&Tasks::C30][0].1;
+            (strategies, evaluation)
+        };
+        let report = include_str!("command/nfdc_report.xml");
+        let (strategies, evaluation) = strategies_of(report.to_string()).await;
+        let multicast = "/localhost/nfd/strategy/multicast/%FD%04".to_string();
+        let expected = vec![("/ndn/broadcast/sync".to_string(), Some(multicast))];
+        assert_eq!(strategies, Data::M28(expected.into_iter().collect()));
+        assert_eq!(evaluation, Some(true));
+
+        // Without its own strategy choice, the broadcast prefix falls back to best-route on `/`
+        let fallback = report.replace(
+            "<namespace>/ndn/broadcast</namespace>",
+            "<namespace>/ndn/unicast</namespace>",
+        );
+        let (strategies, evaluation) = strategies_of(fallback).await;
+        let best_route = "/localhost/nfd/strategy/best-route/%FD%05".to_string();
+        let expected = vec![("/ndn/broadcast/sync".to_string(), Some(best_route))];
+        assert_eq!(strategies, Data::M28(expected.into_iter().collect()));
+        assert_eq!(evaluation, Some(false));
+    }
+
+    #[async_std::test]
+    async fn required_channel_schemes() {
+        let channels_hold = |report: String| async {
+            let runner = mock_runner().with_output(&nfdc::NfdcCommand::Status, Ok(report));
+            let policy = Policy {
+                required_channel_schemes: vec!["udp6".into(), "ws".into(), "unix".into()],
+                ..Policy::default()
+            };
+            let agent = Agent::with_runner(policy, runner);
+            value_of(&agent, Tasks::C33).await
+        };
+        let report = include_str!("command/nfdc_report.xml");
+        assert_eq!(channels_hold(report.to_string()).await, Some(true));
+
+        // Without WebSocket channels, the required `ws` transport is missing
+        let without_ws = report
+            .replace(
+                "<channel><localUri>ws://0.0.0.0:9696</localUri></channel>",
+                "",
+            )
+            .replace("<channel><localUri>ws://[::]:9696</localUri></channel>", "");
+        assert_eq!(channels_hold(without_ws).await, Some(false));
+    }
+
+    #[async_std::test]
+    async fn management_fib_prefixes() {
+        let prefixes_hold = |report: String, policy: Policy| async {
+            let runner = mock_runner().with_output(&nfdc::NfdcCommand::Status, Ok(report));
+            let agent = Agent::with_runner(policy, runner);
+            value_of(&agent, Tasks::C38).await
+        };
+        let report = include_str!("command/nfdc_report.xml");
+        assert_eq!(
+            prefixes_hold(report.to_string(), Policy::default()).await,
+            Some(true)
+        );
+
+        // Without the RIB manager, its prefix is missing from the FIB
+        let without_rib = report.replace(
+            "<fibEntry><prefix>/localhost/nfd/rib</prefix><nextHops><nextHop><faceId>264</faceId>\
+             <cost>0</cost></nextHop></nextHops></fibEntry>",
+            "",
+        );
+        assert_ne!(without_rib, report);
+        assert_eq!(
+            prefixes_hold(without_rib.clone(), Policy::default()).await,
+            Some(false)
+        );
+        let policy = Policy {
+            required_fib_prefixes: vec!["/localhost/nfd".to_string()],
+            ..Policy::default()
+        };
+        assert_eq!(prefixes_hold(without_rib, policy).await, Some(true));
+    }
+
+    #[async_std::test]
+    async fn chain_depth_bound() {
+        // /a signed by /b, signed by the self-signed /c
+        let issuers = [("/a", "/b"), ("/b", "/c"), ("/c", "/c")]
+            .iter()
+            .map(|(i, s)| (i.to_string(), s.to_string()))
+            .collect::<HashMap<_, _>>();
+        let depths = issuers
+            .keys()
+            .map(|i| (i.clone(), chain_depth(i, &issuers) as u64))
+            .collect::<HashMap<_, _>>();
+        assert_eq!(depths["/a"], 3);
+        let within = |max_chain_depth| {
+            let measurement = Measurement::new(Data::M32(depths.clone()), 0);
+            async move {
+                let policy = Policy {
+                    max_chain_depth,
+                    ..Policy::default()
+                };
+                let m32 = async { Ok(Logging(measurement, Logs::default())) };
+                c34(m32, &policy, 0).await.unwrap().0.value
+            }
+        };
+        assert_eq!(within(2).await, Some(false));
+        assert_eq!(within(3).await, Some(true));
+    }
+
+    #[async_std::test]
+    async fn expected_mtus() {
+        let faces = [
+            (1, ("point-to-point", 8800)),
+            (2, ("point-to-point", 1500)),
+            (3, ("multi-access", 1500)),
+        ];
+        let policy = Policy {
+            expected_mtus: [("point-to-point", 8800), ("multi-access", 1500)]
+                .iter()
+                .map(|(link_type, mtu)| (link_type.to_string(), *mtu))
+                .collect(),
+            ..Policy::default()
+        };
+        let mtus_hold = |faces: &[(u64, (&str, u64))]| {
+            let faces = faces
+                .iter()
+                .map(|(id, (link_type, mtu))| (*id, (link_type.to_string(), *mtu)))
+                .collect();
+            let measurement = Measurement::new(Data::M33(faces), 0);
+            let policy = policy.clone();
+            async move {
+                let m33 = async { Ok(Logging(measurement, Logs::default())) };
+                c35(m33, &policy, 0).await.unwrap().0.value
+            }
+        };
+        // The point-to-point face 2 has the MTU of an Ethernet link
+        assert_eq!(mtus_hold(&faces).await, Some(false));
+        assert_eq!(mtus_hold(&[faces[0], faces[2]]).await, Some(true));
+
+        // The Ethernet faces without MTU are skipped, the UDP multicast ones have 8800
+        let report = include_str!("command/nfdc_report.xml").replace("<mtu>1500</mtu>", "");
+        let runner = mock_runner().with_output(&nfdc::NfdcCommand::Status, Ok(report));
+        let policy = Policy {
+            expected_mtus: vec![("multi-access".to_string(), 8800)]
+                .into_iter()
+                .collect(),
+            ..Policy::default()
+        };
+        let agent = Agent::with_runner(policy, runner);
+        assert_eq!(value_of(&agent, Tasks::C35).await, Some(true));
+    }
+
+    #[async_std::test]
+    async fn congestion_thresholds() {
+        let block = "<congestion><baseMarkingInterval>PT0.100S</baseMarkingInterval>\
+            <defaultThreshold>65536</defaultThreshold></congestion>";
+        let thresholds_hold = |report: String, max_congestion_threshold| async move {
+            let runner = mock_runner().with_output(&nfdc::NfdcCommand::Status, Ok(report));
+            let policy = Policy {
+                max_congestion_threshold,
+                ..Policy::default()
+            };
+            let agent = Agent::with_runner(policy, runner);
+            value_of(&agent, Tasks::C36).await
+        };
+        let report = include_str!("command/nfdc_report.xml");
+        assert!(report.contains(block));
+        assert_eq!(
+            thresholds_hold(report.to_string(), ByteSize::from_kib(64)).await,
+            Some(true)
+        );
+        // The marked faces use the 64 KiB default threshold of NFD
+        assert_eq!(
+            thresholds_hold(report.to_string(), ByteSize::from_kib(32)).await,
+            Some(false)
+        );
+
+        // Without congestion settings, the faces are not checked
+        let without_congestion = report.replace(block, "");
+        assert_eq!(
+            thresholds_hold(without_congestion, ByteSize::from_kib(32)).await,
+            Some(true)
+        );
+        // Only the last marked face keeps its settings, and is checked alone
+        let mixed = report.replacen(block, "", report.matches(block).count() - 1);
+        assert!(mixed.contains(block));
+        assert_eq!(
+            thresholds_hold(mixed.clone(), ByteSize::from_kib(64)).await,
+            Some(true)
+        );
+        assert_eq!(
+            thresholds_hold(mixed, ByteSize::from_kib(32)).await,
+            Some(false)
+        );
+    }
+
+    #[async_std::test]
+    async fn unsatisfied_interest_rate() {
+        let report = include_str!("command/nfdc_report.xml");
+        let status = serde_xml_rs::from_str::<nfdc::NfdcStatus>(report).unwrap();
+        let start = Utc.ymd(2021, 4, 21).and_hms(15, 0, 0);
+        let policy = Policy {
+            max_unsatisfied_interest_rate: 2.0,
+            ..Policy::default()
+        };
+        let mut logs = Logs::default();
+        let mut values = Vec::new();
+        // 1 and then 5 unsatisfied interests per second, until NFD restarts
+        let readings = [(0, 100, "a"), (10, 110, "a"), (20, 160, "a"), (30, 5, "b")];
+        for (index, (seconds, unsatisfied, start_time)) in readings.iter().enumerate() {
+            let mut status = status.clone();
+            let now = start + chrono::Duration::seconds(*seconds);
+            status.general_status.n_unsatisfied_interests = *unsatisfied;
+            status.general_status.start_time = start_time.to_string();
+            let index = index as u64;
+            let m35 = m35(async { Ok(status.into()) }, now, index, logs.clone());
+            let Logging(evaluation, merged) = c37(m35, &policy, index).await.unwrap();
+            values.push(evaluation.value);
+            logs = merged;
+        }
+        assert_eq!(values, [None, Some(true), Some(false), None]);
+
+        // A decreasing counter is a reset as well
+        let reading = |n_unsatisfied_interests| UnsatisfiedInterests {
+            start_time: "a".to_string(),
+            n_unsatisfied_interests,
+            rate: None,
+        };
+        let seconds = chrono::Duration::seconds;
+        assert_eq!(
+            reading(150).rate_since(&reading(100), seconds(10)),
+            Some(5.0)
+        );
+        assert_eq!(reading(50).rate_since(&reading(100), seconds(10)), None);
+        assert_eq!(reading(150).rate_since(&reading(100), seconds(0)), None);
+        assert_eq!(reading(150).rate_since(&reading(100), seconds(-10)), None);
+    }
+
+    #[async_std::test]
+    async fn cs_admit_and_serve_across_window() {
+        let policy = Policy::default();
+        let mut logs = Logs::default();
+        let mut values = Vec::new();
+        for (index, admit) in [true, true, true, true, true, false, true]
+            .iter()
+            .enumerate()
+        {
+            let index = index as u64;
+            let measurement = Measurement::new(Data::M29(*admit, true), index);
+            let m29 = async { Ok(Logging(measurement, logs.clone())) };
+            let c31 = vec![c31(m29, index).boxed()];
+            let Logging(evaluation, merged) =
+                windowed_rule(Tasks::R25, c31, &policy, Utc::now(), index)
+                    .await
+                    .unwrap();
+            values.push(evaluation.value);
+            logs = merged;
+        }
+        // Undecided until enough samples, then failing for the whole window once admit flips off
+        let expected = [None, None, None, None, Some(true), Some(false), Some(false)];
+        assert_eq!(values, expected);
+        assert_eq!(logs.evaluations_index[&Tasks::C31][6].1, Some(true));
+
+        let agent = Agent::with_runner(policy, mock_runner());
+        assert_eq!(value_of(&agent, Tasks::C31).await, Some(true));
+    }
+
+    #[async_std::test]
+    async fn window_ends_at_tick_time() {
+        let policy = Policy::default();
+        let now = Utc.ymd(2021, 4, 21).and_hms(15, 0, 0);
+        let mut logs = Logs::default();
+        let history = [
+            (170, false),
+            (100, true),
+            (80, true),
+            (60, true),
+            (40, true),
+            (20, true),
+        ];
+        for (index, (seconds_ago, value)) in history.iter().enumerate() {
+            let mut evaluation = Evaluation::new(*value, index as u64);
+            evaluation.timestamp = now - chrono::Duration::seconds(*seconds_ago);
+            logs.insert_evaluation(evaluation, Tasks::C8);
+        }
+        let r3_at = |now| {
+            let c8 = async { Ok(Logging(Evaluation::new(true, 6), logs.clone())) };
+            windowed_rule(Tasks::R3, vec![c8.boxed()], &policy, now, 6)
+        };
+        // The failing sample is older than two minutes
+        assert_eq!(r3_at(now).await.unwrap().0.value, Some(true));
+        // Ticking 50 seconds earlier brings it back into the window
+        let earlier = now - chrono::Duration::seconds(50);
+        assert_eq!(r3_at(earlier).await.unwrap().0.value, Some(false));
+        // Long after the history, too few samples are left in the window
+        let later = now + chrono::Duration::minutes(10);
+        assert_eq!(r3_at(later).await.unwrap().0.value, None);
+    }
+
+    #[async_std::test]
+    async fn tamper_evident_logs() {
+        let policy = Policy {
+            hash_chain: true,
+            ..Policy::default()
+        };
+        let agent = Agent::with_runner(policy, mock_runner());
+        for index in 0..3 {
+            agent.tick(index).await;
+        }
+        let logs = agent.logs().read().unwrap().clone();
+        assert_eq!(logs.chain_index.len(), 3);
+        assert_eq!(logs.verify_chain(), Ok(()));
+        let table = logs.to_table();
+        assert_eq!(Logs::from(table).verify_chain(), Ok(()));
+
+        let mut altered = logs.clone();
+        let history = altered.evaluations_index.get_mut(&Tasks::P1).unwrap();
+        history[1].1 = history[1].1.map(|value| !value).or(Some(true));
+        assert_eq!(altered.verify_chain(), Err(1));
+
+        let mut altered = logs;
+        let forged = altered.chain_index[&0].clone();
+        altered.chain_index.insert(1, forged);
+        assert_eq!(altered.verify_chain(), Err(1));
+    }
+
+    #[async_std::test]
+    async fn nan_handling() {
+        let report = include_str!("command/nfdc_report.xml").replace(
+            "<averageSize>43.5</averageSize>",
+            "<averageSize>nan</averageSize>",
+        );
+        let tick = |nan_handling| {
+            let runner = mock_runner().with_output(&nfdc::NfdcCommand::Status, Ok(report.clone()));
+            let policy = Policy {
+                nan_handling,
+                ..Policy::default()
+            };
+            async move { Agent::with_runner(policy, runner).tick(0).await }
+        };
+        let evaluation = |report: &TickReport, task: Tasks| {
+            report
+                .evaluations
+                .iter()
+                .find(|(t, _)| *t == task)
+                .map(|(_, e)| e.clone())
+                .unwrap()
+        };
+
+        let report = tick(NanHandling::Evaluate).await;
+        assert_eq!(evaluation(&report, Tasks::C6).unwrap().value, Some(false));
+        assert_eq!(evaluation(&report, Tasks::C7).unwrap().value, Some(false));
+
+        let report = tick(NanHandling::Skip).await;
+        assert!(evaluation(&report, Tasks::C7).unwrap().is_skipped());
+        // The idle faces of the report have NaN averages too
+        assert!(evaluation(&report, Tasks::C10).unwrap().is_skipped());
+        assert!(!evaluation(&report, Tasks::C1).unwrap().is_skipped());
+
+        let report = tick(NanHandling::Fail).await;
+        assert_eq!(evaluation(&report, Tasks::C6).unwrap().value, Some(false));
+        assert_eq!(evaluation(&report, Tasks::C10).unwrap().value, Some(false));
+
+        let report = tick(NanHandling::Error).await;
+        assert!(evaluation(&report, Tasks::C7).is_err());
+        assert!(report.verdict.is_err());
+
+        let statistics = PacketStatistics {
+            min: 0,
+            max: 0,
+            avg: 1.0,
+            std_dev: f64::NAN,
+        };
+        assert!(Data::M4(statistics).has_nan());
+        assert!(!Data::M27(None).has_nan());
+        assert!(!Data::M2(0).has_nan());
+    }
+
+    #[async_std::test]
+    async fn reject_app_routes() {
+        let agent = Agent::with_runner(Policy::default(), mock_runner());
+        assert_eq!(value_of(&agent, Tasks::C28).await, Some(true));
+
+        let policy = Policy {
+            route_origin_prefixes: vec!["/localhost".to_string()],
+            ..Policy::default()
+        };
+        let agent = Agent::with_runner(policy.clone(), mock_runner());
+        assert_eq!(value_of(&agent, Tasks::C28).await, Some(false));
+
+        let report = include_str!("command/nfdc_report.xml");
+        let static_route = "<route><faceId>270</faceId><origin>static</origin><cost>10</cost>\
+            <flags><childInherit/></flags></route>";
+        let static_only = report.replace("<origin>app</origin>", "<origin>nlsr</origin>");
+        let routes = [
+            (
+                static_only.replace("</route>", &format!("</route>{}", static_route)),
+                Some(true),
+            ),
+            (
+                report.replace("</route>", &format!("</route>{}", static_route)),
+                Some(false),
+            ),
+        ];
+        for (report, expected) in routes.iter() {
+            let runner = mock_runner().with_output(&nfdc::NfdcCommand::Status, Ok(report.clone()));
+            let agent = Agent::with_runner(policy.clone(), runner);
+            let evaluations = agent.evaluations(0).collect::<Vec<_>>().await;
+            let (_, evaluation) = evaluations
+                .into_iter()
+                .find(|(t, _)| *t == Tasks::C28)
+                .unwrap();
+            let Logging(evaluation, logs) = evaluation.unwrap();
+            assert_eq!(evaluation.value, *expected);
+            match &logs.measurements_index[&Metrics::M26][0].1 {
+                Data::M26(origins) => assert_eq!(origins["/localhost/nfd"].len(), 2),
+                data => panic!("{:?}", data),
+            }
+        }
+    }
+
+    #[test]
+    fn flat_measurement_records() {
+        let record = |data: Data| Measurement::new(data, 7).to_record(&Metrics::M2);
+        let scalar = record(Data::M2(300));
+        assert_eq!(scalar.metric, "M2");
+        assert_eq!(scalar.index, 7);
+        assert_eq!(scalar.value, "300");
+        assert_eq!(record(Data::M15(0.5)).value, "0.5");
+        assert_eq!(record(Data::M1("lru".to_string())).value, "lru");
+        assert_eq!(record(Data::M12(None)).value, "null");
+        assert_eq!(record(Data::M12(Some("/test".to_string()))).value, "/test");
+        let issuers = vec![("/test".to_string(), "/bertof".to_string())];
+        assert_eq!(
+            record(Data::M17(issuers.into_iter().collect())).value,
+            r#"{"/test":"/bertof"}"#
+        );
+        assert_eq!(record(Data::M18(vec![1, 2])).value, "[1,2]");
+    }
+
+    #[test]
+    fn rolling_percentiles() {
+        let statistics = |avg| PacketStatistics {
+            min: 0,
+            max: 0,
+            avg,
+            std_dev: 0.0,
+        };
+        let mut logs = Logs::default();
+        let start = Utc::now();
+        for (index, avg) in [50.0, 15.0, f64::NAN, 40.0, 20.0, 35.0].iter().enumerate() {
+            let data = Data::M7(vec![(1, statistics(*avg))].into_iter().collect());
+            logs.insert_measurement(Measurement::new(data, index as u64), Metrics::M7);
+        }
+        let p = |face, p| logs.rolling_percentile(&Metrics::M7, face, p, start);
+        assert_eq!(p(1, 50.0), Some(35.0));
+        assert_eq!(p(1, 95.0), Some(48.0));
+        assert_eq!(p(2, 50.0), None);
+        assert_eq!(logs.rolling_percentile(&Metrics::M8, 1, 50.0, start), None);
+        let later = Utc::now() + chrono::Duration::seconds(1);
+        assert_eq!(logs.rolling_percentile(&Metrics::M7, 1, 50.0, later), None);
+    }
+
+    #[async_std::test]
+    async fn detect_slow_ticks() {
+        let agent = Agent::with_runner(Policy::default(), mock_runner());
+        assert_eq!(value_of(&agent, Tasks::C19).await, None);
+        {
+            let logs = agent.logs();
+            let mut logs = logs.write().unwrap();
+            for index in 0..5 {
+                logs.insert_duration(200_000_000, index);
+            }
+        }
+        assert_eq!(value_of(&agent, Tasks::C19).await, Some(true));
+        {
+            let logs = agent.logs();
+            let mut logs = logs.write().unwrap();
+            for index in 5..10 {
+                logs.insert_duration(1_500_000_000, index);
+            }
+            logs.insert_duration(3_000_000_000, 10);
+        }
+        assert_eq!(value_of(&agent, Tasks::C19).await, Some(false));
+    }
+
+    #[async_std::test]
+    async fn tick_fails_without_logging() {
+        let agent = Agent::with_runner(Policy::default(), MockRunner::default());
+        let report = agent.tick(0).await;
+        assert!(report.verdict.is_err());
+        assert!(report
+            .evaluations
+            .iter()
+            .filter(|(task, _)| !matches!(task, Tasks::C19 | Tasks::R13 | Tasks::C32 | Tasks::R26))
+            .all(|(_, e)| e.is_err()));
+        let logs = agent.logs();
+        let logs = logs.read().unwrap();
+        assert!(logs.duration_index.is_empty());
+        let error = report.verdict.unwrap_err().to_string();
+        assert_eq!(logs.errors_index[&0], error);
+        assert_eq!(logs.to_table().errors_index.len(), 1);
+    }
+}
@@ -0,0 +1,103 @@
+//! Memory of the host, read with the standard tools rather than with system calls
+//!
+//! Running `cat` and `ps` through a [`Runner`] reads the memory of the node the runner reaches,
+//! e.g. a remote node through its wrapper, instead of the one of the host running the agent.
+
+use super::{Command, CommandConfig, Error, Runner};
+use std::ffi::OsString;
+
+/// Commands reading the memory of the host
+pub enum HostCommand {
+    /// Memory statistics of the kernel
+    MemInfo,
+    /// Resident memory of the processes with the given name, in KiB, one per line
+    ResidentMemory(String),
+}
+
+impl Command for HostCommand {
+    fn to_command_with(&self, config: &CommandConfig) -> Vec<OsString> {
+        match self {
+            HostCommand::MemInfo => config.command("cat", ["/proc/meminfo"]),
+            HostCommand::ResidentMemory(name) => {
+                config.command("ps", ["-o", "rss=", "-C", name.as_str()])
+            }
+        }
+    }
+}
+
+/// Total memory in bytes, from the `MemTotal` line of `/proc/meminfo`
+pub fn parse_total_memory(meminfo: &str) -> Result<u64, Error> {
+    meminfo
+        .lines()
+        .find_map(|line| line.strip_prefix("MemTotal:"))
+        .and_then(|total| total.trim().strip_suffix("kB"))
+        .and_then(|kib| kib.trim().parse::<u64>().ok())
+        .and_then(|kib| kib.checked_mul(1024))
+        .ok_or_else(|| Error::OutputError(format!("Invalid memory info: {:?}", meminfo)))
+}
+
+/// Largest resident memory in bytes among the lines of `ps`, `None` without any line
+pub fn parse_resident_memory(ps: &str) -> Result<Option<u64>, Error> {
+    let sizes = ps
+        .split_whitespace()
+        .map(|kib| {
+            kib.parse::<u64>()
+                .ok()
+                .and_then(|kib| kib.checked_mul(1024))
+        })
+        .collect::<Option<Vec<_>>>()
+        .ok_or_else(|| Error::OutputError(format!("Invalid resident memory: {:?}", ps)))?;
+    Ok(sizes.into_iter().max())
+}
+
+/// Total memory in bytes of the host `runner` runs its commands on
+pub async fn total_memory(runner: &dyn Runner) -> Result<u64, Error> {
+    parse_total_memory(&HostCommand::MemInfo.run_with(runner).await?)
+}
+
+/// Resident memory in bytes of the process `name` on the host `runner` runs its commands on,
+/// the largest one if several are running, `None` when none is
+pub async fn resident_memory(name: &str, runner: &dyn Runner) -> Result<Option<u64>, Error> {
+    match HostCommand::ResidentMemory(name.to_string())
+        .run_with(runner)
+        .await
+    {
+        // `ps` prints nothing and exits with 1 when no process matches
+        Err(Error::ExitCode(1, stderr)) if stderr.trim().is_empty() => Ok(None),
+        output => parse_resident_memory(&output?),
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::command::MockRunner;
+
+    #[async_std::test]
+    async fn read_memory_through_runner() {
+        let meminfo = "MemTotal:        2048 kB\nMemFree:          512 kB\n";
+        let process = HostCommand::ResidentMemory("nfd".to_string());
+        let runner = MockRunner::default()
+            .with_output(&HostCommand::MemInfo, Ok(meminfo.to_string()))
+            .with_output(&process, Ok("  1024\n  4096\n".to_string()));
+        assert_eq!(total_memory(&runner).await.unwrap(), 2 << 20);
+        assert_eq!(
+            resident_memory("nfd", &runner).await.unwrap(),
+            Some(4 << 20)
+        );
+
+        let runner = MockRunner::default()
+            .with_output(&HostCommand::MemInfo, Ok("MemFree: 512 kB\n".to_string()))
+            .with_output(&process, Err(Error::ExitCode(1, String::new())));
+        assert!(total_memory(&runner).await.is_err());
+        assert_eq!(resident_memory("nfd", &runner).await.unwrap(), None);
+
+        let runner = MockRunner::default().with_output(&process, Ok("1024\nnfd\n".to_string()));
+        assert!(resident_memory("nfd", &runner).await.is_err());
+        let runner = MockRunner::default().with_output(
+            &process,
+            Err(Error::ExitCode(1, "ps: not found".to_string())),
+        );
+        assert!(resident_memory("nfd", &runner).await.is_err());
+    }
+}
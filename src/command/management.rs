@@ -0,0 +1,243 @@
+//! NFD management protocol spoken directly over the unix socket of the forwarder
+//!
+//! Fetching a status dataset this way spares spawning `nfdc` at every tick. Only the general
+//! status dataset is decoded for now, into the [`GeneralStatus`] of the `nfdc` report.
+
+use super::nfdc::{GeneralStatus, InCounters, OutCounters, PacketCounters, PacketCountersEntry};
+use super::{tlv, Error};
+use async_std::io::prelude::*;
+use async_std::os::unix::net::UnixStream;
+use chrono::{DateTime, TimeZone, Utc};
+use std::path::Path;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+/// Default unix socket of NFD
+pub const NFD_SOCKET: &str = "/run/nfd/nfd.sock";
+
+/// Name prefix of the general status dataset
+pub const GENERAL_STATUS: &str = "/localhost/nfd/status/general";
+
+/// Lifetime of the interests fetching a dataset, in milliseconds
+const INTEREST_LIFETIME_MS: u64 = 1000;
+
+const NFD_VERSION: u64 = 0x80;
+const START_TIMESTAMP: u64 = 0x81;
+const CURRENT_TIMESTAMP: u64 = 0x82;
+const N_NAME_TREE_ENTRIES: u64 = 0x83;
+const N_FIB_ENTRIES: u64 = 0x84;
+const N_PIT_ENTRIES: u64 = 0x85;
+const N_MEASUREMENTS_ENTRIES: u64 = 0x86;
+const N_CS_ENTRIES: u64 = 0x87;
+const N_IN_INTERESTS: u64 = 0x90;
+const N_IN_DATA: u64 = 0x91;
+const N_OUT_INTERESTS: u64 = 0x92;
+const N_OUT_DATA: u64 = 0x93;
+const N_IN_NACKS: u64 = 0x97;
+const N_OUT_NACKS: u64 = 0x98;
+const N_SATISFIED_INTERESTS: u64 = 0x99;
+const N_UNSATISFIED_INTERESTS: u64 = 0x9a;
+
+fn management_error(message: &str) -> Error {
+    Error::TlvDecodingError(format!("Invalid status dataset: {}", message))
+}
+
+/// Interest fetching the latest version of the dataset with the given name prefix
+pub fn dataset_interest(prefix: &str, nonce: u32) -> Vec<u8> {
+    let mut value = tlv::encode_name(prefix);
+    value.extend(tlv::encode(tlv::CAN_BE_PREFIX, &[]));
+    value.extend(tlv::encode(tlv::MUST_BE_FRESH, &[]));
+    value.extend(tlv::encode(tlv::NONCE, &nonce.to_be_bytes()));
+    value.extend(tlv::encode(
+        tlv::INTEREST_LIFETIME,
+        &tlv::encode_non_negative_integer(INTEREST_LIFETIME_MS),
+    ));
+    tlv::encode(tlv::INTEREST, &value)
+}
+
+/// Content of a dataset answered by a single Data packet, possibly carried by an NDNLPv2 packet
+///
+/// Datasets split in several segments are not supported yet.
+pub fn dataset_content(packet: &[u8]) -> Result<Vec<u8>, Error> {
+    let (mut typ, mut value, _) = tlv::next_element(packet)?;
+    if typ == tlv::LP_PACKET {
+        let fragment = tlv::find(&tlv::elements(value)?, tlv::LP_FRAGMENT)
+            .map_err(|_| management_error("NDNLPv2 packet without fragment, e.g. a nack"))?;
+        let (fragment_typ, fragment_value, _) = tlv::next_element(fragment)?;
+        typ = fragment_typ;
+        value = fragment_value;
+    }
+    if typ != tlv::DATA {
+        return Err(management_error(&format!("unexpected packet type {}", typ)));
+    }
+    let data = tlv::elements(value)?;
+    let name = tlv::elements(tlv::find(&data, tlv::NAME)?)?;
+    let segment = name
+        .last()
+        .filter(|(typ, _)| *typ == tlv::SEGMENT_NAME_COMPONENT)
+        .ok_or_else(|| management_error("missing segment number"))?;
+    let final_block = tlv::find(&data, tlv::META_INFO)
+        .and_then(tlv::elements)
+        .and_then(|meta_info| tlv::find(&meta_info, tlv::FINAL_BLOCK_ID).map(<[u8]>::to_vec))
+        .map_err(|_| management_error("missing final block id"))?;
+    if tlv::elements(&final_block)?.first() != Some(segment) {
+        return Err(management_error("segmented datasets are not supported"));
+    }
+    Ok(tlv::find(&data, tlv::CONTENT)?.to_vec())
+}
+
+impl GeneralStatus {
+    /// Decode the content of the general status dataset
+    ///
+    /// Times are formatted as in the `nfdc` report, e.g. `2021-04-21T15:08:41.222000`.
+    pub fn from_tlv(content: &[u8]) -> Result<Self, Error> {
+        let elements = tlv::elements(content)?;
+        let number = |typ| tlv::non_negative_integer(tlv::find(&elements, typ)?);
+        let timestamp = |typ| -> Result<DateTime<Utc>, Error> {
+            Utc.timestamp_millis_opt(number(typ)? as i64)
+                .single()
+                .ok_or_else(|| management_error("timestamp out of range"))
+        };
+        let start_time = timestamp(START_TIMESTAMP)?;
+        let current_time = timestamp(CURRENT_TIMESTAMP)?;
+        let format = |time: DateTime<Utc>| time.format("%Y-%m-%dT%H:%M:%S%.6f").to_string();
+        Ok(GeneralStatus {
+            version: String::from_utf8(tlv::find(&elements, NFD_VERSION)?.to_vec())?,
+            start_time: format(start_time),
+            current_time: format(current_time),
+            uptime: format!("PT{}S", (current_time - start_time).num_seconds()),
+            n_name_tree_entries: number(N_NAME_TREE_ENTRIES)?,
+            n_fib_entries: number(N_FIB_ENTRIES)?,
+            n_pit_entries: number(N_PIT_ENTRIES)?,
+            n_measurements_entries: number(N_MEASUREMENTS_ENTRIES)?,
+            n_cs_entries: number(N_CS_ENTRIES)?,
+            packet_counters: PacketCounters {
+                incoming_packets: InCounters(PacketCountersEntry {
+                    n_interests: number(N_IN_INTERESTS)?,
+                    n_data: number(N_IN_DATA)?,
+                    n_nacks: number(N_IN_NACKS)?,
+                }),
+                outgoing_packets: OutCounters(PacketCountersEntry {
+                    n_interests: number(N_OUT_INTERESTS)?,
+                    n_data: number(N_OUT_DATA)?,
+                    n_nacks: number(N_OUT_NACKS)?,
+                }),
+            },
+            n_satisfied_interests: number(N_SATISFIED_INTERESTS)?,
+            n_unsatisfied_interests: number(N_UNSATISFIED_INTERESTS)?,
+        })
+    }
+}
+
+/// Read a whole packet from the stream
+///
+/// No more than a packet is buffered, the elements announcing a larger size being rejected.
+async fn read_packet<S: Read + Unpin>(stream: &mut S) -> Result<Vec<u8>, Error> {
+    let mut packet = Vec::new();
+    let mut buffer = [0; tlv::MAX_PACKET_SIZE];
+    loop {
+        if let Some(size) = tlv::element_size(&packet)? {
+            packet.truncate(size);
+            return Ok(packet);
+        }
+        // Incomplete elements are smaller than a packet, so there is always room left
+        let room = tlv::MAX_PACKET_SIZE - packet.len();
+        let read = stream.read(&mut buffer[..room]).await?;
+        if read == 0 {
+            return Err(Error::IoError(
+                "Connection closed before a whole packet".to_string(),
+            ));
+        }
+        packet.extend_from_slice(&buffer[..read]);
+    }
+}
+
+/// Fetch the general status of the forwarder listening on the unix socket at `path`, failing
+/// with [`Error::Timeout`] when it does not answer within `timeout`
+pub async fn general_status<P: AsRef<Path>>(
+    path: P,
+    timeout: Duration,
+) -> Result<GeneralStatus, Error> {
+    async_std::future::timeout(timeout, fetch_general_status(path))
+        .await
+        .unwrap_or(Err(Error::Timeout(timeout)))
+}
+
+async fn fetch_general_status<P: AsRef<Path>>(path: P) -> Result<GeneralStatus, Error> {
+    let nonce = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map_or(0, |elapsed| elapsed.subsec_nanos());
+    let mut stream = UnixStream::connect(path.as_ref()).await?;
+    stream
+        .write_all(&dataset_interest(GENERAL_STATUS, nonce))
+        .await?;
+    let packet = read_packet(&mut stream).await?;
+    GeneralStatus::from_tlv(&dataset_content(&packet)?)
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::command::nfdc::NfdcStatus;
+    use async_std::os::unix::net::UnixListener;
+    use async_std::task;
+
+    const DATASET: &[u8] = include_bytes!("nfd_general_status.tlv");
+
+    #[test]
+    fn decode_general_status() {
+        let report = include_str!("nfdc_report.xml");
+        let status = serde_xml_rs::from_str::<NfdcStatus>(report).unwrap();
+        let decoded = GeneralStatus::from_tlv(&dataset_content(DATASET).unwrap()).unwrap();
+        assert_eq!(decoded, status.general_status);
+
+        // Carried by an NDNLPv2 packet
+        let lp_packet = tlv::encode(tlv::LP_PACKET, &tlv::encode(tlv::LP_FRAGMENT, DATASET));
+        assert_eq!(
+            dataset_content(&lp_packet).unwrap(),
+            dataset_content(DATASET).unwrap()
+        );
+        assert!(dataset_content(&dataset_interest(GENERAL_STATUS, 1)).is_err());
+        assert!(GeneralStatus::from_tlv(&[]).is_err());
+    }
+
+    #[async_std::test]
+    async fn read_bounded_packets() {
+        assert_eq!(read_packet(&mut &DATASET[..]).await.unwrap(), DATASET);
+        let oversized = tlv::encode(tlv::DATA, &[0; tlv::MAX_PACKET_SIZE]);
+        assert!(read_packet(&mut &oversized[..]).await.is_err());
+    }
+
+    #[async_std::test]
+    async fn fetch_general_status() {
+        let path = std::env::temp_dir().join(format!("ca-nfd-{}.sock", std::process::id()));
+        let _ = std::fs::remove_file(&path);
+        let listener = UnixListener::bind(&path).await.unwrap();
+        let forwarder = task::spawn(async move {
+            let (mut stream, _) = listener.accept().await.unwrap();
+            let interest = read_packet(&mut stream).await.unwrap();
+            // Answer in two writes, as a stream may split the packet
+            stream.write_all(&DATASET[..10]).await.unwrap();
+            stream.write_all(&DATASET[10..]).await.unwrap();
+            interest
+        });
+        let status = general_status(&path, Duration::from_secs(1)).await.unwrap();
+        assert_eq!(status.n_cs_entries, 2);
+        let interest = forwarder.await;
+        let (typ, value, _) = tlv::next_element(&interest).unwrap();
+        assert_eq!(typ, tlv::INTEREST);
+        let name = tlv::find(&tlv::elements(value).unwrap(), tlv::NAME).unwrap();
+        assert_eq!(tlv::name_uri(name).unwrap(), GENERAL_STATUS);
+        std::fs::remove_file(&path).unwrap();
+
+        // A forwarder accepting the interest without ever answering, the connection held open
+        let listener = UnixListener::bind(&path).await.unwrap();
+        let silent = task::spawn(async move { listener.accept().await.unwrap() });
+        let timeout = Duration::from_millis(50);
+        assert!(matches!(
+            general_status(&path, timeout).await,
+            Err(Error::Timeout(t)) if t == timeout
+        ));
+        drop(silent);
+        std::fs::remove_file(&path).unwrap();
+    }
+}
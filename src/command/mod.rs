@@ -1,6 +1,11 @@
+use async_std::channel::{self, Receiver, Sender};
 use async_std::{io, process};
 use async_trait::async_trait;
-use std::ffi::OsString;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::ffi::{OsStr, OsString};
+use std::sync::LazyLock;
+use std::time::Duration;
 use thiserror::Error as ThisError;
 
 /// Command error
@@ -10,6 +15,14 @@ pub enum Error {
     #[error("{0}")]
     OutputError(String),
 
+    /// The command exited with a non-zero code, with its standard error
+    #[error("Command exited with code {0}: {1}")]
+    ExitCode(i32, String),
+
+    /// The requested face does not exist
+    #[error("Face {0} not found")]
+    FaceNotFound(u64),
+
     /// XML parsing error
     #[error("{0}")]
     XmlParsingError(String),
@@ -17,15 +30,51 @@ pub enum Error {
     #[error("{0}")]
     NomParsingError(String),
 
+    /// The same face id is reported for more than one face
+    #[error("Duplicate face id {0}")]
+    DuplicateFaceId(u64),
+
+    /// Base64 decoding error
+    #[error("{0}")]
+    Base64DecodingError(String),
+
+    /// DER decoding error of a public key
+    #[error("{0}")]
+    PublicKeyDecodingError(String),
+
+    /// TLV decoding error of a certificate or of a forwarder packet
+    #[error("{0}")]
+    TlvDecodingError(String),
+
+    /// The command executable does not exist
+    #[error("Command binary not found: {0}")]
+    BinaryNotFound(String),
+
     /// Generic IO error
     #[error("{0}")]
     IoError(String),
 
+    /// The command ran longer than its timeout
+    #[error("Command timed out after {0:?}")]
+    Timeout(Duration),
+
     /// UTF8 conversion error
     #[error(transparent)]
     FromUtf8Error(#[from] std::string::FromUtf8Error),
 }
 
+impl Error {
+    /// Whether running the command again may succeed, e.g. while NFD restarts
+    ///
+    /// Missing faces and binaries, and outputs that cannot be parsed, are not transient.
+    pub fn is_transient(&self) -> bool {
+        matches!(
+            self,
+            Error::IoError(_) | Error::OutputError(_) | Error::ExitCode(1, _)
+        )
+    }
+}
+
 impl From<io::Error> for Error {
     fn from(error: io::Error) -> Self {
         Error::IoError(format!("{}", error))
@@ -38,24 +87,670 @@ impl From<serde_xml_rs::Error> for Error {
     }
 }
 
+/// Executes command lines, returning their standard output
 #[async_trait]
-pub trait Command {
-    fn to_command(&self) -> Vec<OsString>;
-    async fn run(&self) -> Result<String, Error> {
-        let args = self.to_command();
-        let cmd = args.first().ok_or_else(||Error::OutputError("Invalid command".to_string()))?;
+pub trait Runner: Send + Sync {
+    async fn run(&self, args: &[OsString]) -> Result<String, Error>;
+}
+
+/// Runs commands as local processes
+#[derive(Debug, Default, Clone, Copy)]
+pub struct ProcessRunner;
+
+fn spawn_error(cmd: &OsString, error: io::Error) -> Error {
+    match error.kind() {
+        io::ErrorKind::NotFound => Error::BinaryNotFound(cmd.to_string_lossy().to_string()),
+        _ => Error::from(error),
+    }
+}
+
+fn exit_error(status: std::process::ExitStatus, stderr: Vec<u8>) -> Error {
+    match String::from_utf8(stderr) {
+        Ok(err) => match status.code() {
+            Some(code) => Error::ExitCode(code, err),
+            None => Error::OutputError(err),
+        },
+        Err(e) => Error::from(e),
+    }
+}
+
+#[async_trait]
+impl Runner for ProcessRunner {
+    async fn run(&self, args: &[OsString]) -> Result<String, Error> {
+        let cmd = args
+            .first()
+            .ok_or_else(|| Error::OutputError("Invalid command".to_string()))?;
         let res: process::Output = process::Command::new(cmd)
             .args(args.iter().skip(1))
             .output()
-            .await?;
+            .await
+            .map_err(|e| spawn_error(cmd, e))?;
         if res.status.success() {
             Ok(String::from_utf8(res.stdout)?)
         } else {
-            let err = String::from_utf8(res.stderr)?;
-            Err(Error::OutputError(err))
+            Err(exit_error(res.status, res.stderr))
+        }
+    }
+}
+
+impl ProcessRunner {
+    /// Run a command, feeding its standard output to `parse` while it is produced
+    ///
+    /// Unlike [`Runner::run`] the output is never buffered whole, which keeps the memory low on
+    /// large reports. The parsed value is returned only if the command succeeds.
+    pub async fn run_streamed<T, F>(&self, args: Vec<OsString>, parse: F) -> Result<T, Error>
+    where
+        T: Send + 'static,
+        F: FnOnce(&mut dyn std::io::Read) -> Result<T, Error> + Send + 'static,
+    {
+        async_std::task::spawn_blocking(move || {
+            let cmd = args
+                .first()
+                .ok_or_else(|| Error::OutputError("Invalid command".to_string()))?;
+            let mut child = std::process::Command::new(cmd)
+                .args(args.iter().skip(1))
+                .stdout(std::process::Stdio::piped())
+                .stderr(std::process::Stdio::piped())
+                .spawn()
+                .map_err(|e| spawn_error(cmd, e))?;
+            let mut stdout = child
+                .stdout
+                .take()
+                .ok_or_else(|| Error::OutputError("Missing standard output".to_string()))?;
+            let parsed = parse(&mut stdout);
+            drop(stdout);
+            let res = child.wait_with_output()?;
+            if res.status.success() {
+                parsed
+            } else {
+                Err(exit_error(res.status, res.stderr))
+            }
+        })
+        .await
+    }
+}
+
+/// Runs commands as local processes wrapped in another command, e.g. `ssh router1`
+#[derive(Debug, Default, Clone)]
+pub struct WrappedRunner {
+    pub wrapper: Vec<OsString>,
+}
+
+impl WrappedRunner {
+    pub fn new<I: IntoIterator<Item = S>, S: Into<OsString>>(wrapper: I) -> Self {
+        Self {
+            wrapper: wrapper.into_iter().map(Into::into).collect(),
+        }
+    }
+}
+
+#[async_trait]
+impl Runner for WrappedRunner {
+    async fn run(&self, args: &[OsString]) -> Result<String, Error> {
+        let args = self
+            .wrapper
+            .iter()
+            .chain(args.iter())
+            .cloned()
+            .collect::<Vec<_>>();
+        ProcessRunner.run(&args).await
+    }
+}
+
+/// Runs commands with another runner, at most `limit` at the same time
+///
+/// Bounds the subprocesses of a tick on constrained nodes, the other commands wait for a slot.
+#[derive(Debug)]
+pub struct LimitedRunner<R> {
+    inner: R,
+    slots: (Sender<()>, Receiver<()>),
+}
+
+impl<R: Runner> LimitedRunner<R> {
+    /// Run at most `limit` commands of `inner` at the same time, `limit` being positive
+    pub fn new(inner: R, limit: usize) -> Self {
+        Self {
+            inner,
+            slots: channel::bounded(limit),
+        }
+    }
+}
+
+/// Slot of a running command, freed when dropped, even if the command is cancelled by a timeout
+struct Slot<'a>(&'a Receiver<()>);
+
+impl Drop for Slot<'_> {
+    fn drop(&mut self) {
+        let _ = self.0.try_recv();
+    }
+}
+
+#[async_trait]
+impl<R: Runner> Runner for LimitedRunner<R> {
+    async fn run(&self, args: &[OsString]) -> Result<String, Error> {
+        let (taken, free) = &self.slots;
+        taken
+            .send(())
+            .await
+            .map_err(|e| Error::OutputError(e.to_string()))?;
+        let _slot = Slot(free);
+        self.inner.run(args).await
+    }
+}
+
+/// Replies to commands with recorded outputs instead of running them
+#[derive(Debug, Default, Clone)]
+pub struct MockRunner {
+    outputs: HashMap<Vec<OsString>, Result<String, Error>>,
+}
+
+impl MockRunner {
+    pub fn with_output<C: Command>(mut self, command: &C, output: Result<String, Error>) -> Self {
+        self.outputs.insert(command.to_command(), output);
+        self
+    }
+}
+
+#[async_trait]
+impl Runner for MockRunner {
+    async fn run(&self, args: &[OsString]) -> Result<String, Error> {
+        self.outputs.get(args).cloned().unwrap_or_else(|| {
+            Err(Error::OutputError(format!(
+                "No output recorded for {:?}",
+                args
+            )))
+        })
+    }
+}
+
+/// Binaries run by the commands, with their timeouts and retries
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default, rename_all = "camelCase")]
+pub struct CommandConfig {
+    /// Path of the `nfdc` binary, or its name when run through `env`
+    pub nfdc: String,
+    /// Path of the `ndnsec` binary, or its name when run through `env`
+    pub ndnsec: String,
+    /// Wrapper the binaries are run through, e.g. `/usr/bin/env` to look their names up in `PATH`
+    pub env: Option<String>,
+    /// Time in milliseconds a certificate dump may run for, e.g. longer for the keychains slow to
+    /// dump on loaded nodes, `None` for the fixed [`crate::agent::TIMEOUT`] of the measurements
+    pub dump_timeout_ms: Option<u64>,
+    /// How the status and list commands are run again when they fail with a transient error,
+    /// e.g. while the forwarder restarts
+    pub retry: RetryPolicy,
+    /// Source of the general status of the forwarder, `nfdc` by default
+    pub status_source: StatusSource,
+    /// Unix socket of the forwarder, for the general status fetched over the management protocol
+    pub nfd_socket: String,
+}
+
+impl Default for CommandConfig {
+    fn default() -> Self {
+        Self {
+            nfdc: nfdc::NFDC.to_string(),
+            ndnsec: ndnsec::NDNSEC.to_string(),
+            env: None,
+            dump_timeout_ms: None,
+            retry: RetryPolicy::default(),
+            status_source: StatusSource::default(),
+            nfd_socket: management::NFD_SOCKET.to_string(),
+        }
+    }
+}
+
+/// Where the general status of the forwarder is read from
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum StatusSource {
+    /// The `nfdc status report`, along with the rest of the status
+    #[default]
+    Nfdc,
+    /// The general status dataset, fetched over the unix socket of the forwarder
+    Management,
+}
+
+/// Attempts of a command failing with a transient error, with exponential backoff in between
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default, rename_all = "camelCase")]
+pub struct RetryPolicy {
+    /// Attempts in total, a single one by default, at most [`RetryPolicy::MAX_ATTEMPTS`]
+    pub max_attempts: u32,
+    /// Delay in milliseconds before the second attempt, at most [`RetryPolicy::MAX_DELAY`]
+    pub initial_delay_ms: u64,
+    /// Factor of the delay before each further attempt, at least 1
+    pub multiplier: f64,
+}
+
+impl Default for RetryPolicy {
+    fn default() -> Self {
+        Self {
+            max_attempts: 1,
+            initial_delay_ms: 100,
+            multiplier: 2.0,
+        }
+    }
+}
+
+impl RetryPolicy {
+    /// Most attempts a policy may ask for
+    pub const MAX_ATTEMPTS: u32 = 10;
+    /// Longest delay between two attempts, that the growing delays saturate at
+    pub const MAX_DELAY: Duration = Duration::from_secs(60);
+
+    /// Delay before the attempt following the `attempt`-th one, counted from 1
+    pub fn delay(&self, attempt: u32) -> Duration {
+        let exponent = attempt.saturating_sub(1).min(i32::MAX as u32) as i32;
+        let secs = self.initial_delay_ms as f64 / 1000.0 * self.multiplier.powi(exponent);
+        // Overflowing or undefined delays, e.g. of an infinite multiplier, saturate as well
+        Duration::try_from_secs_f64(secs)
+            .unwrap_or(Self::MAX_DELAY)
+            .min(Self::MAX_DELAY)
+    }
+}
+
+impl CommandConfig {
+    /// Arguments running `binary` with `args`, through the wrapper if any
+    pub fn command<I, S>(&self, binary: &str, args: I) -> Vec<OsString>
+    where
+        I: IntoIterator<Item = S>,
+        S: AsRef<OsStr>,
+    {
+        let mut command = self.env.iter().map(OsString::from).collect::<Vec<_>>();
+        command.push(OsString::from(binary));
+        command.extend(args.into_iter().map(|arg| arg.as_ref().to_os_string()));
+        command
+    }
+}
+
+static DEFAULT_CONFIG: LazyLock<CommandConfig> = LazyLock::new(CommandConfig::default);
+
+#[async_trait]
+pub trait Command {
+    /// Arguments of the command, running the binaries of `config`
+    fn to_command_with(&self, config: &CommandConfig) -> Vec<OsString>;
+    /// Binaries run by the command, the default ones unless configured
+    fn config(&self) -> &CommandConfig {
+        &DEFAULT_CONFIG
+    }
+    fn to_command(&self) -> Vec<OsString> {
+        self.to_command_with(self.config())
+    }
+    /// Time the command may run for with the binaries of `config`, unbounded by default
+    fn timeout_with(&self, _config: &CommandConfig) -> Option<Duration> {
+        None
+    }
+    /// Time the command may run for, enforced by [`Command::run_with`]
+    fn timeout(&self) -> Option<Duration> {
+        self.timeout_with(self.config())
+    }
+    /// The same command, running the binaries of `config`
+    fn with_config(self, config: CommandConfig) -> Configured<Self>
+    where
+        Self: Sized,
+    {
+        Configured {
+            command: self,
+            config,
+        }
+    }
+    async fn run(&self) -> Result<String, Error> {
+        self.run_with(&ProcessRunner).await
+    }
+    /// Run the command again after the transient failures, up to the attempts of `retry`
+    ///
+    /// Each attempt is bounded by the timeout of the command, or else by `timeout`, so that the
+    /// delays in between do not count against it. Returns the error of the last attempt once
+    /// they are exhausted.
+    async fn run_with_retry(
+        &self,
+        runner: &dyn Runner,
+        retry: &RetryPolicy,
+        timeout: Duration,
+    ) -> Result<String, Error> {
+        let timeout = self.timeout().unwrap_or(timeout);
+        let mut attempt = 1;
+        loop {
+            let output = async_std::future::timeout(timeout, runner.run(&self.to_command()))
+                .await
+                .unwrap_or(Err(Error::Timeout(timeout)));
+            match output {
+                Err(e) if e.is_transient() && attempt < retry.max_attempts => {
+                    async_std::task::sleep(retry.delay(attempt)).await;
+                    attempt += 1;
+                }
+                output => return output,
+            }
+        }
+    }
+    async fn run_with(&self, runner: &dyn Runner) -> Result<String, Error> {
+        let command = self.to_command();
+        match self.timeout() {
+            Some(timeout) => async_std::future::timeout(timeout, runner.run(&command))
+                .await
+                .unwrap_or(Err(Error::Timeout(timeout))),
+            None => runner.run(&command).await,
+        }
+    }
+}
+
+/// Command running the binaries of its own configuration, see [`Command::with_config`]
+#[derive(Debug, Clone)]
+pub struct Configured<C> {
+    pub command: C,
+    pub config: CommandConfig,
+}
+
+impl<C: Command + Sync> Command for Configured<C> {
+    fn to_command_with(&self, config: &CommandConfig) -> Vec<OsString> {
+        self.command.to_command_with(config)
+    }
+    fn timeout_with(&self, config: &CommandConfig) -> Option<Duration> {
+        self.command.timeout_with(config)
+    }
+    fn config(&self) -> &CommandConfig {
+        &self.config
+    }
+}
+
+/// Runs commands with another runner, slowly, tracking how many of them run at the same time
+///
+/// Clones share their counts, so that a clone can be inspected after handing the runner over.
+#[cfg(test)]
+#[derive(Debug, Clone)]
+pub(crate) struct CountingRunner<R> {
+    inner: R,
+    running: std::sync::Arc<std::sync::atomic::AtomicUsize>,
+    max_running: std::sync::Arc<std::sync::atomic::AtomicUsize>,
+}
+
+#[cfg(test)]
+impl<R: Runner> CountingRunner<R> {
+    pub(crate) fn new(inner: R) -> Self {
+        Self {
+            inner,
+            running: Default::default(),
+            max_running: Default::default(),
         }
     }
+
+    /// Most commands that ran at the same time
+    pub(crate) fn max_running(&self) -> usize {
+        self.max_running.load(std::sync::atomic::Ordering::SeqCst)
+    }
+}
+
+#[cfg(test)]
+#[async_trait]
+impl<R: Runner> Runner for CountingRunner<R> {
+    async fn run(&self, args: &[OsString]) -> Result<String, Error> {
+        use std::sync::atomic::Ordering;
+        let running = self.running.fetch_add(1, Ordering::SeqCst) + 1;
+        self.max_running.fetch_max(running, Ordering::SeqCst);
+        async_std::task::sleep(std::time::Duration::from_millis(10)).await;
+        let output = self.inner.run(args).await;
+        self.running.fetch_sub(1, Ordering::SeqCst);
+        output
+    }
 }
 
+/// Runs commands with another runner, failing one of them with `error` the first `failures`
+/// times it runs
+///
+/// Clones share their counts, so that a clone can be inspected after handing the runner over.
+#[cfg(test)]
+#[derive(Debug, Clone)]
+pub(crate) struct FlakyRunner<R> {
+    inner: R,
+    command: Vec<OsString>,
+    error: Error,
+    failures: usize,
+    attempts: std::sync::Arc<std::sync::atomic::AtomicUsize>,
+}
+
+#[cfg(test)]
+impl<R: Runner> FlakyRunner<R> {
+    pub(crate) fn new<C: Command>(inner: R, command: &C, error: Error, failures: usize) -> Self {
+        Self {
+            inner,
+            command: command.to_command(),
+            error,
+            failures,
+            attempts: Default::default(),
+        }
+    }
+
+    /// Times the flaky command ran
+    pub(crate) fn attempts(&self) -> usize {
+        self.attempts.load(std::sync::atomic::Ordering::SeqCst)
+    }
+}
+
+#[cfg(test)]
+#[async_trait]
+impl<R: Runner> Runner for FlakyRunner<R> {
+    async fn run(&self, args: &[OsString]) -> Result<String, Error> {
+        use std::sync::atomic::Ordering;
+        if args == self.command.as_slice()
+            && self.attempts.fetch_add(1, Ordering::SeqCst) < self.failures
+        {
+            return Err(self.error.clone());
+        }
+        self.inner.run(args).await
+    }
+}
+
+/// Compare the JSON serialization of a parsed fixture with its snapshot in `snapshots/`
+///
+/// Run the tests with `UPDATE_SNAPSHOTS=1` to store the current serialization instead.
+#[cfg(test)]
+pub(crate) fn assert_snapshot<T: serde::Serialize>(name: &str, value: &T) {
+    // Going through a JSON value sorts the map keys
+    let value = serde_json::to_value(value).unwrap();
+    let actual = format!("{}\n", serde_json::to_string_pretty(&value).unwrap());
+    let path = std::path::Path::new(env!("CARGO_MANIFEST_DIR"))
+        .join("src/command/snapshots")
+        .join(format!("{}.json", name));
+    if std::env::var_os("UPDATE_SNAPSHOTS").is_some() {
+        std::fs::write(&path, &actual).unwrap();
+        return;
+    }
+    let expected = std::fs::read_to_string(&path).unwrap_or_else(|_| {
+        panic!(
+            "Missing snapshot {:?}, run with UPDATE_SNAPSHOTS=1 to create it",
+            path
+        )
+    });
+    assert!(
+        expected == actual,
+        "Snapshot {} changed, run with UPDATE_SNAPSHOTS=1 to accept it:\n{}",
+        name,
+        actual
+    );
+}
+
+pub mod host;
+pub mod management;
 pub mod ndnsec;
 pub mod nfdc;
+pub mod record;
+pub mod timestamp;
+pub mod tlv;
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[async_std::test]
+    async fn wrapped_commands() {
+        let runner = WrappedRunner::new(vec!["echo", "-n"]);
+        let args = vec![OsString::from("/usr/bin/nfdc"), OsString::from("status")];
+        assert_eq!(runner.run(&args).await.unwrap(), "/usr/bin/nfdc status");
+    }
+
+    #[test]
+    fn configured_commands() {
+        let status = nfdc::NfdcCommand::Status;
+        assert_eq!(
+            status.to_command(),
+            ["/usr/bin/nfdc", "status", "report", "xml"]
+        );
+        let config = CommandConfig {
+            nfdc: "/usr/local/bin/nfdc".to_string(),
+            ..CommandConfig::default()
+        };
+        assert_eq!(
+            nfdc::NfdcCommand::FaceShow(1)
+                .with_config(config)
+                .to_command(),
+            ["/usr/local/bin/nfdc", "face", "show", "id", "1", "xml"]
+        );
+
+        // Bare names are looked up in `PATH` by the wrapper
+        let config = CommandConfig {
+            ndnsec: "ndnsec".to_string(),
+            env: Some("/bin/env".to_string()),
+            ..CommandConfig::default()
+        };
+        assert_eq!(
+            ndnsec::NdnSecCommand::List.with_config(config).to_command(),
+            ["/bin/env", "ndnsec", "list", "-c"]
+        );
+        let config = serde_json::from_str::<CommandConfig>(r#"{"nfdc": "nfdc"}"#).unwrap();
+        assert_eq!(config.ndnsec, ndnsec::NDNSEC);
+        assert_eq!(config.env, None);
+    }
+
+    #[async_std::test]
+    async fn command_timeouts() {
+        let dump = ndnsec::NdnSecCommand::Dump("/test".to_string());
+        let list = ndnsec::NdnSecCommand::List;
+        let mock = MockRunner::default()
+            .with_output(&dump, Ok("dump".to_string()))
+            .with_output(&list, Ok("list".to_string()));
+        // Every command takes 10ms
+        let runner = CountingRunner::new(mock);
+        assert_eq!(dump.timeout(), None);
+        assert_eq!(dump.run_with(&runner).await.unwrap(), "dump");
+
+        let config = CommandConfig {
+            dump_timeout_ms: Some(1),
+            ..CommandConfig::default()
+        };
+        let dump = dump.with_config(config.clone());
+        assert_eq!(dump.timeout(), Some(Duration::from_millis(1)));
+        assert!(matches!(
+            dump.run_with(&runner).await,
+            Err(Error::Timeout(timeout)) if timeout == Duration::from_millis(1)
+        ));
+        // The other commands keep running unbounded
+        let list = list.with_config(config);
+        assert_eq!(list.timeout(), None);
+        assert_eq!(list.run_with(&runner).await.unwrap(), "list");
+    }
+
+    #[async_std::test]
+    async fn retried_commands() {
+        let status = nfdc::NfdcCommand::Status;
+        let flaky = |error: Error| {
+            let mock = MockRunner::default().with_output(&status, Ok("status".to_string()));
+            FlakyRunner::new(mock, &status, error, 2)
+        };
+        let retry = RetryPolicy {
+            max_attempts: 3,
+            initial_delay_ms: 1,
+            multiplier: 2.0,
+        };
+        let runner = flaky(Error::IoError("Connection refused".to_string()));
+        let timeout = Duration::from_secs(1);
+        assert_eq!(
+            status
+                .run_with_retry(&runner, &retry, timeout)
+                .await
+                .unwrap(),
+            "status"
+        );
+        assert_eq!(runner.attempts(), 3);
+
+        // The error of the last attempt is returned once they are exhausted
+        let runner = flaky(Error::ExitCode(1, "NFD restarting".to_string()));
+        let retry = RetryPolicy {
+            max_attempts: 2,
+            ..retry
+        };
+        assert!(matches!(
+            status.run_with_retry(&runner, &retry, timeout).await,
+            Err(Error::ExitCode(1, _))
+        ));
+        assert_eq!(runner.attempts(), 2);
+
+        // Semantic errors are not retried
+        let runner = flaky(Error::FaceNotFound(42));
+        assert!(status
+            .run_with_retry(&runner, &retry, timeout)
+            .await
+            .is_err());
+        assert_eq!(runner.attempts(), 1);
+
+        assert_eq!(retry.delay(1), Duration::from_millis(1));
+        assert_eq!(retry.delay(3), Duration::from_millis(4));
+        assert_eq!(RetryPolicy::default().delay(2), Duration::from_millis(200));
+
+        // Delays saturate instead of overflowing
+        let retry = RetryPolicy {
+            multiplier: f64::INFINITY,
+            ..retry
+        };
+        assert_eq!(retry.delay(1), Duration::from_millis(1));
+        assert_eq!(retry.delay(2), RetryPolicy::MAX_DELAY);
+        let retry = RetryPolicy {
+            initial_delay_ms: u64::MAX,
+            multiplier: 10.0,
+            ..retry
+        };
+        assert_eq!(retry.delay(u32::MAX), RetryPolicy::MAX_DELAY);
+    }
+
+    #[async_std::test]
+    async fn limited_concurrency() {
+        let status = nfdc::NfdcCommand::Status;
+        let mock = MockRunner::default().with_output(&status, Ok("status".to_string()));
+        let counting = CountingRunner::new(mock);
+        let runner = LimitedRunner::new(counting.clone(), 2);
+        let args = status.to_command();
+        let outputs = futures::future::join_all((0..6).map(|_| runner.run(&args))).await;
+        assert!(outputs
+            .iter()
+            .all(|output| matches!(output.as_deref(), Ok("status"))));
+        assert_eq!(counting.max_running(), 2);
+
+        // A command cancelled by a timeout frees its slot for the next one
+        let runner = LimitedRunner::new(CountingRunner::new(MockRunner::default()), 1);
+        let timeout = |millis| {
+            async_std::future::timeout(std::time::Duration::from_millis(millis), runner.run(&args))
+        };
+        assert!(timeout(1).await.is_err());
+        assert!(timeout(1000).await.is_ok());
+    }
+
+    #[async_std::test]
+    async fn missing_binary() {
+        let args = vec![
+            OsString::from("/nonexistent/nfdc"),
+            OsString::from("status"),
+        ];
+        match ProcessRunner.run(&args).await {
+            Err(Error::BinaryNotFound(path)) => assert_eq!(path, "/nonexistent/nfdc"),
+            res => panic!("Unexpected result {:?}", res),
+        }
+
+        let runner = MockRunner::default().with_output(
+            &nfdc::NfdcCommand::Status,
+            Err(Error::BinaryNotFound("/usr/bin/nfdc".to_string())),
+        );
+        assert!(matches!(
+            nfdc::NfdcCommand::Status.run_with(&runner).await,
+            Err(Error::BinaryNotFound(_))
+        ));
+    }
+}
@@ -1,11 +1,18 @@
-use crate::command::Error;
-use chrono::{DateTime, TimeZone, Utc};
+use super::key::PublicKeyInfo;
+use crate::command::tlv;
+use crate::command::{timestamp::parse_ndn_timestamp, Error};
+use base64::{
+    alphabet,
+    engine::{DecodePaddingMode, GeneralPurpose, GeneralPurposeConfig},
+    Engine,
+};
+use chrono::{DateTime, Utc};
 use nom::{
-    bytes::complete::{tag, take, take_until},
+    bytes::complete::{tag, take_until},
     character::complete::multispace0,
-    combinator::{map, map_res},
+    combinator::map,
     multi::many0,
-    sequence::{delimited, pair, preceded, tuple},
+    sequence::{delimited, pair, preceded},
     IResult,
 };
 use serde::{Deserialize, Serialize};
@@ -19,79 +26,171 @@ pub struct CertificateInfo {
     pub validity_not_after: DateTime<Utc>,
     pub public_key_bits: Vec<u8>,
     pub signature_information: HashMap<String, String>,
+    /// Time the command printing the certificate completed, if known
+    #[serde(skip)]
+    pub completed_at: Option<DateTime<Utc>>,
+}
+
+/// Strictness of the public key bits base64 decoding
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum Base64Mode {
+    /// Require canonical padding and zeroed trailing bits
+    #[default]
+    Strict,
+    /// Accept missing or extra padding and non-zero trailing bits
+    Permissive,
+}
+
+impl Base64Mode {
+    fn engine(self) -> GeneralPurpose {
+        let config = match self {
+            Base64Mode::Strict => GeneralPurposeConfig::new()
+                .with_decode_padding_mode(DecodePaddingMode::RequireCanonical)
+                .with_decode_allow_trailing_bits(false),
+            Base64Mode::Permissive => GeneralPurposeConfig::new()
+                .with_decode_padding_mode(DecodePaddingMode::Indifferent)
+                .with_decode_allow_trailing_bits(true),
+        };
+        GeneralPurpose::new(&alphabet::STANDARD, config)
+    }
+}
+
+/// Decode the standard alphabet base64 public key bits, ignoring line breaks and spaces
+pub fn decode_public_key_bits(input: &str, mode: Base64Mode) -> Result<Vec<u8>, Error> {
+    let stripped = input
+        .chars()
+        .filter(|c| !c.is_whitespace())
+        .collect::<String>();
+    mode.engine()
+        .decode(stripped)
+        .map_err(|e| Error::Base64DecodingError(format!("Invalid public key bits: {}", e)))
+}
+
+/// Identity of a key or certificate name, i.e. the components before `KEY`
+pub fn key_identity(name: &str) -> &str {
+    match name.find("/KEY/") {
+        Some(0) => "/",
+        Some(i) => &name[..i],
+        None => name,
+    }
 }
 
 impl FromStr for CertificateInfo {
     type Err = Error;
     fn from_str(input: &str) -> Result<Self, Error> {
-        let (rest, res) =
+        Self::from_str_with(input, Base64Mode::default())
+    }
+}
+
+impl CertificateInfo {
+    /// Parse a certificate dump decoding the public key bits with the given strictness
+    pub fn from_str_with(input: &str, base64_mode: Base64Mode) -> Result<Self, Error> {
+        let (rest, (mut res, public_key_bits)) =
             Self::parse(input).map_err(|e| Error::NomParsingError(format!("{}", e)))?;
         debug_assert!(rest.is_empty());
+        // Decoded apart from the parsing, so that invalid bits are reported as such
+        res.public_key_bits = decode_public_key_bits(public_key_bits, base64_mode)?;
         Ok(res)
     }
-}
 
-impl CertificateInfo {
-    fn parse(input: &str) -> IResult<&str, Self> {
+    /// Decode a certificate from its NDN Certificate v2 TLV encoding, as `ndnsec cert-dump` would
+    /// print it
+    pub fn from_tlv(input: &[u8]) -> Result<Self, Error> {
+        let (typ, data, _) = tlv::next_element(input)?;
+        if typ != tlv::DATA {
+            return Err(Error::TlvDecodingError(
+                "Invalid TLV: not a Data packet".to_string(),
+            ));
+        }
+        let data = tlv::elements(data)?;
+        let name = tlv::find(&data, tlv::NAME)?;
+        let certificate_name = tlv::name_uri(name)?;
+        let public_key_bits = tlv::find(&data, tlv::CONTENT)?.to_vec();
+        let signature_info = tlv::elements(tlv::find(&data, tlv::SIGNATURE_INFO)?)?;
+
+        let validity = tlv::elements(tlv::find(&signature_info, tlv::VALIDITY_PERIOD)?)?;
+        let timestamp = |typ| -> Result<DateTime<Utc>, Error> {
+            let value = String::from_utf8(tlv::find(&validity, typ)?.to_vec())?;
+            let (_, timestamp) = parse_ndn_timestamp(&value)
+                .map_err(|e| Error::TlvDecodingError(format!("Invalid validity: {}", e)))?;
+            Ok(timestamp)
+        };
+
+        let mut signature_information = HashMap::new();
+        let signature_type =
+            tlv::non_negative_integer(tlv::find(&signature_info, tlv::SIGNATURE_TYPE)?)?;
+        signature_information.insert(
+            "Signature Type".to_string(),
+            tlv::signature_type_name(signature_type),
+        );
+        if let Ok(key_locator) = tlv::find(&signature_info, tlv::KEY_LOCATOR) {
+            let key_locator = match tlv::next_element(key_locator)? {
+                (tlv::NAME, locator, _) => {
+                    // The key name drops the issuer and version components of the certificate
+                    let components = tlv::elements(name)?;
+                    let key_name = &components[..components.len().saturating_sub(2)];
+                    let self_signed = tlv::elements(locator)? == key_name;
+                    format!(
+                        "{}Name={}",
+                        if self_signed { "Self-Signed " } else { "" },
+                        tlv::name_uri(locator)?
+                    )
+                }
+                (tlv::KEY_DIGEST, digest, _) => format!(
+                    "KeyDigest={}",
+                    digest
+                        .iter()
+                        .map(|b| format!("{:02X}", b))
+                        .collect::<String>()
+                ),
+                (typ, _, _) => format!("Unknown({})", typ),
+            };
+            signature_information.insert("Key Locator".to_string(), key_locator);
+        }
+
+        Ok(CertificateInfo {
+            certificate_name,
+            validity_not_before: timestamp(tlv::NOT_BEFORE)?,
+            validity_not_after: timestamp(tlv::NOT_AFTER)?,
+            public_key_bits,
+            signature_information,
+            completed_at: None,
+        })
+    }
+
+    /// Name of the key that signed the certificate, from the key locator
+    pub fn issuer(&self) -> Option<&str> {
+        let key_locator = self.signature_information.get("Key Locator")?;
+        key_locator
+            .split("Name=")
+            .nth(1)
+            .map(str::trim)
+            .filter(|name| !name.is_empty())
+    }
+
+    /// Identity owning the key that signed the certificate
+    pub fn issuer_identity(&self) -> Option<&str> {
+        self.issuer().map(key_identity)
+    }
+
+    /// Algorithm and size of the certified public key
+    pub fn public_key_info(&self) -> Result<PublicKeyInfo, Error> {
+        PublicKeyInfo::from_der(&self.public_key_bits)
+    }
+
+    /// Parse a certificate dump, along with its public key bits still encoded
+    fn parse(input: &str) -> IResult<&str, (Self, &str)> {
         let (input, _) = preceded(multispace0, tag("Certificate name:"))(input)?;
         let (input, certificate_name) =
             preceded(multispace0, map(take_until("\n"), String::from))(input)?;
         let (input, _) = preceded(multispace0, tag("Validity:"))(input)?;
         let (input, _) = preceded(multispace0, tag("NotBefore: "))(input)?;
-        let (input, validity_not_before) = preceded(
-            multispace0,
-            map_res(
-                tuple((
-                    map_res(take(4u64), i32::from_str),
-                    map_res(take(2u64), u32::from_str),
-                    map_res(take(2u64), u32::from_str),
-                    tag("T"),
-                    map_res(take(2u64), u32::from_str),
-                    map_res(take(2u64), u32::from_str),
-                    map_res(take(2u64), u32::from_str),
-                )),
-                |(year, month, day, _, hour, minute, second)| {
-                    Utc.ymd_opt(year, month, day)
-                        .and_hms_opt(hour, minute, second)
-                        .single()
-                        .ok_or_else(|| Error::NomParsingError(String::from("Invalid ISO 8601")))
-                },
-            ),
-        )(input)?;
+        let (input, validity_not_before) = preceded(multispace0, parse_ndn_timestamp)(input)?;
         let (input, _) = preceded(multispace0, tag("NotAfter: "))(input)?;
-        let (input, validity_not_after) = preceded(
-            multispace0,
-            map_res(
-                tuple((
-                    map_res(take(4_u64), i32::from_str),
-                    map_res(take(2_u64), u32::from_str),
-                    map_res(take(2_u64), u32::from_str),
-                    tag("T"),
-                    map_res(take(2_u64), u32::from_str),
-                    map_res(take(2_u64), u32::from_str),
-                    map_res(take(2_u64), u32::from_str),
-                )),
-                |(year, month, day, _, hour, minute, second)| {
-                    Utc.ymd_opt(year, month, day)
-                        .and_hms_opt(hour, minute, second)
-                        .single()
-                        .ok_or_else(|| Error::NomParsingError(String::from("Invalid ISO 8601")))
-                },
-            ),
-        )(input)?;
+        let (input, validity_not_after) = preceded(multispace0, parse_ndn_timestamp)(input)?;
         let (input, _) = preceded(multispace0, tag("Public key bits:"))(input)?;
-        let (input, public_key_bits) = map_res(
-            map(
-                preceded(multispace0, take_until("Signature Information:")),
-                |s: &str| {
-                    s.trim()
-                        .replace(" ", "")
-                        .replace("\n", "")
-                        .replace("\r", "")
-                },
-            ),
-            base64::decode,
-        )(input)?;
+        let (input, public_key_bits) =
+            preceded(multispace0, take_until("Signature Information:"))(input)?;
         let (input, signature_information) = preceded(
             multispace0,
             preceded(
@@ -104,34 +203,132 @@ impl CertificateInfo {
         )(input)?;
         let signature_information = signature_information.iter().cloned().collect();
         let (input, _) = multispace0(input)?;
-        Ok((
-            input,
-            CertificateInfo {
-                certificate_name,
-                validity_not_before,
-                validity_not_after,
-                public_key_bits,
-                signature_information,
-            },
-        ))
+        let info = CertificateInfo {
+            certificate_name,
+            validity_not_before,
+            validity_not_after,
+            public_key_bits: Vec::new(),
+            signature_information,
+            completed_at: None,
+        };
+        Ok((input, (info, public_key_bits)))
     }
 }
 
 #[cfg(test)]
 mod test {
     use super::*;
-    use crate::command::{ndnsec::NdnSecCommand, Command};
+    use crate::command::{assert_snapshot, ndnsec::NdnSecCommand, Command};
     use async_std::prelude::FutureExt;
+    use chrono::TimeZone;
     use std::time::Duration;
 
     #[test]
     fn parse_example_output() {
         let output = include_str!("dump.txt");
-        let (rest, parsed_output) = CertificateInfo::parse(output).unwrap();
+        let (rest, (parsed_output, _)) = CertificateInfo::parse(output).unwrap();
         assert!(rest.is_empty());
         println!("{:?}", parsed_output);
     }
 
+    #[test]
+    fn parse_validity_suffixes() {
+        let expected = Utc.ymd(2021, 2, 9).and_hms(14, 0, 51);
+        for (not_before, offset) in [
+            ("20210209T140051", 0),
+            ("20210209T140051.283000", 283),
+            ("20210209T140051Z", 0),
+        ]
+        .iter()
+        {
+            let output = include_str!("dump.txt").replace(
+                "NotBefore: 19700101T000000",
+                &format!("NotBefore: {}", not_before),
+            );
+            let (rest, (info, _)) = CertificateInfo::parse(&output).unwrap();
+            assert!(rest.is_empty());
+            assert_eq!(
+                info.validity_not_before,
+                expected + chrono::Duration::milliseconds(*offset)
+            );
+        }
+    }
+
+    #[test]
+    fn snapshots() {
+        let info = CertificateInfo::from_str(include_str!("dump.txt")).unwrap();
+        assert_snapshot("ndnsec_dump", &info);
+    }
+
+    #[test]
+    fn extract_issuer() {
+        let mut info = CertificateInfo::from_str(include_str!("dump.txt")).unwrap();
+        assert_eq!(info.issuer(), Some("/bertof/KEY/U%A8%3E%D3%D7%EF%F37"));
+        assert_eq!(info.issuer_identity(), Some("/bertof"));
+        info.signature_information.insert(
+            "Key Locator".to_string(),
+            "Name=/ndn/unimi/KEY/%01%02/ndn/%FD%01".to_string(),
+        );
+        assert_eq!(info.issuer_identity(), Some("/ndn/unimi"));
+        info.signature_information.remove("Key Locator");
+        assert_eq!(info.issuer(), None);
+    }
+
+    #[test]
+    fn decode_tlv_certificate() {
+        let tlv = include_bytes!("dump.tlv");
+        let text = CertificateInfo::from_str(include_str!("dump.txt")).unwrap();
+        assert_eq!(CertificateInfo::from_tlv(tlv).unwrap(), text);
+        assert!(CertificateInfo::from_tlv(&tlv[..tlv.len() - 1]).is_err());
+        assert!(matches!(
+            CertificateInfo::from_tlv(&[0x05, 0x00]),
+            Err(Error::TlvDecodingError(_))
+        ));
+    }
+
+    #[test]
+    fn decode_canonical_public_key_bits() {
+        for mode in [Base64Mode::Strict, Base64Mode::Permissive].iter() {
+            assert_eq!(decode_public_key_bits("QUJD", *mode).unwrap(), b"ABC");
+            assert_eq!(decode_public_key_bits("QU\n  I=", *mode).unwrap(), b"AB");
+        }
+    }
+
+    #[test]
+    fn decode_non_canonical_public_key_bits() {
+        // Missing padding
+        assert!(matches!(
+            decode_public_key_bits("QUI", Base64Mode::Strict),
+            Err(Error::Base64DecodingError(_))
+        ));
+        assert_eq!(
+            decode_public_key_bits("QUI", Base64Mode::Permissive).unwrap(),
+            b"AB"
+        );
+        // Non-zero trailing bits
+        assert!(decode_public_key_bits("QUJ=", Base64Mode::Strict).is_err());
+        assert_eq!(
+            decode_public_key_bits("QUJ=", Base64Mode::Permissive).unwrap(),
+            b"AB"
+        );
+    }
+
+    #[test]
+    fn decode_invalid_public_key_bits() {
+        for mode in [Base64Mode::Strict, Base64Mode::Permissive].iter() {
+            assert!(matches!(
+                decode_public_key_bits("QU!D", *mode),
+                Err(Error::Base64DecodingError(_))
+            ));
+        }
+        let output = include_str!("dump.txt").replace("iwIDAQAB", "iwIDAQA");
+        assert!(matches!(
+            CertificateInfo::from_str_with(&output, Base64Mode::Strict),
+            Err(Error::Base64DecodingError(_))
+        ));
+        assert!(CertificateInfo::from_str_with(&output, Base64Mode::Permissive).is_ok());
+    }
+
     #[ignore = "Must have a running system"]
     #[async_std::test]
     async fn parse_live_output() -> Result<(), Box<dyn std::error::Error>> {
@@ -140,7 +337,7 @@ mod test {
             .run()
             .timeout(Duration::from_millis(1000))
             .await??;
-        let (rest, parsed_output) = CertificateInfo::parse(&output).unwrap();
+        let (rest, (parsed_output, _)) = CertificateInfo::parse(&output).unwrap();
         assert!(rest.is_empty());
         println!("{:?}", parsed_output);
         Ok(())
@@ -0,0 +1,150 @@
+use crate::command::Error;
+use serde::{Deserialize, Serialize};
+
+const RSA_ENCRYPTION: &[u8] = &[0x2a, 0x86, 0x48, 0x86, 0xf7, 0x0d, 0x01, 0x01, 0x01];
+const EC_PUBLIC_KEY: &[u8] = &[0x2a, 0x86, 0x48, 0xce, 0x3d, 0x02, 0x01];
+const CURVES: [(&[u8], u32); 3] = [
+    (&[0x2a, 0x86, 0x48, 0xce, 0x3d, 0x03, 0x01, 0x07], 256),
+    (&[0x2b, 0x81, 0x04, 0x00, 0x22], 384),
+    (&[0x2b, 0x81, 0x04, 0x00, 0x23], 521),
+];
+
+const SEQUENCE: u8 = 0x30;
+const INTEGER: u8 = 0x02;
+const BIT_STRING: u8 = 0x03;
+const OBJECT_IDENTIFIER: u8 = 0x06;
+
+/// Algorithm of a public key
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub enum KeyAlgorithm {
+    Rsa,
+    Ec,
+    /// Any other algorithm, with its object identifier in hexadecimal
+    Other(String),
+}
+
+/// Algorithm and size of a public key, decoded from its `SubjectPublicKeyInfo`
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct PublicKeyInfo {
+    pub algorithm: KeyAlgorithm,
+    /// Modulus size of RSA keys, field size of EC keys, zero if unknown
+    pub bits: u32,
+}
+
+fn decoding_error(message: &str) -> Error {
+    Error::PublicKeyDecodingError(format!("Invalid public key: {}", message))
+}
+
+/// Split the DER element with the expected `tag` off the input, returning its content
+fn element(input: &[u8], tag: u8) -> Result<(&[u8], &[u8]), Error> {
+    match input {
+        [t, ..] if *t != tag => Err(decoding_error("unexpected tag")),
+        [_, length, rest @ ..] if *length < 0x80 => {
+            let length = *length as usize;
+            if rest.len() < length {
+                return Err(decoding_error("truncated element"));
+            }
+            Ok((&rest[..length], &rest[length..]))
+        }
+        [_, length, rest @ ..] => {
+            let octets = (*length & 0x7f) as usize;
+            if octets == 0 || octets > 4 || rest.len() < octets {
+                return Err(decoding_error("unsupported length"));
+            }
+            let length = rest[..octets]
+                .iter()
+                .fold(0usize, |length, b| length << 8 | *b as usize);
+            let rest = &rest[octets..];
+            if rest.len() < length {
+                return Err(decoding_error("truncated element"));
+            }
+            Ok((&rest[..length], &rest[length..]))
+        }
+        _ => Err(decoding_error("truncated element")),
+    }
+}
+
+/// Size in bits of a positive DER integer
+fn integer_bits(integer: &[u8]) -> u32 {
+    let significant = integer.iter().skip_while(|b| **b == 0).collect::<Vec<_>>();
+    match significant.first() {
+        Some(first) => significant.len() as u32 * 8 - first.leading_zeros(),
+        None => 0,
+    }
+}
+
+impl PublicKeyInfo {
+    /// Decode the DER `SubjectPublicKeyInfo` of a certificate, as in its public key bits
+    pub fn from_der(der: &[u8]) -> Result<Self, Error> {
+        let (spki, _) = element(der, SEQUENCE)?;
+        let (algorithm, rest) = element(spki, SEQUENCE)?;
+        let (public_key, _) = element(rest, BIT_STRING)?;
+        let (oid, parameters) = element(algorithm, OBJECT_IDENTIFIER)?;
+        if oid == RSA_ENCRYPTION {
+            let key = public_key
+                .get(1..)
+                .ok_or_else(|| decoding_error("empty key"))?;
+            let (key, _) = element(key, SEQUENCE)?;
+            let (modulus, _) = element(key, INTEGER)?;
+            Ok(PublicKeyInfo {
+                algorithm: KeyAlgorithm::Rsa,
+                bits: integer_bits(modulus),
+            })
+        } else if oid == EC_PUBLIC_KEY {
+            let (curve, _) = element(parameters, OBJECT_IDENTIFIER)?;
+            let bits = CURVES
+                .iter()
+                .find(|(known, _)| *known == curve)
+                .map_or(0, |(_, bits)| *bits);
+            Ok(PublicKeyInfo {
+                algorithm: KeyAlgorithm::Ec,
+                bits,
+            })
+        } else {
+            Ok(PublicKeyInfo {
+                algorithm: KeyAlgorithm::Other(oid.iter().map(|b| format!("{:02x}", b)).collect()),
+                bits: 0,
+            })
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::command::ndnsec::dump::{decode_public_key_bits, Base64Mode, CertificateInfo};
+
+    const RSA_1024: &str = "MIGfMA0GCSqGSIb3DQEBAQUAA4GNADCBiQKBgQDObGdG2yU76ziqSmdbdTntToq8JQUiB+k283zwetft0C9sOvEO/3lJpMA1sQIzPbkaOsj9Khq6w9R3C/ChCl9M8UtEKmOXt2E0/a2KKxKHSfGOxRTAuyjrlTKX2f+qm1jWG6eVO78Qq63An6rsu5Dq+hjtrDgcVUMMIqykocv4JQIDAQAB";
+    const EC_P256: &str = "MFkwEwYHKoZIzj0CAQYIKoZIzj0DAQcDQgAEceg3V1XzOLIoVkrQAe638eDgTysBz/6Cdq22Mj7dH6xtloh0BQHTUTp8ENe4vx0yWipqJmNS4tx35WnRBEQx2w==";
+
+    fn decode(key: &str) -> Result<PublicKeyInfo, Error> {
+        PublicKeyInfo::from_der(&decode_public_key_bits(key, Base64Mode::Strict).unwrap())
+    }
+
+    #[test]
+    fn decode_key_sizes() {
+        assert_eq!(
+            decode(RSA_1024).unwrap(),
+            PublicKeyInfo {
+                algorithm: KeyAlgorithm::Rsa,
+                bits: 1024
+            }
+        );
+        assert_eq!(
+            decode(EC_P256).unwrap(),
+            PublicKeyInfo {
+                algorithm: KeyAlgorithm::Ec,
+                bits: 256
+            }
+        );
+        let info: CertificateInfo = include_str!("dump.txt").parse().unwrap();
+        assert_eq!(info.public_key_info().unwrap().bits, 2048);
+    }
+
+    #[test]
+    fn reject_malformed_keys() {
+        assert!(PublicKeyInfo::from_der(&[]).is_err());
+        assert!(PublicKeyInfo::from_der(&[0x30, 0x05, 0x30]).is_err());
+        assert!(PublicKeyInfo::from_der(&[0x04, 0x00]).is_err());
+    }
+}
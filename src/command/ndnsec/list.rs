@@ -1,4 +1,5 @@
 use crate::command::Error;
+use chrono::{DateTime, Utc};
 use nom::{
     bytes::complete::{is_not, tag, take_until},
     character::complete::multispace0,
@@ -8,17 +9,37 @@ use nom::{
     IResult,
 };
 use serde::{Deserialize, Serialize};
+use std::collections::HashSet;
 use std::str::FromStr;
 
 #[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
 pub struct CertificateList {
     pub certificates: Vec<Certificate>,
+    /// Time the command printing the list completed, if known
+    #[serde(skip)]
+    pub completed_at: Option<DateTime<Utc>>,
 }
 
 impl CertificateList {
     fn parse(input: &str) -> IResult<&str, Self> {
         let (input, certificates) = preceded(multispace0, many0(Certificate::parse))(input)?;
-        Ok((input, CertificateList { certificates }))
+        Ok((
+            input,
+            CertificateList {
+                certificates,
+                completed_at: None,
+            },
+        ))
+    }
+
+    /// Identities listed more than once, e.g. by split keychains, each time with another key
+    pub fn duplicated_identities(&self) -> HashSet<String> {
+        let mut listed = HashSet::new();
+        self.certificates
+            .iter()
+            .filter(|c| !listed.insert(c.identity.as_str()))
+            .map(|c| c.identity.clone())
+            .collect()
     }
 }
 impl FromStr for CertificateList {
@@ -65,6 +86,51 @@ impl Certificate {
     }
 }
 
+/// Identities listed by `ndnsec list`
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct IdentityList {
+    pub identities: Vec<Identity>,
+}
+
+impl IdentityList {
+    fn parse(input: &str) -> IResult<&str, Self> {
+        let (input, identities) = preceded(multispace0, many0(Identity::parse))(input)?;
+        Ok((input, IdentityList { identities }))
+    }
+}
+
+impl FromStr for IdentityList {
+    type Err = Error;
+    fn from_str(input: &str) -> Result<Self, Self::Err> {
+        let (rest, res) =
+            Self::parse(input).map_err(|e| Error::NomParsingError(format!("{}", e)))?;
+        debug_assert!(rest.is_empty());
+        Ok(res)
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
+pub struct Identity {
+    pub is_default: bool,
+    pub name: String,
+}
+
+impl Identity {
+    fn parse(input: &str) -> IResult<&str, Self> {
+        let (input, opt_is_default) =
+            delimited(multispace0, opt(tag("*")), take_until("/"))(input)?;
+        let (input, name) = map(is_not("\n"), |s: &str| s.trim_end().to_string())(input)?;
+        let (input, _) = multispace0(input)?;
+        Ok((
+            input,
+            Identity {
+                is_default: opt_is_default.is_some(),
+                name,
+            },
+        ))
+    }
+}
+
 #[cfg(test)]
 mod test {
     use super::*;
@@ -79,6 +145,62 @@ mod test {
         println!("{:#?}", parsed_output);
     }
 
+    #[test]
+    fn duplicated_identities() {
+        let output = format!(
+            "{}\n  /test\n  +->* /test/KEY/%01\n       +->* /test/KEY/%01/self/%FD%01\n",
+            include_str!("list.txt")
+        );
+        let list = CertificateList::from_str(&output).unwrap();
+        assert_eq!(list.certificates.len(), 3);
+        let keys = list
+            .certificates
+            .iter()
+            .filter(|c| c.identity == "/test")
+            .map(|c| c.key.as_str())
+            .collect::<Vec<_>>();
+        assert_eq!(keys, ["/test/KEY/%A8C%0C%13%ADd%3B%9B", "/test/KEY/%01"]);
+        let expected = ["/test".to_string()]
+            .iter()
+            .cloned()
+            .collect::<HashSet<_>>();
+        assert_eq!(list.duplicated_identities(), expected);
+        let list = CertificateList::from_str(include_str!("list.txt")).unwrap();
+        assert!(list.duplicated_identities().is_empty());
+    }
+
+    #[test]
+    fn snapshots() {
+        let certificates = CertificateList::from_str(include_str!("list.txt")).unwrap();
+        assert_snapshot("ndnsec_list", &certificates);
+        let identities = IdentityList::from_str(include_str!("identities.txt")).unwrap();
+        assert_snapshot("ndnsec_identities", &identities);
+    }
+
+    #[test]
+    fn parse_identities_output() {
+        let output = include_str!("identities.txt");
+        let parsed_output = IdentityList::from_str(output).unwrap();
+        assert_eq!(
+            parsed_output.identities,
+            vec![
+                Identity {
+                    is_default: true,
+                    name: "/bertof".to_string()
+                },
+                Identity {
+                    is_default: false,
+                    name: "/test".to_string()
+                },
+                Identity {
+                    is_default: false,
+                    name: "/ndn/edu/unimi/%C1.Router".to_string()
+                },
+            ]
+        );
+        assert!(IdentityList::from_str("").unwrap().identities.is_empty());
+    }
+
     #[ignore = "Must have a running system"]
     #[async_std::test]
     async fn parse_live_output() -> Result<(), Box<dyn std::error::Error>> {
@@ -2,24 +2,49 @@ use super::*;
 use std::ffi::OsString;
 
 pub mod dump;
+pub mod key;
 pub mod list;
 
+/// Path of the `ndnsec` binary
+pub const NDNSEC: &str = "/usr/bin/ndnsec";
+
 pub enum NdnSecCommand {
     List,
+    /// List the identities only, without their keys and certificates
+    ListIdentities,
+    /// Dump the default certificate of an identity
     Dump(String),
+    /// Dump a certificate by name
+    DumpCertificate(String),
 }
 
 impl Command for NdnSecCommand {
-    fn to_command(&self) -> Vec<OsString> {
+    fn to_command_with(&self, config: &CommandConfig) -> Vec<OsString> {
+        let ndnsec = &config.ndnsec;
         match self {
-            NdnSecCommand::List => ["/usr/bin/ndnsec", "list", "-c"]
-                .iter()
-                .map(OsString::from)
-                .collect(),
-            NdnSecCommand::Dump(identity) => ["/usr/bin/ndnsec", "cert-dump", "-p", "-i", identity.as_str()]
-                .iter()
-                .map(OsString::from)
-                .collect(),
+            NdnSecCommand::List => config.command(ndnsec, ["list", "-c"]),
+            NdnSecCommand::ListIdentities => config.command(ndnsec, ["list"]),
+            NdnSecCommand::Dump(identity) => {
+                config.command(ndnsec, ["cert-dump", "-p", "-i", identity.as_str()])
+            }
+            NdnSecCommand::DumpCertificate(name) => {
+                config.command(ndnsec, ["cert-dump", "-p", name.as_str()])
+            }
         }
     }
+
+    fn timeout_with(&self, config: &CommandConfig) -> Option<std::time::Duration> {
+        match self {
+            NdnSecCommand::Dump(_) | NdnSecCommand::DumpCertificate(_) => {
+                config.dump_timeout_ms.map(std::time::Duration::from_millis)
+            }
+            NdnSecCommand::List | NdnSecCommand::ListIdentities => None,
+        }
+    }
+}
+
+/// Whether `cert-dump` failed because the identity has no default key or certificate
+pub fn is_missing_default_certificate(error: &Error) -> bool {
+    matches!(error, Error::ExitCode(_, stderr)
+        if stderr.contains("No default certificate") || stderr.contains("No default key"))
 }
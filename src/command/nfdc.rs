@@ -1,21 +1,70 @@
+use super::timestamp::parse_ndn_timestamp;
 use super::*;
+use crate::size::ByteSize;
+use crate::version::{ParseVersionError, Version};
+use chrono::{DateTime, Utc};
+use nom::{
+    branch::alt,
+    bytes::complete::{tag, tag_no_case, take_till1},
+    character::complete::{char, digit1, space0, space1},
+    combinator::{all_consuming, map, map_res, opt, recognize, verify},
+    multi::{many0, separated_list0},
+    number::complete::double,
+    sequence::{delimited, pair, preceded, separated_pair, terminated, tuple},
+    IResult,
+};
+use percent_encoding::percent_decode_str;
 use serde::{Deserialize, Deserializer, Serialize};
+use std::collections::{BTreeSet, HashMap, HashSet};
 use std::ffi::OsString;
+use std::fmt;
+use std::ops::{Deref, DerefMut};
 use std::str::FromStr;
+use std::time::Duration;
 use url::Url;
 
+/// Path of the `nfdc` binary
+pub const NFDC: &str = "/usr/bin/nfdc";
+
+/// Exit code of `nfdc face show` for an unknown face
+const FACE_NOT_FOUND: i32 = 3;
+
 pub enum NfdcCommand {
     Status,
+    /// Status of a single face, by id
+    FaceShow(u64),
 }
 
 impl Command for NfdcCommand {
-    fn to_command(&self) -> Vec<OsString> {
+    fn to_command_with(&self, config: &CommandConfig) -> Vec<OsString> {
         match self {
-            NfdcCommand::Status => ["/usr/bin/nfdc", "status", "report", "xml"],
+            NfdcCommand::Status => config.command(&config.nfdc, ["status", "report", "xml"]),
+            NfdcCommand::FaceShow(face_id) => config.command(
+                &config.nfdc,
+                ["face", "show", "id", &face_id.to_string(), "xml"],
+            ),
         }
-        .iter()
-        .map(OsString::from)
-        .collect()
+    }
+}
+
+/// Fetch the status of the forwarder, parsing the report while `nfdc` writes it
+///
+/// Meant for large routers, where buffering the whole report is expensive.
+pub async fn status_streamed() -> Result<NfdcStatus, Error> {
+    ProcessRunner
+        .run_streamed(NfdcCommand::Status.to_command(), |stdout| {
+            NfdcStatus::from_reader(stdout)
+        })
+        .await
+}
+
+/// Fetch the status of a single face
+///
+/// A missing face is reported as [`Error::FaceNotFound`].
+pub async fn face_show(face_id: u64, runner: &dyn Runner) -> Result<Face, Error> {
+    match NfdcCommand::FaceShow(face_id).run_with(runner).await {
+        Err(Error::ExitCode(FACE_NOT_FOUND, _)) => Err(Error::FaceNotFound(face_id)),
+        output => Ok(serde_xml_rs::from_str::<Face>(&output?)?),
     }
 }
 
@@ -29,6 +78,137 @@ pub struct NfdcStatus {
     pub rib: Rib,
     pub cs: Cs,
     pub strategy_choices: StrategyChoices,
+    /// SHA-256 digest of the report the status was parsed from, if recorded
+    #[serde(skip)]
+    pub source_digest: Option<String>,
+    /// Time the command printing the report completed, if known
+    #[serde(skip)]
+    pub completed_at: Option<DateTime<Utc>>,
+}
+
+/// Face skipped while parsing a report, see [`NfdcStatus::parse_recovering`]
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ParseWarning {
+    /// Position of the face in the report
+    pub position: usize,
+    pub message: String,
+}
+
+impl NfdcStatus {
+    /// Parse a report, skipping the faces that cannot be parsed
+    ///
+    /// Each skipped face is reported by a warning, while malformed content outside of the faces
+    /// still fails the whole report.
+    pub fn parse_recovering(xml: &str) -> Result<(Self, Vec<ParseWarning>), Error> {
+        let (start, end) = match (xml.find("<faces>"), xml.find("</faces>")) {
+            (Some(start), Some(end)) if start < end => (start + "<faces>".len(), end),
+            _ => return Ok((serde_xml_rs::from_str(xml)?, Vec::new())),
+        };
+        let mut faces = Vec::new();
+        let mut warnings = Vec::new();
+        for (position, chunk) in xml[start..end].split("<face>").skip(1).enumerate() {
+            let face = format!("<face>{}", chunk);
+            match serde_xml_rs::from_str::<Face>(&face) {
+                Ok(face) => faces.push(face),
+                Err(e) => warnings.push(ParseWarning {
+                    position,
+                    message: e.to_string(),
+                }),
+            }
+        }
+        let rest = format!("{}{}", &xml[..start], &xml[end..]);
+        let mut status: NfdcStatus = serde_xml_rs::from_str(&rest)?;
+        status.faces.face = faces;
+        Ok((status, warnings))
+    }
+
+    /// Parse a report while reading it, without buffering the whole document
+    pub fn from_reader<R: std::io::Read>(reader: R) -> Result<Self, Error> {
+        Ok(serde_xml_rs::from_reader(reader)?)
+    }
+
+    /// Check that no two faces share the same id
+    ///
+    /// Per-face metrics are keyed by face id, so a report with duplicate ids is rejected rather
+    /// than silently collapsing the faces.
+    pub fn check_face_ids(&self) -> Result<(), Error> {
+        let mut face_ids = HashSet::new();
+        match self.faces.face.iter().find(|f| !face_ids.insert(f.face_id)) {
+            Some(face) => Err(Error::DuplicateFaceId(face.face_id)),
+            None => Ok(()),
+        }
+    }
+
+    /// Drop the internal faces of the forwarder, see [`Face::is_internal`]
+    pub fn exclude_internal_faces(&mut self) {
+        self.faces.face.retain(|face| !face.is_internal());
+    }
+
+    /// Drop the faces with one of the given ids, or with a remote uri matching one of the
+    /// patterns, see [`glob_match`]
+    pub fn exclude_faces(&mut self, face_ids: &[u64], remote_uris: &[String]) {
+        self.faces.face.retain(|face| {
+            !face_ids.contains(&face.face_id)
+                && !remote_uris
+                    .iter()
+                    .any(|pattern| glob_match(pattern, &face.remote_uri))
+        });
+    }
+
+    /// Per second rates of each face since a previous snapshot, taken `elapsed` time before
+    ///
+    /// Faces that disappeared are left out, while faces that appeared were created after the
+    /// previous snapshot and are measured from zero. Without elapsed time there are no rates.
+    pub fn face_rates(&self, previous: &NfdcStatus, elapsed: Duration) -> HashMap<u64, FaceRate> {
+        if elapsed.as_secs_f64() == 0.0 {
+            return HashMap::new();
+        }
+        self.faces
+            .face
+            .iter()
+            .map(|face| {
+                let previous = previous
+                    .faces
+                    .face
+                    .iter()
+                    .find(|f| f.face_id == face.face_id);
+                let (packets, bytes) = match previous {
+                    Some(previous) => (
+                        face.packet_counters.since(&previous.packet_counters),
+                        face.byte_counters.since(&previous.byte_counters),
+                    ),
+                    None => (face.packet_counters.clone(), face.byte_counters.clone()),
+                };
+                (face.face_id, FaceRate::new(&packets, &bytes, elapsed))
+            })
+            .collect()
+    }
+}
+
+/// Per second packet and byte rates of a face
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct FaceRate {
+    pub incoming_interests: f64,
+    pub incoming_data: f64,
+    pub outgoing_interests: f64,
+    pub outgoing_data: f64,
+    pub incoming_bytes: f64,
+    pub outgoing_bytes: f64,
+}
+
+impl FaceRate {
+    fn new(packets: &PacketCounters, bytes: &ByteCounters, elapsed: Duration) -> Self {
+        let seconds = elapsed.as_secs_f64();
+        Self {
+            incoming_interests: packets.incoming_packets.n_interests as f64 / seconds,
+            incoming_data: packets.incoming_packets.n_data as f64 / seconds,
+            outgoing_interests: packets.outgoing_packets.n_interests as f64 / seconds,
+            outgoing_data: packets.outgoing_packets.n_data as f64 / seconds,
+            incoming_bytes: bytes.incoming_bytes as f64 / seconds,
+            outgoing_bytes: bytes.outgoing_bytes as f64 / seconds,
+        }
+    }
 }
 
 #[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
@@ -48,11 +228,144 @@ pub struct GeneralStatus {
     pub n_unsatisfied_interests: u64,
 }
 
+impl GeneralStatus {
+    /// Parsed [`GeneralStatus::version`]
+    pub fn parsed_version(&self) -> Result<Version, Error> {
+        self.version
+            .parse()
+            .map_err(|e: ParseVersionError| Error::NomParsingError(e.to_string()))
+    }
+
+    /// Parsed [`GeneralStatus::current_time`], reported by NFD in UTC
+    pub fn current_time_utc(&self) -> Result<DateTime<Utc>, Error> {
+        all_consuming(parse_ndn_timestamp)(self.current_time.trim())
+            .map(|(_, time)| time)
+            .map_err(|e| Error::NomParsingError(format!("{}: {}", self.current_time, e)))
+    }
+
+    /// Detect a reset of the cumulative counters since the `previous` status
+    ///
+    /// Rates computed across a reset are meaningless and should be skipped.
+    pub fn reset_since(&self, previous: &GeneralStatus) -> Option<CounterReset> {
+        if self.start_time != previous.start_time {
+            return Some(CounterReset::Restart {
+                previous_start_time: previous.start_time.clone(),
+                start_time: self.start_time.clone(),
+            });
+        }
+        let counters = |s: &GeneralStatus| {
+            [
+                s.packet_counters.incoming_packets.n_interests,
+                s.packet_counters.incoming_packets.n_data,
+                s.packet_counters.incoming_packets.n_nacks,
+                s.packet_counters.outgoing_packets.n_interests,
+                s.packet_counters.outgoing_packets.n_data,
+                s.packet_counters.outgoing_packets.n_nacks,
+                s.n_satisfied_interests,
+                s.n_unsatisfied_interests,
+            ]
+        };
+        if counters(self)
+            .iter()
+            .zip(counters(previous).iter())
+            .any(|(current, previous)| current < previous)
+        {
+            Some(CounterReset::CounterDecrease)
+        } else {
+            None
+        }
+    }
+}
+
+/// Reason of a cumulative counters reset
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub enum CounterReset {
+    /// NFD restarted, as its start time changed
+    Restart {
+        previous_start_time: String,
+        start_time: String,
+    },
+    /// Some cumulative counter decreased without a start time change
+    CounterDecrease,
+}
+
 #[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
 pub struct PacketCounters {
-    pub incoming_packets: PacketCountersEntry,
-    pub outgoing_packets: PacketCountersEntry,
+    pub incoming_packets: InCounters,
+    pub outgoing_packets: OutCounters,
+}
+
+impl PacketCounters {
+    /// Packets counted since a previous reading of the counters
+    pub fn since(&self, previous: &PacketCounters) -> PacketCounters {
+        PacketCounters {
+            incoming_packets: self.incoming_packets.since(&previous.incoming_packets),
+            outgoing_packets: self.outgoing_packets.since(&previous.outgoing_packets),
+        }
+    }
+
+    /// Incoming interests not answered by outgoing data or nacks
+    pub fn unanswered_interests(&self) -> i64 {
+        unanswered_interests(&self.incoming_packets, &self.outgoing_packets)
+    }
+}
+
+/// Incoming interests not answered by outgoing data or nacks
+///
+/// Taking the counters by direction rules out comparing two incoming or two outgoing counters.
+pub fn unanswered_interests(incoming: &InCounters, outgoing: &OutCounters) -> i64 {
+    incoming.n_interests as i64 - outgoing.n_data as i64 - outgoing.n_nacks as i64
+}
+
+/// Counters of the packets received by the forwarder or one of its faces
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(transparent)]
+pub struct InCounters(pub PacketCountersEntry);
+
+impl InCounters {
+    /// Packets received since a previous reading of the counters
+    pub fn since(&self, previous: &InCounters) -> InCounters {
+        InCounters(self.0.since(&previous.0))
+    }
+}
+
+impl Deref for InCounters {
+    type Target = PacketCountersEntry;
+    fn deref(&self) -> &Self::Target {
+        &self.0
+    }
+}
+
+impl DerefMut for InCounters {
+    fn deref_mut(&mut self) -> &mut Self::Target {
+        &mut self.0
+    }
+}
+
+/// Counters of the packets sent by the forwarder or one of its faces
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(transparent)]
+pub struct OutCounters(pub PacketCountersEntry);
+
+impl OutCounters {
+    /// Packets sent since a previous reading of the counters
+    pub fn since(&self, previous: &OutCounters) -> OutCounters {
+        OutCounters(self.0.since(&previous.0))
+    }
+}
+
+impl Deref for OutCounters {
+    type Target = PacketCountersEntry;
+    fn deref(&self) -> &Self::Target {
+        &self.0
+    }
+}
+
+impl DerefMut for OutCounters {
+    fn deref_mut(&mut self) -> &mut Self::Target {
+        &mut self.0
+    }
 }
 
 #[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
@@ -63,6 +376,28 @@ pub struct PacketCountersEntry {
     pub n_nacks: u64,
 }
 
+impl PacketCountersEntry {
+    /// Packets counted since a previous reading of the counters
+    pub fn since(&self, previous: &PacketCountersEntry) -> PacketCountersEntry {
+        PacketCountersEntry {
+            n_interests: self.n_interests.saturating_sub(previous.n_interests),
+            n_data: self.n_data.saturating_sub(previous.n_data),
+            n_nacks: self.n_nacks.saturating_sub(previous.n_nacks),
+        }
+    }
+
+    /// Ratio of nacks over interests
+    ///
+    /// Without interests there is nothing to nack, so the ratio is zero.
+    pub fn nack_ratio(&self) -> f64 {
+        if self.n_interests == 0 {
+            0.0
+        } else {
+            self.n_nacks as f64 / self.n_interests as f64
+        }
+    }
+}
+
 #[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
 pub struct Channels {
@@ -75,9 +410,41 @@ pub struct Channel {
     pub local_uri: Url,
 }
 
+impl Channels {
+    /// Transport schemes of the channels, e.g. `udp6` or `ws`
+    ///
+    /// The `unix` socket of local applications and the `dev` Ethernet channels are transports
+    /// like any other, so they are listed too.
+    pub fn schemes(&self) -> BTreeSet<String> {
+        self.channel
+            .iter()
+            .map(|channel| channel.local_uri.scheme().to_string())
+            .collect()
+    }
+}
+
+/// Channels listed by `nfdc channel list`, one local URI per line, e.g. `unix:///run/nfd.sock`
+impl FromStr for Channels {
+    type Err = Error;
+    fn from_str(input: &str) -> Result<Self, Self::Err> {
+        let channel = input
+            .lines()
+            .map(str::trim)
+            .filter(|line| !line.is_empty())
+            .map(|line| {
+                Url::parse(line)
+                    .map(|local_uri| Channel { local_uri })
+                    .map_err(|e| Error::NomParsingError(format!("Invalid channel {}: {}", line, e)))
+            })
+            .collect::<Result<_, _>>()?;
+        Ok(Channels { channel })
+    }
+}
+
 #[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
 pub struct Faces {
+    #[serde(default)]
     pub face: Vec<Face>,
 }
 
@@ -90,8 +457,12 @@ pub struct Face {
     pub face_scope: String,
     pub face_persistency: String,
     pub link_type: String,
+    /// Empty when the face has no congestion settings
+    #[serde(default)]
     pub congestion: Congestion,
-    pub mtu: u64,
+    /// Missing when the MTU of the face is unlimited
+    #[serde(default)]
+    pub mtu: Option<u64>,
     pub flags: FaceFlags,
     pub packet_counters: PacketCounters,
     pub byte_counters: ByteCounters,
@@ -101,13 +472,193 @@ pub struct Face {
     pub data_packet_components: PacketStatistics,
 }
 
-#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+/// URI schemes of the faces internal to the forwarder
+const INTERNAL_SCHEMES: [&str; 3] = ["internal", "contentstore", "null"];
+
+impl Face {
+    /// Whether the face is internal to the forwarder rather than a link
+    ///
+    /// Internal faces are local and use the `internal://`, `contentstore://` or `null://` scheme.
+    pub fn is_internal(&self) -> bool {
+        let scheme = self.local_uri.split("://").next().unwrap_or_default();
+        self.face_scope == "local" && INTERNAL_SCHEMES.contains(&scheme)
+    }
+
+    /// Base congestion marking interval, `None` if congestion marking is not enabled
+    pub fn marking_interval(&self) -> Option<Result<Duration, Error>> {
+        self.flags.congestion_marking_enabled.as_ref()?;
+        self.congestion
+            .base_marking_interval
+            .as_deref()
+            .map(parse_marking_interval)
+    }
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
 pub struct Congestion {
     pub base_marking_interval: Option<String>,
     pub default_threshold: Option<u64>,
 }
 
+/// `key=value` field of the congestion block printed by `nfdc face`
+fn congestion_field(input: &str) -> IResult<&str, (&str, &str)> {
+    let token = |c: char| c == '=' || c == '}' || c.is_whitespace();
+    separated_pair(take_till1(token), char('='), take_till1(token))(input)
+}
+
+/// Congestion block of the text output of `nfdc face`, e.g.
+/// `congestion={base-marking-interval=100ms default-threshold=65536B}`
+///
+/// The fields are checked and stored as in the XML status, unknown fields are ignored.
+impl FromStr for Congestion {
+    type Err = Error;
+    fn from_str(input: &str) -> Result<Self, Self::Err> {
+        let input = input.trim();
+        let input = input.strip_prefix("congestion=").unwrap_or(input);
+        let (_, fields) = all_consuming(delimited(
+            char('{'),
+            separated_list0(space1, congestion_field),
+            char('}'),
+        ))(input)
+        .map_err(|e| Error::NomParsingError(format!("{}", e)))?;
+        let mut congestion = Congestion {
+            base_marking_interval: None,
+            default_threshold: None,
+        };
+        for (key, value) in fields {
+            match key {
+                "base-marking-interval" => {
+                    parse_marking_interval(value)?;
+                    congestion.base_marking_interval = Some(value.to_string());
+                }
+                "default-threshold" => {
+                    let threshold = ByteSize::from_str(value)
+                        .map_err(|e| Error::NomParsingError(e.to_string()))?;
+                    congestion.default_threshold = Some(threshold.as_u64());
+                }
+                _ => {}
+            }
+        }
+        Ok(congestion)
+    }
+}
+
+/// Attributes of a face in the text output of `nfdc face list`, e.g.
+/// `faceid=300 remote=udp4://10.0.0.2:6363 local=udp4://10.0.0.1:6363 congestion={...} mtu=8800`
+///
+/// Newer NFD versions also print `scope=`, `persistency=` and `link-type=` inline, they are
+/// captured when present. The counters and flags blocks are skipped.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TextFace {
+    pub face_id: u64,
+    pub remote_uri: String,
+    pub local_uri: String,
+    pub face_scope: Option<String>,
+    pub face_persistency: Option<String>,
+    pub link_type: Option<String>,
+    pub congestion: Option<Congestion>,
+    pub mtu: Option<u64>,
+}
+
+/// Block enclosed in braces, possibly holding nested blocks
+fn braced(input: &str) -> IResult<&str, &str> {
+    recognize(delimited(
+        char('{'),
+        many0(alt((braced, take_till1(|c| c == '{' || c == '}')))),
+        char('}'),
+    ))(input)
+}
+
+/// `key=value` attribute of a face line, the value being a word or a closed block
+fn face_attribute(input: &str) -> IResult<&str, (&str, &str)> {
+    separated_pair(
+        take_till1(|c: char| c == '=' || c.is_whitespace()),
+        char('='),
+        alt((
+            braced,
+            verify(take_till1(char::is_whitespace), |value: &str| {
+                !value.starts_with('{')
+            }),
+        )),
+    )(input)
+}
+
+impl FromStr for TextFace {
+    type Err = Error;
+    fn from_str(input: &str) -> Result<Self, Self::Err> {
+        let (_, attributes) = all_consuming(delimited(
+            space0,
+            separated_list0(space1, face_attribute),
+            space0,
+        ))(input.trim())
+        .map_err(|e| Error::NomParsingError(format!("{}", e)))?;
+        let attributes = attributes.into_iter().collect::<HashMap<_, _>>();
+        let required = |key: &str| {
+            attributes
+                .get(key)
+                .map(|value| value.to_string())
+                .ok_or_else(|| Error::NomParsingError(format!("Missing face attribute {}", key)))
+        };
+        let number = |value: &str| {
+            value
+                .parse::<u64>()
+                .map_err(|e| Error::NomParsingError(format!("Invalid number {}: {}", value, e)))
+        };
+        let optional = |key: &str| attributes.get(key).map(|value| value.to_string());
+        Ok(TextFace {
+            face_id: number(&required("faceid")?)?,
+            remote_uri: required("remote")?,
+            local_uri: required("local")?,
+            face_scope: optional("scope"),
+            face_persistency: optional("persistency"),
+            link_type: optional("link-type").or_else(|| optional("linkType")),
+            congestion: attributes
+                .get("congestion")
+                .map(|value| value.parse())
+                .transpose()?,
+            mtu: attributes
+                .get("mtu")
+                .map(|value| number(value))
+                .transpose()?,
+        })
+    }
+}
+
+fn marking_interval(input: &str) -> IResult<&str, Duration> {
+    let seconds = || verify(double, |s: &f64| s.is_finite() && *s >= 0.0);
+    alt((
+        map(
+            delimited(tag("PT"), seconds(), tag("S")),
+            Duration::from_secs_f64,
+        ),
+        map(
+            pair(seconds(), alt((tag("ns"), tag("us"), tag("ms"), tag("s")))),
+            |(value, unit)| {
+                Duration::from_secs_f64(
+                    value
+                        * match unit {
+                            "ns" => 1e-9,
+                            "us" => 1e-6,
+                            "ms" => 1e-3,
+                            _ => 1.0,
+                        },
+                )
+            },
+        ),
+    ))(input)
+}
+
+/// Parse a congestion marking interval
+///
+/// Both a number with a unit (`100ms`, `1s`) and the ISO 8601 duration reported by NFD
+/// (`PT0.100S`) are accepted.
+pub fn parse_marking_interval(input: &str) -> Result<Duration, Error> {
+    let (_, interval) = all_consuming(marking_interval)(input.trim())
+        .map_err(|e| Error::NomParsingError(format!("{}", e)))?;
+    Ok(interval)
+}
+
 #[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
 pub struct FaceFlags {
@@ -130,6 +681,16 @@ pub struct ByteCounters {
     pub outgoing_bytes: u64,
 }
 
+impl ByteCounters {
+    /// Bytes counted since a previous reading of the counters
+    pub fn since(&self, previous: &ByteCounters) -> ByteCounters {
+        ByteCounters {
+            incoming_bytes: self.incoming_bytes.saturating_sub(previous.incoming_bytes),
+            outgoing_bytes: self.outgoing_bytes.saturating_sub(previous.outgoing_bytes),
+        }
+    }
+}
+
 #[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
 pub struct PacketStatistics {
@@ -141,15 +702,54 @@ pub struct PacketStatistics {
     pub std_dev: f64,
 }
 
+/// Statistic value, a decimal number or the `-nan` printed by NFD without samples
+fn statistic(input: &str) -> IResult<&str, f64> {
+    alt((
+        map(preceded(opt(char('-')), tag_no_case("nan")), |_| f64::NAN),
+        double,
+    ))(input)
+}
+
+/// Value directly followed by its label, e.g. `43.5avg`
+fn labelled<'a, O, F>(value: F, label: &'static str) -> impl FnMut(&'a str) -> IResult<&'a str, O>
+where
+    F: FnMut(&'a str) -> IResult<&'a str, O>,
+{
+    preceded(space0, terminated(value, tag(label)))
+}
+
+/// Statistics in the text form `0min 48max 43.5avg 6.96419std_dev`
+///
+/// Each value is directly followed by its label, zero, negative and `-nan` values included.
+impl FromStr for PacketStatistics {
+    type Err = Error;
+    fn from_str(input: &str) -> Result<Self, Self::Err> {
+        let count = || map_res(digit1, u64::from_str);
+        let (_, (min, max, avg, std_dev)) = all_consuming(tuple((
+            labelled(count(), "min"),
+            labelled(count(), "max"),
+            labelled(statistic, "avg"),
+            labelled(statistic, "std_dev"),
+        )))(input.trim())
+        .map_err(|e| Error::NomParsingError(format!("{}", e)))?;
+        Ok(PacketStatistics {
+            min,
+            max,
+            avg,
+            std_dev,
+        })
+    }
+}
+
 fn deserialize_f64<'de, D>(deser: D) -> Result<f64, D::Error>
 where
     D: Deserializer<'de>,
 {
     let s = String::deserialize(deser)?;
-    match s.as_str() {
-        "-nan" | "nan" => Ok(f64::NAN),
-        s => f64::from_str(s).map_err(serde::de::Error::custom),
-    }
+    let value = all_consuming(statistic)(s.trim())
+        .map(|(_, value)| value)
+        .map_err(|e| serde::de::Error::custom(format!("Invalid statistic {}: {}", s, e)));
+    value
 }
 
 #[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
@@ -158,6 +758,13 @@ pub struct Fib {
     pub fib_entry: Vec<FibEntry>,
 }
 
+impl Fib {
+    /// FIB entry of a prefix, regardless of its encoding
+    pub fn entry(&self, prefix: &str) -> Option<&FibEntry> {
+        self.fib_entry.iter().find(|e| name_eq(&e.prefix, prefix))
+    }
+}
+
 #[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
 pub struct FibEntry {
@@ -184,6 +791,13 @@ pub struct Rib {
     pub rib_entry: Vec<RibEntry>,
 }
 
+impl Rib {
+    /// RIB entry of a prefix, regardless of its encoding
+    pub fn entry(&self, prefix: &str) -> Option<&RibEntry> {
+        self.rib_entry.iter().find(|e| name_eq(&e.prefix, prefix))
+    }
+}
+
 #[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
 pub struct RibEntry {
@@ -234,6 +848,16 @@ pub struct Cs {
     pub invalid_signature_packets: u64,
 }
 
+impl Cs {
+    /// Percentage of the capacity taken by the entries, `None` for a CS without capacity
+    pub fn usage_percent(&self) -> Option<f64> {
+        match self.capacity {
+            0 => None,
+            capacity => Some(self.n_entries as f64 / capacity as f64 * 100.0),
+        }
+    }
+}
+
 #[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
 pub struct AdmitEnabled;
@@ -248,10 +872,30 @@ pub struct StrategyChoices {
     pub strategy_choice: Vec<StrategyChoice>,
 }
 
+impl StrategyChoices {
+    /// Strategy chosen for a namespace, regardless of its encoding
+    pub fn strategy(&self, namespace: &str) -> Option<&Strategy> {
+        let namespace = NdnName::from(namespace);
+        self.strategy_choice
+            .iter()
+            .find(|sc| sc.namespace == namespace)
+            .map(|sc| &sc.strategy)
+    }
+
+    /// Strategy applying to a name, the one chosen for its longest namespace
+    pub fn strategy_for(&self, name: &NdnName) -> Option<&Strategy> {
+        self.strategy_choice
+            .iter()
+            .filter(|sc| name.starts_with(&sc.namespace))
+            .max_by_key(|sc| sc.namespace.components().len())
+            .map(|sc| &sc.strategy)
+    }
+}
+
 #[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
 pub struct StrategyChoice {
-    pub namespace: String,
+    pub namespace: NdnName,
     pub strategy: Strategy,
 }
 
@@ -261,10 +905,256 @@ pub struct Strategy {
     pub name: String,
 }
 
+impl Strategy {
+    /// Strategy name without the trailing version component
+    pub fn base_name(&self) -> &str {
+        strip_strategy_version(&self.name)
+    }
+
+    /// Whether this is the given strategy, ignoring versions and encoding
+    pub fn is(&self, name: &str) -> bool {
+        name_eq(self.base_name(), strip_strategy_version(name))
+    }
+}
+
+/// Strip the trailing version component from a strategy name
+///
+/// Both the `%FD` version marker and the `v=` typed component are recognized, e.g.
+/// `/localhost/nfd/strategy/best-route/%FD%05` becomes `/localhost/nfd/strategy/best-route`.
+pub fn strip_strategy_version(name: &str) -> &str {
+    let name = name.trim_end_matches('/');
+    match name.rfind('/') {
+        Some(i) if name[i + 1..].starts_with("%FD") || name[i + 1..].starts_with("v=") => {
+            &name[..i]
+        }
+        _ => name,
+    }
+}
+
+/// Whether `text` matches `pattern`, where `*` stands for any sequence of characters and `?`
+/// for any single character
+///
+/// A prefix is matched by a trailing `*`, e.g. `udp4://224.0.23.170:*`.
+pub fn glob_match(pattern: &str, text: &str) -> bool {
+    let pattern = pattern.chars().collect::<Vec<_>>();
+    let text = text.chars().collect::<Vec<_>>();
+    let (mut p, mut t) = (0, 0);
+    // Position of the last `*` and of the text it was matched at, to backtrack to
+    let mut star = None;
+    while t < text.len() {
+        match pattern.get(p) {
+            Some('*') => {
+                star = Some((p, t));
+                p += 1;
+            }
+            Some(c) if *c == '?' || *c == text[t] => {
+                p += 1;
+                t += 1;
+            }
+            _ => match star {
+                Some((star_p, star_t)) => {
+                    p = star_p + 1;
+                    t = star_t + 1;
+                    star = Some((star_p, star_t + 1));
+                }
+                None => return false,
+            },
+        }
+    }
+    pattern[p..].iter().all(|c| *c == '*')
+}
+
+/// Percent-decoded components of an NDN name in URI form
+///
+/// The `ndn:` scheme, empty components and the generic component type `8=` are dropped, so
+/// equivalent URIs have the same components.
+pub fn name_components(name: &str) -> Vec<Vec<u8>> {
+    let name = name.strip_prefix("ndn:").unwrap_or(name);
+    name.split('/')
+        .filter(|component| !component.is_empty())
+        .map(|component| {
+            let component = component.strip_prefix("8=").unwrap_or(component);
+            percent_decode_str(component).collect()
+        })
+        .collect()
+}
+
+/// NDN name, kept in the URI form it was given in and compared by its components
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(from = "String", into = "String")]
+pub struct NdnName {
+    uri: String,
+    components: Vec<Vec<u8>>,
+}
+
+impl NdnName {
+    /// Percent-decoded components, see [`name_components`]
+    pub fn components(&self) -> &[Vec<u8>] {
+        &self.components
+    }
+
+    /// Whether this is `prefix` or a name under it
+    pub fn starts_with(&self, prefix: &NdnName) -> bool {
+        self.components.starts_with(&prefix.components)
+    }
+}
+
+impl From<String> for NdnName {
+    fn from(uri: String) -> Self {
+        let components = name_components(&uri);
+        Self { uri, components }
+    }
+}
+
+impl From<&str> for NdnName {
+    fn from(uri: &str) -> Self {
+        Self::from(uri.to_string())
+    }
+}
+
+impl From<NdnName> for String {
+    fn from(name: NdnName) -> Self {
+        name.uri
+    }
+}
+
+impl fmt::Display for NdnName {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.uri)
+    }
+}
+
+impl PartialEq for NdnName {
+    fn eq(&self, other: &Self) -> bool {
+        self.components == other.components
+    }
+}
+
+impl Eq for NdnName {}
+
+/// Whether two NDN names in URI form are the same name
+pub fn name_eq(a: &str, b: &str) -> bool {
+    name_components(a) == name_components(b)
+}
+
+/// Whether an NDN name in URI form is `prefix` or a name under it
+pub fn name_starts_with(name: &str, prefix: &str) -> bool {
+    name_components(name).starts_with(&name_components(prefix))
+}
+
 #[cfg(test)]
 mod test {
     use super::*;
 
+    #[test]
+    fn parse_nfd_version() {
+        let status = serde_xml_rs::from_str::<NfdcStatus>(include_str!("nfdc_report.xml")).unwrap();
+        let version = status.general_status.parsed_version().unwrap();
+        assert_eq!(version.core(), Version::new(0, 7, 1));
+        assert_eq!(version.git.unwrap().commits, 26);
+    }
+
+    #[test]
+    fn parse_nfd_current_time() {
+        let status = serde_xml_rs::from_str::<NfdcStatus>(include_str!("nfdc_report.xml")).unwrap();
+        let time = status.general_status.current_time_utc().unwrap();
+        assert_eq!(time.to_rfc3339(), "2021-04-21T15:11:27.640+00:00");
+        let general_status = GeneralStatus {
+            current_time: "yesterday".to_string(),
+            ..status.general_status
+        };
+        assert!(general_status.current_time_utc().is_err());
+    }
+
+    #[test]
+    fn match_globs() {
+        assert!(glob_match("fd://70", "fd://70"));
+        assert!(!glob_match("fd://70", "fd://701"));
+        assert!(glob_match("fd://*", "fd://70"));
+        assert!(glob_match(
+            "udp4://224.0.23.170:*",
+            "udp4://224.0.23.170:56363"
+        ));
+        assert!(glob_match(
+            "udp6://*%wlp0s20f3]:*",
+            "udp6://[ff02::1234%wlp0s20f3]:56363"
+        ));
+        assert!(!glob_match(
+            "udp6://*%wlp0s20f3]:*",
+            "udp6://[ff02::1234%cni0]:56363"
+        ));
+        assert!(glob_match("fd://?0", "fd://70"));
+        assert!(glob_match("*", ""));
+        assert!(!glob_match("?", ""));
+    }
+
+    #[test]
+    fn recover_malformed_faces() {
+        let output = include_str!("nfdc_report.xml");
+        let (status, warnings) = NfdcStatus::parse_recovering(output).unwrap();
+        assert!(warnings.is_empty());
+        assert_eq!(
+            format!("{:?}", status),
+            format!(
+                "{:?}",
+                serde_xml_rs::from_str::<NfdcStatus>(output).unwrap()
+            )
+        );
+
+        let corrupted = output.replacen("<faceId>257</faceId>", "<faceId>oops</faceId>", 1);
+        assert!(serde_xml_rs::from_str::<NfdcStatus>(&corrupted).is_err());
+        let (status, warnings) = NfdcStatus::parse_recovering(&corrupted).unwrap();
+        assert_eq!(warnings.len(), 1);
+        assert_eq!(warnings[0].position, 4);
+        let ids = status
+            .faces
+            .face
+            .iter()
+            .map(|f| f.face_id)
+            .collect::<Vec<_>>();
+        assert_eq!(ids.len(), 21);
+        assert!(!ids.contains(&257));
+        assert_eq!(ids.last(), Some(&278));
+
+        let corrupted = output.replacen("<nFibEntries>2</nFibEntries>", "<nFibEntries/>", 1);
+        assert!(NfdcStatus::parse_recovering(&corrupted).is_err());
+    }
+
+    #[test]
+    fn snapshots() {
+        let status = serde_xml_rs::from_str::<NfdcStatus>(include_str!("nfdc_report.xml")).unwrap();
+        assert_snapshot("nfdc_report", &status);
+        let face = serde_xml_rs::from_str::<Face>(include_str!("nfdc_face.xml")).unwrap();
+        assert_snapshot("nfdc_face", &face);
+    }
+
+    #[test]
+    fn streamed_parsing() {
+        let output = include_str!("nfdc_report.xml");
+        let buffered = serde_xml_rs::from_str::<NfdcStatus>(output).unwrap();
+        let streamed = NfdcStatus::from_reader(output.as_bytes()).unwrap();
+        // Unmeasured averages are NaN, so compare the printed reports
+        assert_eq!(format!("{:?}", streamed), format!("{:?}", buffered));
+    }
+
+    #[async_std::test]
+    async fn streamed_process_output() {
+        let args = ["cat", "src/command/nfdc_report.xml"]
+            .iter()
+            .map(OsString::from)
+            .collect();
+        let streamed = ProcessRunner
+            .run_streamed(args, |stdout| NfdcStatus::from_reader(stdout))
+            .await
+            .unwrap();
+        let buffered =
+            serde_xml_rs::from_str::<NfdcStatus>(include_str!("nfdc_report.xml")).unwrap();
+        assert_eq!(
+            format!("{:?}", streamed.faces),
+            format!("{:?}", buffered.faces)
+        );
+    }
+
     #[test]
     fn parse_example_output() {
         let output = include_str!("nfdc_report.xml");
@@ -272,6 +1162,103 @@ mod test {
         println!("{:#?}", parsed_output);
     }
 
+    #[test]
+    fn nack_ratio() {
+        let entry = |n_interests, n_nacks| PacketCountersEntry {
+            n_interests,
+            n_data: 0,
+            n_nacks,
+        };
+        assert_eq!(entry(100, 5).nack_ratio(), 0.05);
+        assert_eq!(entry(4, 4).nack_ratio(), 1.0);
+        assert_eq!(entry(10, 0).nack_ratio(), 0.0);
+        assert_eq!(entry(0, 0).nack_ratio(), 0.0);
+        assert_eq!(entry(0, 3).nack_ratio(), 0.0);
+    }
+
+    #[test]
+    fn directed_counters() {
+        let entry = |n_interests, n_data, n_nacks| PacketCountersEntry {
+            n_interests,
+            n_data,
+            n_nacks,
+        };
+        let counters = PacketCounters {
+            incoming_packets: InCounters(entry(100, 3, 5)),
+            outgoing_packets: OutCounters(entry(7, 80, 15)),
+        };
+        assert_eq!(counters.unanswered_interests(), 5);
+        assert_eq!(counters.incoming_packets.nack_ratio(), 0.05);
+        let previous = PacketCounters {
+            incoming_packets: InCounters(entry(40, 0, 5)),
+            outgoing_packets: OutCounters(entry(0, 30, 10)),
+        };
+        let delta = counters.since(&previous);
+        assert_eq!(delta.incoming_packets, InCounters(entry(60, 3, 0)));
+        assert_eq!(delta.outgoing_packets, OutCounters(entry(7, 50, 5)));
+        assert_eq!(
+            unanswered_interests(&delta.incoming_packets, &delta.outgoing_packets),
+            5
+        );
+        assert_eq!(
+            serde_json::to_value(&counters.incoming_packets).unwrap(),
+            serde_json::json!({"nInterests": 100, "nData": 3, "nNacks": 5})
+        );
+    }
+
+    #[test]
+    fn strip_strategy_versions() {
+        assert_eq!(
+            strip_strategy_version("/localhost/nfd/strategy/best-route/%FD%05"),
+            "/localhost/nfd/strategy/best-route"
+        );
+        assert_eq!(
+            strip_strategy_version("/localhost/nfd/strategy/multicast/%FD%03"),
+            "/localhost/nfd/strategy/multicast"
+        );
+        assert_eq!(
+            strip_strategy_version("/localhost/nfd/strategy/multicast/v=4"),
+            "/localhost/nfd/strategy/multicast"
+        );
+        assert_eq!(
+            strip_strategy_version("/localhost/nfd/strategy/multicast"),
+            "/localhost/nfd/strategy/multicast"
+        );
+        let strategy = Strategy {
+            name: "/localhost/nfd/strategy/best-route/%FD%05".to_string(),
+        };
+        assert_eq!(strategy.base_name(), "/localhost/nfd/strategy/best-route");
+    }
+
+    #[test]
+    fn detect_counter_reset() {
+        let output = include_str!("nfdc_report.xml");
+        let previous = serde_xml_rs::from_str::<NfdcStatus>(output)
+            .unwrap()
+            .general_status;
+        assert_eq!(previous.reset_since(&previous), None);
+
+        let mut current = previous.clone();
+        current.packet_counters.incoming_packets.n_interests += 10;
+        current.n_satisfied_interests += 10;
+        assert_eq!(current.reset_since(&previous), None);
+
+        current.packet_counters.incoming_packets.n_interests = 2;
+        assert_eq!(
+            current.reset_since(&previous),
+            Some(CounterReset::CounterDecrease)
+        );
+
+        current.start_time = "2021-04-21T15:12:00.000000".to_string();
+        assert_eq!(
+            current.reset_since(&previous),
+            Some(CounterReset::Restart {
+                previous_start_time: "2021-04-21T15:08:41.222000".to_string(),
+                start_time: "2021-04-21T15:12:00.000000".to_string(),
+            })
+        );
+    }
+
     #[ignore = "Must have a running system"]
     #[async_std::test]
     async fn parse_live_output() -> Result<(), Error> {
@@ -280,4 +1267,289 @@ mod test {
         println!("{:#?}", parsed_output);
         Ok(())
     }
+
+    #[test]
+    fn compare_encoded_names() {
+        assert!(name_eq("/ndn/broadcast", "/%6E%64%6E/broadcast"));
+        assert!(name_eq("/ndn/broadcast", "ndn:/ndn/broadcast/"));
+        assert!(name_eq("/ndn/broadcast", "/8=ndn/%62roadcast"));
+        assert!(name_eq("/", ""));
+        assert!(!name_eq("/ndn/broadcast", "/ndn%2Fbroadcast"));
+        assert!(!name_eq("/ndn/broadcast", "/ndn"));
+
+        let status = serde_xml_rs::from_str::<NfdcStatus>(include_str!("nfdc_report.xml")).unwrap();
+        assert!(status
+            .strategy_choices
+            .strategy("/%6Edn/broadcast")
+            .unwrap()
+            .is("/localhost/nfd/strategy/multicast"));
+        assert!(status.fib.entry("/localhost/nfd/%72ib").is_some());
+        assert!(status.rib.entry("/localhost/%6Efd").is_some());
+        assert!(status.fib.entry("/localhost/nfd/rib/extra").is_none());
+    }
+
+    #[test]
+    fn longest_prefix_strategy() {
+        let status = serde_xml_rs::from_str::<NfdcStatus>(include_str!("nfdc_report.xml")).unwrap();
+        let choices = status.strategy_choices;
+        let strategy_for = |name: &str| choices.strategy_for(&name.into()).unwrap().base_name();
+        let best_route = "/localhost/nfd/strategy/best-route";
+        let multicast = "/localhost/nfd/strategy/multicast";
+        assert_eq!(strategy_for("/app/data"), best_route);
+        assert_eq!(strategy_for("/"), best_route);
+        assert_eq!(strategy_for("/localhost/ping"), multicast);
+        assert_eq!(strategy_for("/localhost/nfd/rib"), best_route);
+        assert_eq!(strategy_for("/localhost/%6Efd"), best_route);
+        assert_eq!(strategy_for("/ndn/broadcast/hello"), multicast);
+        assert_eq!(strategy_for("/ndn/broadcastx"), best_route);
+
+        let empty = StrategyChoices {
+            strategy_choice: Vec::new(),
+        };
+        assert!(empty.strategy_for(&"/app".into()).is_none());
+        assert_eq!(
+            NdnName::from("/8=app/%64ata"),
+            NdnName::from("ndn:/app/data")
+        );
+        assert_eq!(NdnName::from("/app/%64ata").to_string(), "/app/%64ata");
+    }
+
+    #[test]
+    fn parse_text_statistics() {
+        let statistics: PacketStatistics = "0min 48max 43.5avg 6.96419std_dev".parse().unwrap();
+        assert_eq!(
+            statistics,
+            PacketStatistics {
+                min: 0,
+                max: 48,
+                avg: 43.5,
+                std_dev: 6.96419,
+            }
+        );
+        let zeros: PacketStatistics = "0min 0max 0avg 0std_dev".parse().unwrap();
+        assert_eq!((zeros.avg, zeros.std_dev), (0.0, 0.0));
+        let abutting: PacketStatistics = "0min0max0avg0std_dev".parse().unwrap();
+        assert_eq!(abutting, zeros);
+        let negative: PacketStatistics = " 0min 10max -1.5avg 0.5std_dev\n".parse().unwrap();
+        assert_eq!(negative.avg, -1.5);
+        let idle: PacketStatistics =
+            "18446744073709551615min 18446744073709551615max -nanavg 0std_dev"
+                .parse()
+                .unwrap();
+        assert_eq!(idle.min, u64::MAX);
+        assert!(idle.avg.is_nan());
+
+        assert!("0min 48max avg 0std_dev"
+            .parse::<PacketStatistics>()
+            .is_err());
+        assert!("0min 48max 1avg".parse::<PacketStatistics>().is_err());
+        assert!("-1min 48max 1avg 0std_dev"
+            .parse::<PacketStatistics>()
+            .is_err());
+
+        #[derive(Deserialize)]
+        struct Value {
+            #[serde(deserialize_with = "deserialize_f64")]
+            value: f64,
+        }
+        let value = |xml: &str| serde_xml_rs::from_str::<Value>(xml).map(|v| v.value);
+        assert_eq!(value("<v><value>0</value></v>").unwrap(), 0.0);
+        assert_eq!(value("<v><value>-2.25</value></v>").unwrap(), -2.25);
+        assert!(value("<v><value>-nan</value></v>").unwrap().is_nan());
+        assert!(value("<v><value>2avg</value></v>").is_err());
+    }
+
+    #[test]
+    fn channel_schemes() {
+        let status = serde_xml_rs::from_str::<NfdcStatus>(include_str!("nfdc_report.xml")).unwrap();
+        let schemes = status.channels.schemes();
+        let expected = ["dev", "tcp4", "tcp6", "udp4", "udp6", "unix", "ws"];
+        assert_eq!(schemes.iter().collect::<Vec<_>>(), expected);
+
+        let listed = "dev://enp60s0\nudp6://[::]:6363\n\nunix:///run/nfd/nfd.sock\n";
+        let channels = listed.parse::<Channels>().unwrap();
+        assert_eq!(channels.channel.len(), 3);
+        assert_eq!(
+            channels.schemes().iter().collect::<Vec<_>>(),
+            ["dev", "udp6", "unix"]
+        );
+        assert!("not a uri".parse::<Channels>().is_err());
+    }
+
+    #[test]
+    fn parse_marking_intervals() {
+        assert_eq!(
+            parse_marking_interval("100ms").unwrap(),
+            Duration::from_millis(100)
+        );
+        assert_eq!(
+            parse_marking_interval("1s").unwrap(),
+            Duration::from_secs(1)
+        );
+        assert_eq!(
+            parse_marking_interval("PT0.100S").unwrap(),
+            Duration::from_millis(100)
+        );
+        assert!(parse_marking_interval("100 parsecs").is_err());
+        assert!(parse_marking_interval("-1s").is_err());
+        assert!(parse_marking_interval("ms").is_err());
+
+        let status = serde_xml_rs::from_str::<NfdcStatus>(include_str!("nfdc_report.xml")).unwrap();
+        for face in status.faces.face.iter() {
+            match face.marking_interval() {
+                Some(interval) => assert_eq!(interval.unwrap(), Duration::from_millis(100)),
+                None => assert!(face.flags.congestion_marking_enabled.is_none()),
+            }
+        }
+    }
+
+    #[test]
+    fn cs_usage_percent() {
+        let status = serde_xml_rs::from_str::<NfdcStatus>(include_str!("nfdc_report.xml")).unwrap();
+        let mut cs = status.cs;
+        assert_eq!(cs.usage_percent(), Some(2.0 / 300.0 * 100.0));
+        cs.n_entries = cs.capacity;
+        assert_eq!(cs.usage_percent(), Some(100.0));
+        cs.capacity = 0;
+        assert_eq!(cs.usage_percent(), None);
+    }
+
+    #[test]
+    fn parse_text_congestion() {
+        let status = serde_xml_rs::from_str::<NfdcStatus>(include_str!("nfdc_report.xml")).unwrap();
+        let expected = &status.faces.face[0].congestion;
+        let congestion: Congestion =
+            "congestion={base-marking-interval=100ms default-threshold=65536B}"
+                .parse()
+                .unwrap();
+        assert_eq!(congestion.default_threshold, expected.default_threshold);
+        assert_eq!(
+            parse_marking_interval(congestion.base_marking_interval.as_ref().unwrap()).unwrap(),
+            parse_marking_interval(expected.base_marking_interval.as_ref().unwrap()).unwrap()
+        );
+
+        let congestion: Congestion = "{default-threshold=64KiB}".parse().unwrap();
+        assert_eq!(congestion.default_threshold, Some(65536));
+        assert_eq!(congestion.base_marking_interval, None);
+        assert_eq!("{}".parse::<Congestion>().unwrap().default_threshold, None);
+        assert!("{default-threshold=lots}".parse::<Congestion>().is_err());
+        assert!("{base-marking-interval=100}".parse::<Congestion>().is_err());
+        assert!("base-marking-interval=100ms".parse::<Congestion>().is_err());
+    }
+
+    #[test]
+    fn parse_text_faces() {
+        let older = "faceid=262 remote=udp4://192.168.1.2:6363 local=udp4://192.168.1.1:6363 \
+            congestion={base-marking-interval=100ms default-threshold=65536B} mtu=8800 \
+            counters={in={64i 70d 0n 12345B} out={64i 52d 0n 23456B}} \
+            flags={non-local permanent point-to-point}";
+        let face: TextFace = older.parse().unwrap();
+        assert_eq!(face.face_id, 262);
+        assert_eq!(face.local_uri, "udp4://192.168.1.1:6363");
+        assert_eq!(face.face_scope, None);
+        assert_eq!(face.link_type, None);
+        assert_eq!(face.mtu, Some(8800));
+        assert_eq!(face.congestion.unwrap().default_threshold, Some(65536));
+
+        let newer = "faceid=1 remote=internal:// local=internal:// scope=local \
+            persistency=permanent link-type=point-to-point counters={in={0i 0d 0n 0B} \
+            out={0i 0d 0n 0B}} flags={local}";
+        let face: TextFace = newer.parse().unwrap();
+        assert_eq!(face.face_scope.as_deref(), Some("local"));
+        assert_eq!(face.face_persistency.as_deref(), Some("permanent"));
+        assert_eq!(face.link_type.as_deref(), Some("point-to-point"));
+        assert_eq!(face.congestion, None);
+        assert_eq!(face.mtu, None);
+        let camel_case = "faceid=1 remote=a:// local=b:// linkType=multi-access";
+        let face: TextFace = camel_case.parse().unwrap();
+        assert_eq!(face.link_type.as_deref(), Some("multi-access"));
+
+        assert!("remote=a:// local=b://".parse::<TextFace>().is_err());
+        assert!("faceid=x remote=a:// local=b://"
+            .parse::<TextFace>()
+            .is_err());
+        assert!("faceid=1 remote=a:// local=b:// flags={local"
+            .parse::<TextFace>()
+            .is_err());
+    }
+
+    #[test]
+    fn face_rates_between_snapshots() {
+        let previous =
+            serde_xml_rs::from_str::<NfdcStatus>(include_str!("nfdc_report.xml")).unwrap();
+        let mut current = previous.clone();
+        let gone = current.faces.face.remove(0).face_id;
+        let face = &mut current.faces.face[0];
+        face.packet_counters.incoming_packets.n_interests += 20;
+        face.packet_counters.outgoing_packets.n_data += 10;
+        face.byte_counters.incoming_bytes += 4000;
+        let changed = face.face_id;
+        let mut new_face = current.faces.face[1].clone();
+        new_face.face_id = 1000;
+        new_face.packet_counters.incoming_packets.n_interests = 4;
+        current.faces.face.push(new_face);
+
+        let rates = current.face_rates(&previous, Duration::from_secs(2));
+        assert_eq!(rates.len(), previous.faces.face.len());
+        assert!(!rates.contains_key(&gone));
+        let rate = &rates[&changed];
+        assert_eq!(rate.incoming_interests, 10.0);
+        assert_eq!(rate.outgoing_data, 5.0);
+        assert_eq!(rate.incoming_bytes, 2000.0);
+        assert_eq!(rate.outgoing_bytes, 0.0);
+        assert_eq!(rates[&1000].incoming_interests, 2.0);
+        assert!(current
+            .face_rates(&previous, Duration::from_secs(0))
+            .is_empty());
+    }
+
+    #[test]
+    fn exclude_internal_faces() {
+        let mut status =
+            serde_xml_rs::from_str::<NfdcStatus>(include_str!("nfdc_report.xml")).unwrap();
+        let internal = status
+            .faces
+            .face
+            .iter()
+            .filter(|f| f.is_internal())
+            .map(|f| f.remote_uri.as_str())
+            .collect::<Vec<_>>();
+        assert_eq!(internal, ["internal://", "contentstore://", "null://"]);
+        let n_faces = status.faces.face.len();
+        status.exclude_internal_faces();
+        assert_eq!(status.faces.face.len(), n_faces - 3);
+        assert!(status.faces.face.iter().all(|f| !f.is_internal()));
+    }
+
+    #[test]
+    fn duplicate_face_ids() {
+        let status = serde_xml_rs::from_str::<NfdcStatus>(include_str!("nfdc_report.xml")).unwrap();
+        assert!(status.check_face_ids().is_ok());
+        let output = include_str!("nfdc_report_duplicate_face.xml");
+        let status = serde_xml_rs::from_str::<NfdcStatus>(output).unwrap();
+        assert!(matches!(
+            status.check_face_ids(),
+            Err(Error::DuplicateFaceId(1))
+        ));
+    }
+
+    #[async_std::test]
+    async fn show_single_face() {
+        let runner = MockRunner::default()
+            .with_output(
+                &NfdcCommand::FaceShow(265),
+                Ok(include_str!("nfdc_face.xml").to_string()),
+            )
+            .with_output(
+                &NfdcCommand::FaceShow(42),
+                Err(Error::ExitCode(3, "Face not found".to_string())),
+            );
+        let face = face_show(265, &runner).await.unwrap();
+        assert_eq!(face.face_id, 265);
+        assert_eq!(face.remote_uri, "udp4://224.0.23.170:56363");
+        assert!(matches!(
+            face_show(42, &runner).await,
+            Err(Error::FaceNotFound(42))
+        ));
+    }
 }
@@ -0,0 +1,171 @@
+//! Recording of the raw command outputs and their replay, to build fixtures from real nodes
+//!
+//! A recording directory holds one file per command output, named after the time the command
+//! completed, and an index `commands.jsonl` mapping each command line to its output file.
+//! The outputs are the ones of `nfdc` and `ndnsec`: the forwarder status and the certificates
+//! of the keychain, public keys included. Private keys are never printed by these commands,
+//! still review a recording before sharing it, as it discloses the node configuration.
+
+use super::{Error, Runner};
+use async_std::io::prelude::WriteExt;
+use async_trait::async_trait;
+use chrono::Utc;
+use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, VecDeque};
+use std::ffi::OsString;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::Mutex;
+
+/// Name of the index of a recording directory
+pub const INDEX: &str = "commands.jsonl";
+
+/// Line of the index of a recording
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+struct Recorded {
+    command: Vec<String>,
+    output: String,
+}
+
+/// Runs commands with another runner, storing their successful outputs in a directory
+#[derive(Debug)]
+pub struct RecordingRunner<R> {
+    inner: R,
+    dir: PathBuf,
+    count: AtomicU64,
+}
+
+impl<R: Runner> RecordingRunner<R> {
+    /// Record the outputs of `inner` in `dir`, created if missing
+    pub fn new<P: AsRef<Path>>(inner: R, dir: P) -> Result<Self, Error> {
+        let dir = dir.as_ref().to_path_buf();
+        fs::create_dir_all(&dir)?;
+        Ok(Self {
+            inner,
+            dir,
+            count: AtomicU64::new(0),
+        })
+    }
+
+    async fn record(&self, args: &[OsString], output: &str) -> Result<(), Error> {
+        let count = self.count.fetch_add(1, Ordering::SeqCst);
+        let name = format!("{}-{}.out", Utc::now().format("%Y%m%dT%H%M%S%.6f"), count);
+        async_std::fs::write(self.dir.join(&name), output).await?;
+        let recorded = Recorded {
+            command: args.iter().map(|a| a.to_string_lossy().into()).collect(),
+            output: name,
+        };
+        let line = serde_json::to_string(&recorded).map_err(|e| Error::IoError(e.to_string()))?;
+        let mut index = async_std::fs::OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(self.dir.join(INDEX))
+            .await?;
+        index.write_all(format!("{}\n", line).as_bytes()).await?;
+        index.flush().await?;
+        Ok(())
+    }
+}
+
+#[async_trait]
+impl<R: Runner> Runner for RecordingRunner<R> {
+    async fn run(&self, args: &[OsString]) -> Result<String, Error> {
+        let output = self.inner.run(args).await?;
+        // A recording that cannot be written leaves the measurement as it is
+        if let Err(e) = self.record(args, &output).await {
+            eprintln!("Cannot record in {}: {}", self.dir.display(), e);
+        }
+        Ok(output)
+    }
+}
+
+/// Replies to commands with the outputs of a recording, in the order they were recorded
+///
+/// Once the outputs of a command are exhausted, its last output is repeated.
+#[derive(Debug, Default)]
+pub struct ReplayRunner {
+    outputs: Mutex<HashMap<Vec<OsString>, VecDeque<String>>>,
+}
+
+impl ReplayRunner {
+    /// Load the recording in `dir`
+    pub fn open<P: AsRef<Path>>(dir: P) -> Result<Self, Error> {
+        let dir = dir.as_ref();
+        let mut outputs = HashMap::<_, VecDeque<_>>::new();
+        for line in fs::read_to_string(dir.join(INDEX))?.lines() {
+            let recorded: Recorded = serde_json::from_str(line)
+                .map_err(|e| Error::OutputError(format!("Invalid recording index: {}", e)))?;
+            let output = fs::read_to_string(dir.join(&recorded.output))?;
+            let command = recorded.command.into_iter().map(OsString::from).collect();
+            outputs.entry(command).or_default().push_back(output);
+        }
+        Ok(Self {
+            outputs: Mutex::new(outputs),
+        })
+    }
+}
+
+#[async_trait]
+impl Runner for ReplayRunner {
+    async fn run(&self, args: &[OsString]) -> Result<String, Error> {
+        let mut outputs = self.outputs.lock().unwrap();
+        let recorded = outputs
+            .get_mut(args)
+            .ok_or_else(|| Error::OutputError(format!("No output recorded for {:?}", args)))?;
+        match recorded.len() {
+            1 => Ok(recorded[0].clone()),
+            _ => Ok(recorded.pop_front().unwrap_or_default()),
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::command::MockRunner;
+    use crate::command::{ndnsec::NdnSecCommand, nfdc::NfdcCommand, Command};
+
+    /// Empty directory for a test, removed first if left by a previous run
+    fn test_dir(name: &str) -> PathBuf {
+        let dir = std::env::temp_dir().join(format!("ca-{}-{}", name, std::process::id()));
+        let _ = fs::remove_dir_all(&dir);
+        dir
+    }
+
+    #[async_std::test]
+    async fn record_and_replay() {
+        let dir = test_dir("record");
+        let status = NfdcCommand::Status.to_command();
+        let list = NdnSecCommand::List.to_command();
+        for output in ["first", "second"].iter() {
+            let mock = MockRunner::default()
+                .with_output(&NfdcCommand::Status, Ok(output.to_string()))
+                .with_output(&NdnSecCommand::List, Err(Error::FaceNotFound(0)));
+            let runner = RecordingRunner::new(mock, &dir).unwrap();
+            assert_eq!(runner.run(&status).await.unwrap(), *output);
+            assert!(runner.run(&list).await.is_err());
+        }
+        let index = fs::read_to_string(dir.join(INDEX)).unwrap();
+        assert_eq!(index.lines().count(), 2);
+
+        let replay = ReplayRunner::open(&dir).unwrap();
+        for expected in ["first", "second", "second"].iter() {
+            assert_eq!(replay.run(&status).await.unwrap(), *expected);
+        }
+        assert!(replay.run(&list).await.is_err());
+        assert!(ReplayRunner::open(dir.join("missing")).is_err());
+        fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[async_std::test]
+    async fn output_kept_when_not_recorded() {
+        let dir = test_dir("unrecorded");
+        let status = NfdcCommand::Status.to_command();
+        let mock = MockRunner::default().with_output(&NfdcCommand::Status, Ok("status".into()));
+        let runner = RecordingRunner::new(mock, &dir).unwrap();
+        fs::remove_dir_all(&dir).unwrap();
+        assert_eq!(runner.run(&status).await.unwrap(), "status");
+        assert!(!dir.exists());
+    }
+}
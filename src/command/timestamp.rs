@@ -0,0 +1,117 @@
+use chrono::{DateTime, Duration, TimeZone, Utc};
+use nom::{
+    branch::alt,
+    bytes::complete::{tag, take, take_while1},
+    character::complete::{char, one_of},
+    combinator::{map, map_opt, map_res, opt, verify},
+    sequence::{preceded, tuple},
+    IResult,
+};
+use std::str::FromStr;
+
+/// Exactly `digits` decimal digits
+fn number<'a, T: FromStr>(digits: usize) -> impl FnMut(&'a str) -> IResult<&'a str, T> {
+    map_res(
+        verify(take(digits), |s: &str| {
+            s.bytes().all(|b| b.is_ascii_digit())
+        }),
+        T::from_str,
+    )
+}
+
+/// Fractional seconds, in nanoseconds, ignoring digits past the nanosecond
+fn nanoseconds(input: &str) -> IResult<&str, u32> {
+    map_res(take_while1(|c: char| c.is_ascii_digit()), |digits: &str| {
+        format!("{:0<9}", &digits[..digits.len().min(9)]).parse()
+    })(input)
+}
+
+/// Offset from UTC in seconds, `Z`, `+hh:mm` or `-hhmm`
+fn utc_offset(input: &str) -> IResult<&str, i64> {
+    alt((
+        map(char('Z'), |_| 0),
+        map(
+            tuple((
+                one_of("+-"),
+                number::<i64>(2),
+                preceded(opt(char(':')), number::<i64>(2)),
+            )),
+            |(sign, hours, minutes)| {
+                let offset = hours * 3600 + minutes * 60;
+                if sign == '-' {
+                    -offset
+                } else {
+                    offset
+                }
+            },
+        ),
+    ))(input)
+}
+
+/// Timestamp printed by the NDN tools, in UTC
+///
+/// Both the compact `20210421T151127` of `ndnsec` and the extended
+/// `2021-04-21T15:11:27.640000` of the NFD status are accepted, with optional fractional seconds
+/// and an optional offset, without which the time is in UTC.
+pub fn parse_ndn_timestamp(input: &str) -> IResult<&str, DateTime<Utc>> {
+    let (input, time) = map_opt(
+        tuple((
+            number::<i32>(4),
+            preceded(opt(char('-')), number::<u32>(2)),
+            preceded(opt(char('-')), number::<u32>(2)),
+            preceded(tag("T"), number::<u32>(2)),
+            preceded(opt(char(':')), number::<u32>(2)),
+            preceded(opt(char(':')), number::<u32>(2)),
+            opt(preceded(char('.'), nanoseconds)),
+        )),
+        |(year, month, day, hour, minute, second, nanos)| {
+            Utc.ymd_opt(year, month, day)
+                .and_hms_nano_opt(hour, minute, second, nanos.unwrap_or(0))
+                .single()
+        },
+    )(input)?;
+    let (input, offset) = opt(utc_offset)(input)?;
+    Ok((input, time - Duration::seconds(offset.unwrap_or(0))))
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn parse_timestamps() {
+        let (rest, time) = parse_ndn_timestamp("20210421T151127\n").unwrap();
+        assert_eq!(rest, "\n");
+        assert_eq!(time, Utc.ymd(2021, 4, 21).and_hms(15, 11, 27));
+        let (_, time) = parse_ndn_timestamp("2021-04-21T15:11:27.640000").unwrap();
+        assert_eq!(time, Utc.ymd(2021, 4, 21).and_hms_milli(15, 11, 27, 640));
+        let (_, time) = parse_ndn_timestamp("20210421T151127.5").unwrap();
+        assert_eq!(time, Utc.ymd(2021, 4, 21).and_hms_milli(15, 11, 27, 500));
+    }
+
+    #[test]
+    fn parse_utc_offsets() {
+        let expected = Utc.ymd(2021, 2, 9).and_hms(14, 0, 51);
+        for input in [
+            "20210209T140051",
+            "20210209T140051Z",
+            "20210209T160051+02:00",
+            "20210209T093051-0430",
+        ]
+        .iter()
+        {
+            assert_eq!(parse_ndn_timestamp(input), Ok(("", expected)), "{}", input);
+        }
+        let (_, time) = parse_ndn_timestamp("20210209T140051.283000Z").unwrap();
+        assert_eq!(time, expected + Duration::milliseconds(283));
+    }
+
+    #[test]
+    fn reject_invalid_timestamps() {
+        assert!(parse_ndn_timestamp("20210231T151127").is_err());
+        assert!(parse_ndn_timestamp("20210421T251127").is_err());
+        assert!(parse_ndn_timestamp("2021042T151127").is_err());
+        assert!(parse_ndn_timestamp("+2021042T15112").is_err());
+        assert!(parse_ndn_timestamp("yesterday").is_err());
+    }
+}
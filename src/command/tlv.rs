@@ -0,0 +1,270 @@
+//! Encoding and decoding of the NDN packet format TLV elements needed to read certificates and
+//! to talk to the forwarder
+
+use crate::command::Error;
+use percent_encoding::percent_decode_str;
+use std::convert::TryFrom;
+
+/// Maximum size of an NDN packet
+pub const MAX_PACKET_SIZE: usize = 8800;
+
+pub const INTEREST: u64 = 0x05;
+pub const DATA: u64 = 0x06;
+pub const NAME: u64 = 0x07;
+pub const GENERIC_NAME_COMPONENT: u64 = 0x08;
+pub const IMPLICIT_SHA256_DIGEST: u64 = 0x01;
+pub const PARAMETERS_SHA256_DIGEST: u64 = 0x02;
+pub const SEGMENT_NAME_COMPONENT: u64 = 0x32;
+pub const CAN_BE_PREFIX: u64 = 0x21;
+pub const MUST_BE_FRESH: u64 = 0x12;
+pub const NONCE: u64 = 0x0a;
+pub const INTEREST_LIFETIME: u64 = 0x0c;
+pub const META_INFO: u64 = 0x14;
+pub const FINAL_BLOCK_ID: u64 = 0x1a;
+pub const CONTENT: u64 = 0x15;
+pub const SIGNATURE_INFO: u64 = 0x16;
+pub const SIGNATURE_TYPE: u64 = 0x1b;
+pub const KEY_LOCATOR: u64 = 0x1c;
+pub const KEY_DIGEST: u64 = 0x1d;
+pub const VALIDITY_PERIOD: u64 = 0xfd;
+pub const NOT_BEFORE: u64 = 0xfe;
+pub const NOT_AFTER: u64 = 0xff;
+pub const LP_PACKET: u64 = 0x64;
+pub const LP_FRAGMENT: u64 = 0x50;
+
+fn decoding_error(message: &str) -> Error {
+    Error::TlvDecodingError(format!("Invalid TLV: {}", message))
+}
+
+/// Split a variable size number, as used by types and lengths, off the input
+fn var_number(input: &[u8]) -> Result<(u64, &[u8]), Error> {
+    let (first, rest) = input
+        .split_first()
+        .ok_or_else(|| decoding_error("truncated number"))?;
+    let octets = match first {
+        253 => 2,
+        254 => 4,
+        255 => 8,
+        _ => return Ok((*first as u64, rest)),
+    };
+    if rest.len() < octets {
+        return Err(decoding_error("truncated number"));
+    }
+    Ok((non_negative_integer(&rest[..octets])?, &rest[octets..]))
+}
+
+/// Value of a non-negative integer element
+pub fn non_negative_integer(value: &[u8]) -> Result<u64, Error> {
+    match value.len() {
+        1 | 2 | 4 | 8 => Ok(value.iter().fold(0, |n, b| n << 8 | *b as u64)),
+        _ => Err(decoding_error("invalid non-negative integer")),
+    }
+}
+
+/// Split the next element off the input, returning its type and value
+pub fn next_element(input: &[u8]) -> Result<(u64, &[u8], &[u8]), Error> {
+    let (typ, rest) = var_number(input)?;
+    let (length, rest) = var_number(rest)?;
+    let length = length as usize;
+    if rest.len() < length {
+        return Err(decoding_error("truncated element"));
+    }
+    Ok((typ, &rest[..length], &rest[length..]))
+}
+
+/// Size of the first element of the input, `None` while the input does not hold all of it
+///
+/// Elements larger than a packet are rejected as soon as their length is read.
+pub fn element_size(input: &[u8]) -> Result<Option<usize>, Error> {
+    let header = var_number(input).and_then(|(_, rest)| var_number(rest));
+    let (length, value) = match header {
+        Ok(header) => header,
+        Err(_) => return Ok(None),
+    };
+    let size = usize::try_from(length)
+        .ok()
+        .and_then(|length| (input.len() - value.len()).checked_add(length))
+        .filter(|size| *size <= MAX_PACKET_SIZE)
+        .ok_or_else(|| decoding_error("element larger than a packet"))?;
+    Ok(Some(size).filter(|size| input.len() >= *size))
+}
+
+/// Type and value of the sub-elements of the value of an element
+pub fn elements(mut input: &[u8]) -> Result<Vec<(u64, &[u8])>, Error> {
+    let mut elements = Vec::new();
+    while !input.is_empty() {
+        let (typ, value, rest) = next_element(input)?;
+        elements.push((typ, value));
+        input = rest;
+    }
+    Ok(elements)
+}
+
+/// Value of the first sub-element of the given type
+pub fn find<'a>(elements: &[(u64, &'a [u8])], typ: u64) -> Result<&'a [u8], Error> {
+    elements
+        .iter()
+        .find(|(t, _)| *t == typ)
+        .map(|(_, value)| *value)
+        .ok_or_else(|| decoding_error(&format!("missing element {}", typ)))
+}
+
+/// Append a variable size number, as used by types and lengths
+fn encode_var_number(number: u64, output: &mut Vec<u8>) {
+    match number {
+        0..=252 => output.push(number as u8),
+        253..=0xffff => {
+            output.push(253);
+            output.extend_from_slice(&(number as u16).to_be_bytes());
+        }
+        0x10000..=0xffff_ffff => {
+            output.push(254);
+            output.extend_from_slice(&(number as u32).to_be_bytes());
+        }
+        _ => {
+            output.push(255);
+            output.extend_from_slice(&number.to_be_bytes());
+        }
+    }
+}
+
+/// Element of the given type and value
+pub fn encode(typ: u64, value: &[u8]) -> Vec<u8> {
+    let mut element = Vec::with_capacity(value.len() + 4);
+    encode_var_number(typ, &mut element);
+    encode_var_number(value.len() as u64, &mut element);
+    element.extend_from_slice(value);
+    element
+}
+
+/// Shortest value of a non-negative integer element
+pub fn encode_non_negative_integer(number: u64) -> Vec<u8> {
+    match number {
+        0..=0xff => vec![number as u8],
+        0x100..=0xffff => (number as u16).to_be_bytes().to_vec(),
+        0x10000..=0xffff_ffff => (number as u32).to_be_bytes().to_vec(),
+        _ => number.to_be_bytes().to_vec(),
+    }
+}
+
+/// `Name` element of an NDN URI made of generic components, e.g. `/localhost/nfd/status`
+pub fn encode_name(uri: &str) -> Vec<u8> {
+    let components = uri
+        .split('/')
+        .filter(|component| !component.is_empty())
+        .flat_map(|component| {
+            let value = percent_decode_str(component).collect::<Vec<_>>();
+            encode(GENERIC_NAME_COMPONENT, &value)
+        })
+        .collect::<Vec<_>>();
+    encode(NAME, &components)
+}
+
+/// Percent-encode a name component as in the NDN URI scheme
+fn escape(component: &[u8]) -> String {
+    let mut escaped = String::new();
+    if component.iter().all(|b| *b == b'.') {
+        escaped.push_str("...");
+    }
+    for b in component {
+        match b {
+            b'a'..=b'z' | b'A'..=b'Z' | b'0'..=b'9' | b'+' | b'-' | b'.' | b'_' | b'~' => {
+                escaped.push(*b as char)
+            }
+            _ => escaped.push_str(&format!("%{:02X}", b)),
+        }
+    }
+    escaped
+}
+
+/// NDN URI of the value of a `Name` element
+pub fn name_uri(name: &[u8]) -> Result<String, Error> {
+    let components = elements(name)?;
+    if components.is_empty() {
+        return Ok("/".to_string());
+    }
+    let hex = |value: &[u8]| {
+        value
+            .iter()
+            .map(|b| format!("{:02x}", b))
+            .collect::<String>()
+    };
+    Ok(components
+        .iter()
+        .map(|(typ, value)| match *typ {
+            GENERIC_NAME_COMPONENT => format!("/{}", escape(value)),
+            IMPLICIT_SHA256_DIGEST => format!("/sha256digest={}", hex(value)),
+            PARAMETERS_SHA256_DIGEST => format!("/params-sha256={}", hex(value)),
+            _ => format!("/{}={}", typ, escape(value)),
+        })
+        .collect())
+}
+
+/// Name printed by `ndnsec` for a signature type
+pub fn signature_type_name(signature_type: u64) -> String {
+    match signature_type {
+        0 => "DigestSha256".to_string(),
+        1 => "SignatureSha256WithRsa".to_string(),
+        3 => "SignatureSha256WithEcdsa".to_string(),
+        4 => "SignatureHmacWithSha256".to_string(),
+        5 => "SignatureEd25519".to_string(),
+        other => format!("Unknown({})", other),
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn decode_names() {
+        let name = [
+            0x08, 0x03, b'n', b'd', b'n', 0x08, 0x03, 0x3e, b'a', 0x20, 0x08, 0x02, b'.', b'.',
+            0x01, 0x01, 0xab,
+        ];
+        assert_eq!(
+            name_uri(&name).unwrap(),
+            "/ndn/%3Ea%20/...../sha256digest=ab"
+        );
+        assert_eq!(name_uri(&[]).unwrap(), "/");
+        assert!(name_uri(&[0x08, 0x05, b'n']).is_err());
+    }
+
+    #[test]
+    fn encode_elements() {
+        let name = encode_name("/localhost/nfd/a%20b");
+        assert_eq!(
+            name_uri(next_element(&name).unwrap().1).unwrap(),
+            "/localhost/nfd/a%20b"
+        );
+        for number in [0, 252, 253, 0xffff, 0x10000, 0x1_0000_0000].iter() {
+            let mut encoded = Vec::new();
+            encode_var_number(*number, &mut encoded);
+            assert_eq!(var_number(&encoded).unwrap(), (*number, &[][..]));
+            let value = encode_non_negative_integer(*number);
+            assert_eq!(non_negative_integer(&value).unwrap(), *number);
+        }
+        let element = encode(CONTENT, &[1; 300]);
+        assert_eq!(element_size(&element).unwrap(), Some(304));
+        assert_eq!(element_size(&element[..303]).unwrap(), None);
+        assert_eq!(element_size(&element[..2]).unwrap(), None);
+        let element = encode(CONTENT, &[1; MAX_PACKET_SIZE]);
+        assert!(element_size(&element[..4]).is_err());
+        let mut header = Vec::new();
+        encode_var_number(CONTENT, &mut header);
+        encode_var_number(u64::MAX, &mut header);
+        assert!(element_size(&header).is_err());
+    }
+
+    #[test]
+    fn decode_numbers() {
+        assert_eq!(var_number(&[0xfc]).unwrap().0, 0xfc);
+        assert_eq!(var_number(&[0xfd, 0x01, 0x00]).unwrap().0, 0x100);
+        assert_eq!(
+            var_number(&[0xfe, 0, 1, 0, 0, 7]).unwrap(),
+            (0x10000, &[7][..])
+        );
+        assert!(var_number(&[0xfd, 0x01]).is_err());
+        assert!(non_negative_integer(&[0, 0, 0]).is_err());
+    }
+}
@@ -0,0 +1,312 @@
+use crate::agent::{Data, Metrics, Pause, SharedLogs, Tasks};
+use crate::task::Logs;
+use async_std::io::{prelude::*, BufReader};
+use async_std::net::TcpListener;
+use async_std::task;
+use chrono::{DateTime, Duration, Utc};
+use futures::{Stream, StreamExt};
+use std::io;
+
+/// Tick intervals without a completed tick after which the agent is considered stuck
+const STUCK_TICKS: i32 = 3;
+
+/// Time a client has to send its request before the connection is dropped
+const REQUEST_TIMEOUT: std::time::Duration = std::time::Duration::from_secs(5);
+
+const PROPERTIES: [Tasks; 3] = [Tasks::P1, Tasks::P2, Tasks::P3];
+
+/// State of the agent exposed to the liveness and readiness probes
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Health {
+    /// The agent completed a tick recently
+    pub alive: bool,
+    /// The agent is alive and all the required properties held in the last tick
+    pub ready: bool,
+}
+
+impl Health {
+    /// Health of an agent ticking every `tick_interval` and requiring `required_properties`,
+    /// from its logs
+    ///
+    /// A tick is completed when its properties are merged into the logs. The agent is stuck
+    /// when no tick completed within a few tick intervals plus the duration of the last tick.
+    pub fn of(
+        logs: &Logs<Metrics, Tasks, Data>,
+        now: DateTime<Utc>,
+        tick_interval: Duration,
+        required_properties: &[Tasks],
+    ) -> Self {
+        let last_tick = PROPERTIES
+            .iter()
+            .filter_map(|task| logs.evaluations_timestamp.get(task)?.back())
+            .max_by_key(|(timestamp, _)| *timestamp);
+        let last_duration = logs
+            .duration_index
+            .iter()
+            .max_by_key(|(index, _)| **index)
+            .map_or(0, |(_, duration)| *duration);
+        let max_age = tick_interval * STUCK_TICKS + Duration::nanoseconds(last_duration);
+        let alive = last_tick.is_some_and(|(timestamp, _)| now - *timestamp <= max_age);
+        let ready = alive
+            && required_properties.iter().all(|task| {
+                logs.evaluations_timestamp
+                    .get(task)
+                    .and_then(|history| history.back())
+                    .is_some_and(|(_, value)| *value == Some(true))
+            });
+        Health { alive, ready }
+    }
+
+    /// Status code and body of the answer to a `GET` of `path`
+    pub fn respond(&self, path: &str) -> (u16, &'static str) {
+        match path {
+            "/healthz" if self.alive => (200, "ok"),
+            "/healthz" => (503, "stuck"),
+            "/readyz" if self.ready => (200, "ok"),
+            "/readyz" if self.alive => (503, "non-compliant"),
+            "/readyz" => (503, "stuck"),
+            _ => (404, "not found"),
+        }
+    }
+}
+
+/// Status code and body of the answer to a `method` request of `path` to the pause control
+///
+/// `POST /pause` and `POST /resume` flip the switch, while a paused agent answers the probes as
+/// alive but not ready, as it is expected not to tick. Other requests are left to the probes.
+fn control(pause: &Pause, method: &str, path: &str) -> Option<(u16, &'static str)> {
+    match (method, path) {
+        ("POST", "/pause") => {
+            pause.pause();
+            Some((200, "paused"))
+        }
+        ("POST", "/resume") => {
+            pause.resume();
+            Some((200, "resumed"))
+        }
+        (_, "/healthz") if pause.is_paused() => Some((200, "paused")),
+        (_, "/readyz") if pause.is_paused() => Some((503, "paused")),
+        _ => None,
+    }
+}
+
+/// Answer a single probe or control request read from the stream
+async fn answer<S>(
+    mut stream: S,
+    logs: SharedLogs,
+    pause: Pause,
+    tick_interval: Duration,
+    required_properties: &[Tasks],
+) -> io::Result<()>
+where
+    S: Read + Write + Unpin,
+{
+    let mut request_line = String::new();
+    async_std::io::timeout(REQUEST_TIMEOUT, async {
+        let mut reader = BufReader::new(&mut stream);
+        reader.read_line(&mut request_line).await?;
+        // Consume the headers, closing with unread data would reset the connection
+        let mut header = String::new();
+        while reader.read_line(&mut header).await? > 2 {
+            header.clear();
+        }
+        Ok(())
+    })
+    .await?;
+    let mut words = request_line.split_whitespace();
+    let method = words.next().unwrap_or_default();
+    let path = words.next().unwrap_or_default();
+    let (status, body) = control(&pause, method, path).unwrap_or_else(|| {
+        let health = Health::of(
+            &logs.read().unwrap(),
+            Utc::now(),
+            tick_interval,
+            required_properties,
+        );
+        health.respond(path)
+    });
+    let reason = match status {
+        200 => "OK",
+        404 => "Not Found",
+        _ => "Service Unavailable",
+    };
+    let response = format!(
+        "HTTP/1.1 {} {}\r\nContent-Type: text/plain\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{}\n",
+        status,
+        reason,
+        body.len() + 1,
+        body
+    );
+    stream.write_all(response.as_bytes()).await
+}
+
+/// Answer the `/healthz` and `/readyz` probes and the `/pause` and `/resume` controls on the
+/// listener, until it fails
+///
+/// Each connection is answered in its own task, so that a slow client does not hold back the
+/// probes after it.
+pub async fn serve(
+    listener: TcpListener,
+    logs: SharedLogs,
+    pause: Pause,
+    tick_interval: Duration,
+    required_properties: Vec<Tasks>,
+) -> io::Result<()> {
+    let incoming = listener.incoming();
+    serve_connections(incoming, logs, pause, tick_interval, required_properties).await
+}
+
+/// Answer each of the `incoming` connections in its own task
+async fn serve_connections<I, S>(
+    mut incoming: I,
+    logs: SharedLogs,
+    pause: Pause,
+    tick_interval: Duration,
+    required_properties: Vec<Tasks>,
+) -> io::Result<()>
+where
+    I: Stream<Item = io::Result<S>> + Unpin,
+    S: Read + Write + Unpin + Send + 'static,
+{
+    while let Some(stream) = incoming.next().await {
+        let (stream, logs, pause) = (stream?, logs.clone(), pause.clone());
+        let required_properties = required_properties.clone();
+        task::spawn(async move {
+            let answered = answer(stream, logs, pause, tick_interval, &required_properties);
+            if let Err(e) = answered.await {
+                eprintln!("Health probe: {}", e);
+            }
+        });
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::task::Evaluation;
+    use async_std::future::timeout;
+    use async_std::os::unix::net::UnixStream;
+    use std::sync::{Arc, RwLock};
+
+    fn logs_at(timestamp: DateTime<Utc>, values: [bool; 3]) -> Logs<Metrics, Tasks, Data> {
+        let mut logs = Logs::default();
+        for (task, value) in PROPERTIES.iter().zip(values.iter()) {
+            let mut evaluation = Evaluation::new(*value, 7);
+            evaluation.timestamp = timestamp;
+            logs.insert_evaluation(evaluation, task.clone());
+        }
+        logs.insert_duration(Duration::seconds(1).num_nanoseconds().unwrap(), 7);
+        logs
+    }
+
+    #[test]
+    fn stuck_agent() {
+        let now = Utc::now();
+        let tick_interval = Duration::seconds(1);
+        let health = Health::of(&Logs::default(), now, tick_interval, &PROPERTIES);
+        assert_eq!(health.respond("/healthz"), (503, "stuck"));
+
+        let logs = logs_at(now - Duration::seconds(3), [true, true, true]);
+        let health = Health::of(&logs, now, tick_interval, &PROPERTIES);
+        assert!(health.ready);
+        let logs = logs_at(now - Duration::seconds(5), [true, true, true]);
+        let health = Health::of(&logs, now, tick_interval, &PROPERTIES);
+        assert_eq!(
+            health,
+            Health {
+                alive: false,
+                ready: false
+            }
+        );
+        assert_eq!(health.respond("/healthz"), (503, "stuck"));
+        assert_eq!(health.respond("/readyz"), (503, "stuck"));
+    }
+
+    #[test]
+    fn non_compliant_but_alive() {
+        let now = Utc::now();
+        let logs = logs_at(now, [true, false, true]);
+        let health = Health::of(&logs, now, Duration::seconds(1), &PROPERTIES);
+        assert_eq!(health.respond("/healthz"), (200, "ok"));
+        assert_eq!(health.respond("/readyz"), (503, "non-compliant"));
+        assert_eq!(health.respond("/metrics"), (404, "not found"));
+
+        // P2 is not required, the agent is ready without it
+        let required_properties = [Tasks::P1, Tasks::P3];
+        let health = Health::of(&logs, now, Duration::seconds(1), &required_properties);
+        assert_eq!(health.respond("/readyz"), (200, "ok"));
+    }
+
+    #[test]
+    fn pause_control() {
+        let pause = Pause::default();
+        assert_eq!(control(&pause, "GET", "/healthz"), None);
+        assert_eq!(control(&pause, "GET", "/pause"), None);
+        assert_eq!(control(&pause, "POST", "/pause"), Some((200, "paused")));
+        assert!(pause.is_paused());
+        assert_eq!(control(&pause, "GET", "/healthz"), Some((200, "paused")));
+        assert_eq!(control(&pause, "GET", "/readyz"), Some((503, "paused")));
+        assert_eq!(control(&pause, "POST", "/resume"), Some((200, "resumed")));
+        assert!(!pause.is_paused());
+        assert_eq!(control(&pause, "GET", "/readyz"), None);
+    }
+
+    #[async_std::test]
+    async fn answer_probes() {
+        let logs = Arc::new(RwLock::new(logs_at(Utc::now(), [true, true, false])));
+        for (path, status, body) in [
+            ("/healthz", "HTTP/1.1 200 OK\r\n", "\r\n\r\nok\n"),
+            (
+                "/readyz",
+                "HTTP/1.1 503 Service Unavailable\r\n",
+                "\r\n\r\nnon-compliant\n",
+            ),
+        ]
+        .iter()
+        {
+            let (mut client, server) = UnixStream::pair().unwrap();
+            let request = format!("GET {} HTTP/1.1\r\nHost: localhost\r\n\r\n", path);
+            client.write_all(request.as_bytes()).await.unwrap();
+            answer(
+                server,
+                logs.clone(),
+                Pause::default(),
+                Duration::seconds(1),
+                &PROPERTIES,
+            )
+            .await
+            .unwrap();
+            let mut response = String::new();
+            client.read_to_string(&mut response).await.unwrap();
+            assert!(response.starts_with(status), "{}", response);
+            assert!(response.ends_with(body), "{}", response);
+        }
+    }
+
+    #[async_std::test]
+    async fn idle_client_does_not_block_probes() {
+        let logs = Arc::new(RwLock::new(logs_at(Utc::now(), [true, true, true])));
+        // The first client connects without sending its request
+        let (_idle, idle_server) = UnixStream::pair().unwrap();
+        let (mut client, server) = UnixStream::pair().unwrap();
+        client
+            .write_all(b"GET /readyz HTTP/1.1\r\n\r\n")
+            .await
+            .unwrap();
+        let incoming = futures::stream::iter(vec![Ok(idle_server), Ok(server)]);
+        let (pause, tick_interval) = (Pause::default(), Duration::seconds(1));
+        serve_connections(incoming, logs, pause, tick_interval, PROPERTIES.to_vec())
+            .await
+            .unwrap();
+        let mut response = String::new();
+        timeout(
+            std::time::Duration::from_secs(2),
+            client.read_to_string(&mut response),
+        )
+        .await
+        .unwrap()
+        .unwrap();
+        assert!(response.starts_with("HTTP/1.1 200 OK\r\n"), "{}", response);
+    }
+}
@@ -1,2 +1,12 @@
+pub mod agent;
 pub mod command;
+pub mod digest;
+pub mod health;
+pub mod output;
+pub mod policy;
+pub mod report;
+pub mod rules;
+pub mod schema;
+pub mod size;
 pub mod task;
+pub mod version;
@@ -0,0 +1,202 @@
+use crate::agent::{Data, Metrics, Tasks};
+use crate::task::Logs;
+use std::io;
+use std::path::Path;
+use thiserror::Error as ThisError;
+
+#[derive(Debug, ThisError)]
+pub enum OutputError {
+    #[error("Cannot write the logs: {0}")]
+    Io(#[from] io::Error),
+
+    #[error("Cannot encode the logs as JSON: {0}")]
+    Json(#[from] serde_json::Error),
+
+    #[error("Cannot encode the logs as MessagePack: {0}")]
+    MessagePack(#[from] rmp_serde::encode::Error),
+
+    #[error("Cannot encode the logs as CSV: {0}")]
+    Csv(#[from] csv::Error),
+}
+
+/// Format of the logs file, chosen by its extension
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum OutputFormat {
+    /// Table of the logs, as read back by `--baseline` and `--compare`
+    Json,
+    /// Table of the logs, with the field names of the JSON one
+    MessagePack,
+    /// One measurement record per row, after a header
+    Csv,
+    /// One measurement record per line, as a JSON object
+    JsonLines,
+}
+
+impl OutputFormat {
+    /// Format of the logs file at `path`, JSON for a missing or unknown extension
+    pub fn from_path<P: AsRef<Path>>(path: P) -> Self {
+        let extension = path
+            .as_ref()
+            .extension()
+            .map(|extension| extension.to_string_lossy().to_lowercase());
+        match extension.as_deref() {
+            Some("msgpack") => OutputFormat::MessagePack,
+            Some("csv") => OutputFormat::Csv,
+            Some("ndjson") => OutputFormat::JsonLines,
+            _ => OutputFormat::Json,
+        }
+    }
+
+    /// Extension of the files in the format
+    pub fn extension(&self) -> &'static str {
+        match self {
+            OutputFormat::Json => "json",
+            OutputFormat::MessagePack => "msgpack",
+            OutputFormat::Csv => "csv",
+            OutputFormat::JsonLines => "ndjson",
+        }
+    }
+
+    /// Write `logs` to `writer` in the format
+    pub fn write<W: io::Write>(
+        &self,
+        logs: &Logs<Metrics, Tasks, Data>,
+        mut writer: W,
+    ) -> Result<(), OutputError> {
+        match self {
+            OutputFormat::Json => serde_json::to_writer(&mut writer, &logs.to_table())?,
+            OutputFormat::MessagePack => {
+                rmp_serde::encode::write_named(&mut writer, &logs.to_table())?
+            }
+            OutputFormat::Csv => {
+                let mut csv = csv::Writer::from_writer(&mut writer);
+                csv.write_record(["metric", "index", "timestamp", "completed_at", "value"])?;
+                for record in logs.records() {
+                    csv.write_record([
+                        record.metric,
+                        record.index.to_string(),
+                        record.timestamp.to_rfc3339(),
+                        record
+                            .completed_at
+                            .map(|completed_at| completed_at.to_rfc3339())
+                            .unwrap_or_default(),
+                        record.value,
+                    ])?;
+                }
+                csv.flush()?;
+            }
+            OutputFormat::JsonLines => {
+                for record in logs.records() {
+                    serde_json::to_writer(&mut writer, &record)?;
+                    writer.write_all(b"\n")?;
+                }
+            }
+        }
+        Ok(writer.flush()?)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::task::{Measurement, Record, Table};
+    use chrono::{TimeZone, Utc};
+
+    fn logs() -> Logs<Metrics, Tasks, Data> {
+        let start = Utc.ymd(2021, 4, 21).and_hms(15, 0, 0);
+        let mut logs = Logs::default();
+        for index in 0..2 {
+            let mut measurement = Measurement::new(Data::M2(65536 + index), index);
+            measurement.timestamp = start + chrono::Duration::seconds(index as i64);
+            measurement.completed_at = Some(measurement.timestamp - chrono::Duration::seconds(1));
+            logs.insert_measurement(measurement, Metrics::M2);
+            let mut measurement = Measurement::new(Data::M1("lru".to_string()), index);
+            measurement.timestamp = start + chrono::Duration::seconds(index as i64);
+            logs.insert_measurement(measurement, Metrics::M1);
+        }
+        logs
+    }
+
+    #[test]
+    fn formats_by_extension() {
+        let format = OutputFormat::from_path;
+        assert_eq!(format("/tmp/ca/logs.json"), OutputFormat::Json);
+        assert_eq!(format("/tmp/ca/logs.msgpack"), OutputFormat::MessagePack);
+        assert_eq!(format("/tmp/ca/logs.csv"), OutputFormat::Csv);
+        assert_eq!(format("/tmp/ca/logs.ndjson"), OutputFormat::JsonLines);
+        assert_eq!(format("logs.CSV"), OutputFormat::Csv);
+        assert_eq!(format("logs.txt"), OutputFormat::Json);
+        assert_eq!(format("logs"), OutputFormat::Json);
+        for format in [
+            OutputFormat::Json,
+            OutputFormat::MessagePack,
+            OutputFormat::Csv,
+            OutputFormat::JsonLines,
+        ] {
+            assert_eq!(
+                OutputFormat::from_path(format!("logs.{}", format.extension())),
+                format
+            );
+        }
+    }
+
+    #[test]
+    fn json_writer() {
+        let mut bytes = Vec::new();
+        OutputFormat::Json.write(&logs(), &mut bytes).unwrap();
+        let table: Table<Metrics, Tasks, Data> = serde_json::from_slice(&bytes).unwrap();
+        assert_eq!(table, logs().to_table());
+        assert_eq!(
+            table.completed_at_index[&Metrics::M2][&1],
+            Utc.ymd(2021, 4, 21).and_hms(15, 0, 0)
+        );
+    }
+
+    #[test]
+    fn messagepack_writer() {
+        let mut bytes = Vec::new();
+        OutputFormat::MessagePack
+            .write(&logs(), &mut bytes)
+            .unwrap();
+        let table: Table<Metrics, Tasks, Data> = rmp_serde::from_slice(&bytes).unwrap();
+        assert_eq!(table, logs().to_table());
+    }
+
+    #[test]
+    fn csv_writer() {
+        let mut bytes = Vec::new();
+        OutputFormat::Csv.write(&logs(), &mut bytes).unwrap();
+        let text = String::from_utf8(bytes).unwrap();
+        let lines = text.lines().collect::<Vec<_>>();
+        assert_eq!(
+            lines,
+            [
+                "metric,index,timestamp,completed_at,value",
+                "M1,0,2021-04-21T15:00:00+00:00,,lru",
+                "M2,0,2021-04-21T15:00:00+00:00,2021-04-21T14:59:59+00:00,65536",
+                "M1,1,2021-04-21T15:00:01+00:00,,lru",
+                "M2,1,2021-04-21T15:00:01+00:00,2021-04-21T15:00:00+00:00,65537",
+            ]
+        );
+    }
+
+    #[test]
+    fn json_lines_writer() {
+        let mut bytes = Vec::new();
+        OutputFormat::JsonLines.write(&logs(), &mut bytes).unwrap();
+        let records = String::from_utf8(bytes)
+            .unwrap()
+            .lines()
+            .map(|line| serde_json::from_str(line).unwrap())
+            .collect::<Vec<Record>>();
+        assert_eq!(records, logs().records());
+        assert_eq!(records.len(), 4);
+        assert_eq!((records[1].metric.as_str(), records[1].index), ("M2", 0));
+        assert_eq!(records[3].value, "65537");
+        assert_eq!(records[2].completed_at, None);
+        assert_eq!(
+            records[3].completed_at,
+            Some(Utc.ymd(2021, 4, 21).and_hms(15, 0, 0))
+        );
+    }
+}
@@ -0,0 +1,603 @@
+use crate::agent::{Metrics, Tasks};
+use crate::command::ndnsec::key::{KeyAlgorithm, PublicKeyInfo};
+use crate::command::nfdc::{name_starts_with, NdnName};
+use crate::command::{CommandConfig, RetryPolicy, StatusSource};
+use crate::size::ByteSize;
+use crate::task::Aggregation;
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, HashSet};
+use std::fs;
+use std::path::Path;
+use std::time::Duration;
+use thiserror::Error as ThisError;
+
+/// How the constraints treat measurements holding NaN values
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum NanHandling {
+    /// Evaluate the constraints on the values as they are
+    #[default]
+    Evaluate,
+    /// Skip the constraints, as for measurements that cannot be parsed
+    Skip,
+    /// Evaluate the constraints to false
+    Fail,
+    /// Fail the evaluation of the constraints, and so the tick
+    Error,
+}
+
+#[derive(Debug, Clone, PartialEq, ThisError)]
+pub enum PolicyError {
+    /// The policy file cannot be read
+    #[error("Cannot read {0}: {1}")]
+    Io(String, String),
+
+    /// The policy file does not hold a policy
+    #[error("Invalid policy file {0}: {1}")]
+    Parse(String, String),
+
+    /// Some settings of the policy are inconsistent or out of range
+    #[error("Invalid policy: {}", .0.join("; "))]
+    Invalid(Vec<String>),
+}
+
+/// Node certified by a testbed agent
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct NodeConfig {
+    /// Name of the node, keying its logs
+    pub id: String,
+    /// Command the `nfdc` and `ndnsec` invocations are wrapped in, e.g. `["ssh", "router1"]`, as
+    /// well as the `cat` and `ps` ones reading the memory of the node
+    #[serde(default)]
+    pub wrapper: Vec<String>,
+}
+
+/// Certification policy
+///
+/// Thresholds used by the constraints. Missing fields take the default value.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default, rename_all = "camelCase")]
+pub struct Policy {
+    /// Acceptable CS replacement policies, compared case-insensitively
+    pub cs_policies: Vec<String>,
+    /// Minimum ratio of CS hits over lookups within the window, while interests reach the CS
+    pub min_cs_hit_ratio: f64,
+    /// Maximum ratio of incoming nacks over incoming interests
+    pub max_nack_ratio: f64,
+    /// Maximum rate of the interests NFD could not satisfy, per second
+    pub max_unsatisfied_interest_rate: f64,
+    /// Maximum number of true/false transitions of a constraint within the window
+    pub max_transitions: usize,
+    /// Maximum sustained growth of the face count within the window
+    pub max_face_growth: u64,
+    /// Minimum number of evaluations within the window before a rule evaluates
+    pub min_samples: usize,
+    /// Ticks after the start of the agent during which the windowed rules and properties are
+    /// withheld, on top of `min_samples`
+    pub warm_up_ticks: u64,
+    /// Minimum base congestion marking interval of a face, in milliseconds
+    pub min_marking_interval_ms: u64,
+    /// Maximum base congestion marking interval of a face, in milliseconds
+    pub max_marking_interval_ms: u64,
+    /// Minimum default congestion threshold of a face with congestion marking enabled
+    pub min_congestion_threshold: ByteSize,
+    /// Maximum default congestion threshold of a face with congestion marking enabled
+    pub max_congestion_threshold: ByteSize,
+    /// Leave the internal faces of the forwarder out of the per-face metrics
+    pub exclude_internal_faces: bool,
+    /// Faces left out of the per-face metrics
+    pub excluded_face_ids: Vec<u64>,
+    /// Remote uri patterns of the faces left out of the per-face metrics, e.g. `"fd://*"`
+    pub excluded_remote_uris: Vec<String>,
+    /// Memory taken by a CS entry, e.g. `"8KiB"`
+    pub cs_entry_size: ByteSize,
+    /// Host memory kept free of CS entries on top of the 20% margin, e.g. `"1GiB"`
+    pub memory_headroom: ByteSize,
+    /// Maximum resident memory of the `nfd` process, e.g. `"2GiB"`
+    pub max_nfd_memory: ByteSize,
+    /// Minimum number of identities in the keychain
+    pub min_identities: u64,
+    /// Maximum number of identities in the keychain, more hint at key sprawl
+    pub max_identities: u64,
+    /// Identities every content certificate must chain to, none to disable the check
+    pub trust_anchors: Vec<String>,
+    /// Maximum number of certificates in the signing chain of a content certificate, its own
+    /// included, deeper chains hinting at a misconfigured or forged keychain
+    pub max_chain_depth: u64,
+    /// Properties deciding the overall verdict and the exit code, so that a node can be
+    /// certified against some of them only, e.g. `["P2", "P3"]` for its certificates
+    pub required_properties: Vec<Tasks>,
+    /// Interval between two ticks of the agent, in milliseconds
+    pub tick_interval_ms: u64,
+    /// Random variation of the interval between two ticks, in percent of the interval, keeping
+    /// agents polling the same forwarders from synchronizing, zero to disable
+    pub tick_jitter_percent: f64,
+    /// Upper bound of the commands an agent runs at the same time, e.g. to spare constrained
+    /// nodes, `None` to run all the independent commands of a tick at once
+    pub max_concurrent_commands: Option<usize>,
+    /// Remote nodes to certify, none to certify the local node
+    pub nodes: Vec<NodeConfig>,
+    /// Weight of each task in the compliance score, none to weigh every constraint alike
+    pub task_weights: BTreeMap<Tasks, f64>,
+    /// Minimum size of the interest and data packets of a face, in bytes
+    pub min_packet_size: u64,
+    /// Percentile of the average packet sizes within the window checked against the minimum
+    /// size, none to check the minimum size of each tick
+    pub packet_size_percentile: Option<f64>,
+    /// Lower bound, excluded, of the average number of name components of a face
+    pub min_name_components: f64,
+    /// Upper bound, excluded, of the average number of name components of a face
+    pub max_name_components: f64,
+    /// Maximum number of consecutive ticks the NFD current time may stay still before the
+    /// forwarder is considered hung
+    pub max_stale_ticks: usize,
+    /// Minimum modulus size of the RSA keys of the content certificates, in bits
+    pub min_rsa_key_bits: u32,
+    /// Minimum field size of the EC keys of the content certificates, in bits
+    pub min_ec_key_bits: u32,
+    /// Store the digest of the `nfdc` report each measurement is derived from, for audits
+    pub record_source_digests: bool,
+    /// Record the measurements whose command output cannot be parsed as parse errors, skipping
+    /// the constraints evaluated on them, instead of failing the tick
+    pub lenient_parsing: bool,
+    /// Maximum share of the ticks within the window with a command output that cannot be parsed
+    pub max_parse_error_rate: f64,
+    /// How the constraints treat measurements holding NaN values, e.g. the `-nan` averages of
+    /// idle faces
+    pub nan_handling: NanHandling,
+    /// How each rule and property combines the evaluations within its window, e.g.
+    /// `{"R3": "latestOnly", "R4": {"atLeastFraction": 0.9}}`, by default all must be true
+    pub aggregations: BTreeMap<Tasks, Aggregation>,
+    /// Prefixes whose routes must have an allowed origin, e.g. the production namespaces, none
+    /// to disable the check
+    pub route_origin_prefixes: Vec<String>,
+    /// Identity prefixes the certificate constraints are restricted to, none to consider every
+    /// identity of the keychain
+    pub included_identities: Vec<String>,
+    /// Identity prefixes ignored by the certificate constraints, e.g. system or test keys, even
+    /// under an included prefix
+    pub excluded_identities: Vec<String>,
+    /// Acceptable origins of the routes of the protected prefixes, e.g. `static` or `nlsr`
+    pub allowed_route_origins: Vec<String>,
+    /// Number of latest samples of a metric checked by a stability constraint, e.g. C5
+    pub stability_window: usize,
+    /// Upper bound, excluded, of the standard deviation of the samples of a stable metric
+    pub max_stability_std_dev: f64,
+    /// Strategy each namespace must be forwarded with, compared without versions, e.g.
+    /// `{"/ndn/broadcast": "/localhost/nfd/strategy/multicast"}`
+    pub required_strategies: BTreeMap<String, String>,
+    /// Transport schemes NFD must have a channel of, e.g. `["udp6", "ws"]`, including the `unix`
+    /// socket and the `dev` Ethernet channels
+    pub required_channel_schemes: Vec<String>,
+    /// Management prefixes the FIB must have a next hop for, e.g. `/localhost/nfd/rib` of the
+    /// RIB manager
+    pub required_fib_prefixes: Vec<String>,
+    /// MTU expected for the faces of each link type, e.g. `point-to-point`, other link types
+    /// are not checked
+    pub expected_mtus: BTreeMap<String, u64>,
+    /// Metrics compared with a baseline run, the configuration of the node by default
+    pub drift_metrics: Vec<Metrics>,
+    /// Relative change of a numeric metric from its baseline value beyond which it drifted
+    pub drift_tolerance: f64,
+    /// Chain the digests of the evaluations of each tick in the logs, so that later alterations
+    /// of the logs can be detected
+    pub hash_chain: bool,
+    /// Age in seconds after which measurements are summarized instead of kept at full
+    /// resolution, beyond the windows of the rules, `None` to keep them all
+    pub downsample_after_secs: Option<u64>,
+    /// Duration in seconds of the buckets summarizing the downsampled measurements
+    pub downsample_bucket_secs: u64,
+    /// Binaries run by the agent, with their timeouts and retries
+    pub commands: CommandConfig,
+}
+
+impl Default for Policy {
+    fn default() -> Self {
+        Self {
+            cs_policies: vec!["lru".to_string()],
+            min_cs_hit_ratio: 0.0,
+            max_nack_ratio: 0.1,
+            max_unsatisfied_interest_rate: 10.0,
+            max_transitions: 4,
+            max_face_growth: 10,
+            min_samples: 5,
+            warm_up_ticks: 0,
+            min_marking_interval_ms: 10,
+            max_marking_interval_ms: 1000,
+            min_congestion_threshold: ByteSize::from_kib(1),
+            max_congestion_threshold: ByteSize::from_mib(1),
+            exclude_internal_faces: false,
+            excluded_face_ids: Vec::new(),
+            excluded_remote_uris: Vec::new(),
+            cs_entry_size: ByteSize::from_kib(8),
+            memory_headroom: ByteSize(0),
+            max_nfd_memory: ByteSize::from_gib(2),
+            min_identities: 1,
+            max_identities: 16,
+            trust_anchors: Vec::new(),
+            max_chain_depth: 4,
+            required_properties: vec![Tasks::P1, Tasks::P2, Tasks::P3],
+            tick_interval_ms: 1000,
+            tick_jitter_percent: 0.0,
+            max_concurrent_commands: None,
+            nodes: Vec::new(),
+            task_weights: BTreeMap::new(),
+            min_packet_size: 10,
+            packet_size_percentile: None,
+            min_name_components: 3.0,
+            max_name_components: 12.0,
+            max_stale_ticks: 3,
+            min_rsa_key_bits: 2048,
+            min_ec_key_bits: 256,
+            record_source_digests: false,
+            lenient_parsing: false,
+            max_parse_error_rate: 0.1,
+            nan_handling: NanHandling::Evaluate,
+            aggregations: BTreeMap::new(),
+            route_origin_prefixes: Vec::new(),
+            included_identities: Vec::new(),
+            excluded_identities: Vec::new(),
+            allowed_route_origins: vec!["static".to_string(), "nlsr".to_string()],
+            stability_window: 5,
+            max_stability_std_dev: 5.0,
+            required_strategies: BTreeMap::new(),
+            required_channel_schemes: Vec::new(),
+            required_fib_prefixes: vec![
+                "/localhost/nfd".to_string(),
+                "/localhost/nfd/rib".to_string(),
+            ],
+            expected_mtus: BTreeMap::new(),
+            drift_metrics: vec![
+                Metrics::M1,
+                Metrics::M17,
+                Metrics::M20,
+                Metrics::M21,
+                Metrics::M28,
+                Metrics::M31,
+            ],
+            drift_tolerance: 0.1,
+            hash_chain: false,
+            downsample_after_secs: None,
+            downsample_bucket_secs: 60,
+            commands: CommandConfig::default(),
+        }
+    }
+}
+
+impl Policy {
+    /// Read a JSON policy file, without validating it
+    pub fn from_file<P: AsRef<Path>>(path: P) -> Result<Self, PolicyError> {
+        let path = path.as_ref();
+        let text = fs::read_to_string(path)
+            .map_err(|e| PolicyError::Io(path.display().to_string(), e.to_string()))?;
+        serde_json::from_str(&text)
+            .map_err(|e| PolicyError::Parse(path.display().to_string(), e.to_string()))
+    }
+
+    /// Check that the settings are consistent and within range, reporting every problem
+    pub fn validate(&self) -> Result<(), PolicyError> {
+        let mut problems = Vec::new();
+        let mut check = |ok: bool, problem: &str| {
+            if !ok {
+                problems.push(problem.to_string());
+            }
+        };
+        let ratio = |value: f64| (0.0..=1.0).contains(&value);
+        check(!self.cs_policies.is_empty(), "csPolicies is empty");
+        check(
+            ratio(self.min_cs_hit_ratio),
+            "minCsHitRatio is not within 0 and 1",
+        );
+        check(
+            ratio(self.max_nack_ratio),
+            "maxNackRatio is not within 0 and 1",
+        );
+        check(
+            self.max_unsatisfied_interest_rate >= 0.0,
+            "maxUnsatisfiedInterestRate is negative",
+        );
+        check(
+            ratio(self.max_parse_error_rate),
+            "maxParseErrorRate is not within 0 and 1",
+        );
+        check(
+            self.min_marking_interval_ms <= self.max_marking_interval_ms,
+            "minMarkingIntervalMs is above maxMarkingIntervalMs",
+        );
+        check(
+            self.min_congestion_threshold <= self.max_congestion_threshold,
+            "minCongestionThreshold is above maxCongestionThreshold",
+        );
+        check(
+            self.min_identities <= self.max_identities,
+            "minIdentities is above maxIdentities",
+        );
+        check(
+            self.min_name_components < self.max_name_components,
+            "minNameComponents is not below maxNameComponents",
+        );
+        check(
+            self.packet_size_percentile
+                .is_none_or(|p| (0.0..=100.0).contains(&p)),
+            "packetSizePercentile is not within 0 and 100",
+        );
+        check(
+            !self.required_properties.is_empty(),
+            "requiredProperties is empty",
+        );
+        check(
+            self.required_properties
+                .iter()
+                .all(|task| matches!(task, Tasks::P1 | Tasks::P2 | Tasks::P3)),
+            "requiredProperties holds a task that is not a property",
+        );
+        check(self.tick_interval_ms > 0, "tickIntervalMs is zero");
+        check(
+            (0.0..=100.0).contains(&self.tick_jitter_percent),
+            "tickJitterPercent is not within 0 and 100",
+        );
+        check(
+            self.max_concurrent_commands != Some(0),
+            "maxConcurrentCommands is zero",
+        );
+        check(
+            self.task_weights
+                .values()
+                .all(|w| w.is_finite() && *w >= 0.0),
+            "taskWeights holds a negative weight",
+        );
+        check(
+            self.task_weights.is_empty() || self.task_weights.values().sum::<f64>() > 0.0,
+            "taskWeights weighs every task zero",
+        );
+        check(
+            self.aggregations
+                .values()
+                .all(|aggregation| match aggregation {
+                    Aggregation::AtLeastFraction(fraction) => ratio(*fraction),
+                    _ => true,
+                }),
+            "aggregations holds a fraction not within 0 and 1",
+        );
+        check(
+            self.aggregations
+                .keys()
+                .all(|task| !format!("{:?}", task).starts_with('C')),
+            "aggregations holds a task that is not a rule or a property",
+        );
+        check(
+            self.route_origin_prefixes.is_empty() || !self.allowed_route_origins.is_empty(),
+            "allowedRouteOrigins is empty",
+        );
+        check(
+            self.drift_tolerance.is_finite() && self.drift_tolerance >= 0.0,
+            "driftTolerance is negative",
+        );
+        check(self.stability_window >= 2, "stabilityWindow is below 2");
+        check(
+            self.max_stability_std_dev > 0.0,
+            "maxStabilityStdDev is not positive",
+        );
+        check(
+            self.downsample_after_secs.is_none() || self.downsample_bucket_secs > 0,
+            "downsampleBucketSecs is zero",
+        );
+        let retry = &self.commands.retry;
+        check(retry.max_attempts > 0, "commands.retry.maxAttempts is zero");
+        check(
+            retry.max_attempts <= RetryPolicy::MAX_ATTEMPTS,
+            "commands.retry.maxAttempts is above 10",
+        );
+        check(
+            retry.multiplier.is_finite(),
+            "commands.retry.multiplier is not finite",
+        );
+        check(
+            retry.multiplier >= 1.0 || retry.multiplier.is_nan(),
+            "commands.retry.multiplier is below 1",
+        );
+        let mut ids = HashSet::new();
+        check(
+            self.nodes.iter().all(|node| ids.insert(&node.id)),
+            "nodes holds a duplicate id",
+        );
+        check(
+            self.nodes.iter().all(|node| !node.id.is_empty()),
+            "nodes holds an empty id",
+        );
+        // The management socket is a local one, it cannot reach the forwarders of the nodes
+        check(
+            self.nodes.is_empty() || self.commands.status_source == StatusSource::Nfdc,
+            "commands.statusSource is management, which cannot reach the nodes",
+        );
+        if problems.is_empty() {
+            Ok(())
+        } else {
+            Err(PolicyError::Invalid(problems))
+        }
+    }
+
+    /// Whether a key uses an approved algorithm with at least the minimum size
+    pub fn approves_key(&self, key: &PublicKeyInfo) -> bool {
+        match key.algorithm {
+            KeyAlgorithm::Rsa => key.bits >= self.min_rsa_key_bits,
+            KeyAlgorithm::Ec => key.bits >= self.min_ec_key_bits,
+            KeyAlgorithm::Other(_) => false,
+        }
+    }
+
+    /// Interval before the next tick, `random` within 0 and 1 picking the jitter
+    pub fn jittered_tick_interval(&self, random: f64) -> Duration {
+        let jitter = self.tick_jitter_percent / 100.0 * (2.0 * random - 1.0);
+        Duration::from_millis(self.tick_interval_ms).mul_f64((1.0 + jitter).max(0.0))
+    }
+
+    /// How a rule or property combines the evaluations within its window
+    pub fn aggregation(&self, task: &Tasks) -> Aggregation {
+        self.aggregations.get(task).cloned().unwrap_or_default()
+    }
+
+    /// Whether the routes of a prefix are subject to the allowed route origins
+    pub fn protects_prefix(&self, prefix: &str) -> bool {
+        self.route_origin_prefixes
+            .iter()
+            .any(|protected| name_starts_with(prefix, protected))
+    }
+
+    /// Whether the certificates of an identity are subject to the certificate constraints
+    pub fn certifies_identity(&self, identity: &str) -> bool {
+        let identity = NdnName::from(identity);
+        let matches = |prefixes: &[String]| {
+            prefixes
+                .iter()
+                .any(|prefix| identity.starts_with(&prefix.as_str().into()))
+        };
+        (self.included_identities.is_empty() || matches(&self.included_identities))
+            && !matches(&self.excluded_identities)
+    }
+
+    /// Whether a route origin is allowed, compared case-insensitively
+    pub fn allows_route_origin(&self, origin: &str) -> bool {
+        self.allowed_route_origins
+            .iter()
+            .any(|allowed| allowed.eq_ignore_ascii_case(origin))
+    }
+
+    /// Weight of a task in the compliance score
+    pub fn weight(&self, task: &Tasks) -> f64 {
+        if self.task_weights.is_empty() {
+            match task.metrics() {
+                [] => 0.0,
+                _ => 1.0,
+            }
+        } else {
+            self.task_weights.get(task).cloned().unwrap_or_default()
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn validate_policy_files() {
+        let policy = Policy::from_file("src/policy.json").unwrap();
+        assert_eq!(policy.validate(), Ok(()));
+        assert_eq!(policy.aggregation(&Tasks::R3), Aggregation::LatestOnly);
+        assert_eq!(
+            policy.aggregation(&Tasks::R4),
+            Aggregation::AtLeastFraction(0.9)
+        );
+        assert_eq!(policy.aggregation(&Tasks::R5), Aggregation::AllInWindow);
+        assert_eq!(
+            policy.required_strategies["/ndn/broadcast"],
+            "/localhost/nfd/strategy/multicast"
+        );
+        assert_eq!(Policy::default().validate(), Ok(()));
+
+        let policy = Policy::from_file("src/policy_invalid.json").unwrap();
+        match policy.validate() {
+            Err(PolicyError::Invalid(problems)) => assert_eq!(
+                problems,
+                [
+                    "maxNackRatio is not within 0 and 1",
+                    "minIdentities is above maxIdentities",
+                    "requiredProperties holds a task that is not a property",
+                    "tickIntervalMs is zero",
+                    "maxConcurrentCommands is zero",
+                    "aggregations holds a task that is not a rule or a property",
+                    "stabilityWindow is below 2",
+                    "commands.retry.maxAttempts is zero",
+                    "nodes holds a duplicate id",
+                    "commands.statusSource is management, which cannot reach the nodes",
+                ]
+            ),
+            other => panic!("{:?}", other),
+        }
+        let mut policy = Policy::default();
+        policy.commands.retry = RetryPolicy {
+            max_attempts: u32::MAX,
+            multiplier: f64::INFINITY,
+            ..RetryPolicy::default()
+        };
+        match policy.validate() {
+            Err(PolicyError::Invalid(problems)) => assert_eq!(
+                problems,
+                [
+                    "commands.retry.maxAttempts is above 10",
+                    "commands.retry.multiplier is not finite",
+                ]
+            ),
+            other => panic!("{:?}", other),
+        }
+        policy.commands.retry.multiplier = f64::NAN;
+        assert!(policy.validate().is_err());
+        assert!(matches!(
+            Policy::from_file("src/lib.rs"),
+            Err(PolicyError::Parse(_, _))
+        ));
+        assert!(matches!(
+            Policy::from_file("src/missing.json"),
+            Err(PolicyError::Io(_, _))
+        ));
+    }
+
+    #[test]
+    fn jittered_tick_intervals() {
+        let mut policy = Policy::default();
+        for random in [0.0, 0.3, 1.0].iter() {
+            assert_eq!(
+                policy.jittered_tick_interval(*random),
+                Duration::from_millis(1000)
+            );
+        }
+        policy.tick_jitter_percent = 10.0;
+        assert_eq!(
+            policy.jittered_tick_interval(0.0),
+            Duration::from_millis(900)
+        );
+        assert_eq!(
+            policy.jittered_tick_interval(0.5),
+            Duration::from_millis(1000)
+        );
+        assert_eq!(
+            policy.jittered_tick_interval(1.0),
+            Duration::from_millis(1100)
+        );
+        for step in 0..=100 {
+            let interval = policy.jittered_tick_interval(step as f64 / 100.0);
+            assert!(interval >= Duration::from_millis(900));
+            assert!(interval <= Duration::from_millis(1100));
+        }
+        policy.tick_jitter_percent = 100.0;
+        assert_eq!(policy.jittered_tick_interval(0.0), Duration::from_millis(0));
+        policy.tick_jitter_percent = 150.0;
+        assert!(policy.validate().is_err());
+    }
+
+    #[test]
+    fn approved_keys() {
+        let policy = Policy::default();
+        let key = |algorithm, bits| PublicKeyInfo { algorithm, bits };
+        assert!(!policy.approves_key(&key(KeyAlgorithm::Rsa, 1024)));
+        assert!(policy.approves_key(&key(KeyAlgorithm::Rsa, 2048)));
+        assert!(policy.approves_key(&key(KeyAlgorithm::Ec, 256)));
+        assert!(!policy.approves_key(&key(KeyAlgorithm::Ec, 0)));
+        assert!(!policy.approves_key(&key(KeyAlgorithm::Other("2b6570".to_string()), 256)));
+    }
+
+    #[test]
+    fn certified_identities() {
+        let mut policy = Policy::default();
+        assert!(policy.certifies_identity("/test"));
+        policy.included_identities = vec!["/ndn/unimi".to_string()];
+        policy.excluded_identities = vec!["/ndn/unimi/test".to_string()];
+        assert!(policy.certifies_identity("/ndn/unimi"));
+        assert!(policy.certifies_identity("/ndn/unimi/bertof"));
+        assert!(!policy.certifies_identity("/ndn/unimi/test/key"));
+        assert!(!policy.certifies_identity("/ndn/unimix"));
+        assert!(!policy.certifies_identity("/test"));
+    }
+}
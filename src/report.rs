@@ -0,0 +1,284 @@
+use crate::agent::{Data, Metrics, Tasks, TickReport};
+use crate::task::Logs;
+use std::collections::BTreeSet;
+use std::fmt::Write;
+
+/// Position of a task in the DAG: constraints, then rules, then properties
+fn task_order(task: &Tasks) -> (usize, u64) {
+    let name = format!("{:?}", task);
+    let (kind, number) = name.split_at(1);
+    let kind = match kind {
+        "C" => 0,
+        "R" => 1,
+        _ => 2,
+    };
+    (kind, number.parse().unwrap_or_default())
+}
+
+fn task_class(task: &Tasks) -> &'static str {
+    match task_order(task).0 {
+        0 => "constraints",
+        1 => "rules",
+        _ => "properties",
+    }
+}
+
+fn escape(s: &str) -> String {
+    s.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+        .replace('\'', "&apos;")
+}
+
+/// JUnit XML report of a tick, with a test case for each task
+///
+/// Tasks evaluated to false are failures, tasks that could not be evaluated are errors and
+/// tasks without enough data to decide are skipped.
+pub fn junit(report: &TickReport) -> String {
+    let mut evaluations = report.evaluations.iter().collect::<Vec<_>>();
+    evaluations.sort_by_key(|(task, _)| task_order(task));
+    let failures = evaluations
+        .iter()
+        .filter(|(_, e)| matches!(e, Ok(e) if e.value == Some(false)))
+        .count();
+    let errors = evaluations.iter().filter(|(_, e)| e.is_err()).count();
+    let skipped = evaluations
+        .iter()
+        .filter(|(_, e)| matches!(e, Ok(e) if e.value.is_none()))
+        .count();
+
+    let mut xml = String::from("<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n");
+    writeln!(
+        xml,
+        "<testsuite name=\"ndn-certification-agent\" tests=\"{}\" failures=\"{}\" errors=\"{}\" skipped=\"{}\" id=\"{}\">",
+        evaluations.len(),
+        failures,
+        errors,
+        skipped,
+        report.index
+    )
+    .unwrap();
+    for (task, evaluation) in evaluations {
+        let name = format!("{:?}", task);
+        match evaluation {
+            Ok(e) if e.value == Some(true) => writeln!(
+                xml,
+                "  <testcase name=\"{}\" classname=\"{}\" timestamp=\"{}\"/>",
+                name,
+                task_class(task),
+                e.timestamp.to_rfc3339()
+            )
+            .unwrap(),
+            Ok(e) => {
+                writeln!(
+                    xml,
+                    "  <testcase name=\"{}\" classname=\"{}\" timestamp=\"{}\">",
+                    name,
+                    task_class(task),
+                    e.timestamp.to_rfc3339()
+                )
+                .unwrap();
+                match e.value {
+                    Some(value) => writeln!(
+                        xml,
+                        "    <failure message=\"{} evaluated to {} at {}\"/>",
+                        name,
+                        value,
+                        e.timestamp.to_rfc3339()
+                    ),
+                    None => writeln!(
+                        xml,
+                        "    <skipped message=\"{} has not enough data at {}\"/>",
+                        name,
+                        e.timestamp.to_rfc3339()
+                    ),
+                }
+                .unwrap();
+                writeln!(xml, "  </testcase>").unwrap();
+            }
+            Err(err) => {
+                writeln!(
+                    xml,
+                    "  <testcase name=\"{}\" classname=\"{}\">",
+                    name,
+                    task_class(task)
+                )
+                .unwrap();
+                writeln!(xml, "    <error message=\"{}\"/>", escape(&err.to_string())).unwrap();
+                writeln!(xml, "  </testcase>").unwrap();
+            }
+        }
+    }
+    writeln!(xml, "</testsuite>").unwrap();
+    xml
+}
+
+/// OpenMetrics exposition of how many times each constraint of a tick held
+///
+/// Each counter carries an exemplar with the index and value of the measurement the latest
+/// evaluation of the constraint was computed on.
+pub fn openmetrics(report: &TickReport, logs: &Logs<Metrics, Tasks, Data>) -> String {
+    let mut evaluations = report
+        .evaluations
+        .iter()
+        .filter(|(task, e)| task_order(task).0 == 0 && e.is_ok())
+        .collect::<Vec<_>>();
+    evaluations.sort_by_key(|(task, _)| task_order(task));
+
+    let mut text = String::new();
+    writeln!(text, "# TYPE ndn_constraint_passed counter").unwrap();
+    writeln!(
+        text,
+        "# HELP ndn_constraint_passed Evaluations of a constraint that held"
+    )
+    .unwrap();
+    for (task, evaluation) in evaluations {
+        let index = match evaluation {
+            Ok(e) => e.index,
+            Err(_) => continue,
+        };
+        let passed = logs.evaluations_index.get(task).map_or(0, |history| {
+            history
+                .iter()
+                .filter(|(_, value)| *value == Some(true))
+                .count()
+        });
+        write!(
+            text,
+            "ndn_constraint_passed_total{{task=\"{:?}\"}} {}",
+            task, passed
+        )
+        .unwrap();
+        let exemplar = task.metrics().iter().find_map(|metric| {
+            logs.measurements_index
+                .get(metric)?
+                .iter()
+                .find(|(i, _)| *i == index)
+                .and_then(|(_, data)| data.as_f64())
+                .map(|value| (metric, value))
+        });
+        if let Some((metric, value)) = exemplar {
+            write!(
+                text,
+                " # {{metric=\"{:?}\",index=\"{}\"}} {}",
+                metric, index, value
+            )
+            .unwrap();
+        }
+        writeln!(text).unwrap();
+    }
+    writeln!(text, "# EOF").unwrap();
+    text
+}
+
+/// Graphviz DOT description of the DAG, from the metrics to the properties depending on them
+pub fn dot() -> String {
+    let mut edges = BTreeSet::new();
+    let mut pending = vec![Tasks::P1, Tasks::P2, Tasks::P3];
+    while let Some(task) = pending.pop() {
+        for metric in task.metrics() {
+            edges.insert((format!("{:?}", metric), format!("{:?}", task)));
+        }
+        for dependency in task.dependencies() {
+            edges.insert((format!("{:?}", dependency), format!("{:?}", task)));
+            pending.push(dependency.clone());
+        }
+    }
+    let mut dot = String::from("digraph dag {\n    rankdir=LR;\n");
+    for (from, to) in edges {
+        let _ = writeln!(dot, "    \"{}\" -> \"{}\";", from, to);
+    }
+    dot.push_str("}\n");
+    dot
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::task::{Error, Evaluation, Measurement};
+    use std::collections::BTreeMap;
+
+    #[test]
+    fn junit_testcases() {
+        let report = TickReport {
+            index: 3,
+            evaluations: vec![
+                (Tasks::P1, Ok(Evaluation::new(false, 3))),
+                (Tasks::C2, Ok(Evaluation::new(false, 3))),
+                (Tasks::C1, Ok(Evaluation::new(true, 3))),
+                (Tasks::R2, Ok(Evaluation::new(None, 3))),
+                (
+                    Tasks::C13,
+                    Err(Error::EvaluationError("<missing> dump".to_string())),
+                ),
+            ],
+            properties: BTreeMap::new(),
+            verdict: Ok(Some(false)),
+            score: Some(50.0),
+        };
+        let xml = junit(&report);
+        assert!(xml.contains("tests=\"5\" failures=\"2\" errors=\"1\" skipped=\"1\" id=\"3\""));
+        assert!(xml.contains("<testcase name=\"C1\" classname=\"constraints\""));
+        assert!(xml.contains("<failure message=\"C2 evaluated to false at "));
+        assert!(xml.contains("<skipped message=\"R2 has not enough data at "));
+        assert!(xml.contains("<testcase name=\"P1\" classname=\"properties\""));
+        assert!(xml.contains("<error message=\"&lt;missing&gt; dump\"/>"));
+        assert!(xml.find("name=\"C1\"") < xml.find("name=\"C2\""));
+        assert!(xml.find("name=\"C13\"") < xml.find("name=\"P1\""));
+        assert_eq!(xml.matches("<testcase ").count(), 5);
+    }
+
+    #[test]
+    fn openmetrics_exemplars() {
+        let mut logs = Logs::default();
+        for index in 4..6 {
+            logs.insert_measurement(Measurement::new(Data::M2(1000 * index), index), Metrics::M2)
+                .insert_evaluation(Evaluation::new(true, index), Tasks::C3);
+        }
+        logs.insert_evaluation(Evaluation::new(true, 5), Tasks::C1);
+        let report = TickReport {
+            index: 5,
+            evaluations: vec![
+                (Tasks::C3, Ok(Evaluation::new(true, 5))),
+                (Tasks::C1, Ok(Evaluation::new(true, 5))),
+                (Tasks::R3, Ok(Evaluation::new(true, 5))),
+            ],
+            properties: BTreeMap::new(),
+            verdict: Ok(Some(true)),
+            score: Some(100.0),
+        };
+        let text = openmetrics(&report, &logs);
+        assert!(text.contains(
+            "ndn_constraint_passed_total{task=\"C3\"} 2 # {metric=\"M2\",index=\"5\"} 5000\n"
+        ));
+        assert!(text.contains("ndn_constraint_passed_total{task=\"C1\"} 1\n"));
+        assert!(!text.contains("R3"));
+        assert!(text.find("\"C1\"") < text.find("\"C3\""));
+        assert!(text.ends_with("# EOF\n"));
+    }
+
+    #[test]
+    fn dot_dependencies() {
+        let dot = dot();
+        assert!(dot.starts_with("digraph dag {"));
+        for edge in [
+            "\"M2\" -> \"C2\";",
+            "\"M13\" -> \"C2\";",
+            "\"C2\" -> \"R1\";",
+            "\"C2\" -> \"R10\";",
+            "\"R1\" -> \"P1\";",
+            "\"R6\" -> \"P2\";",
+            "\"R6\" -> \"P3\";",
+        ]
+        .iter()
+        {
+            assert!(dot.contains(edge), "{}", edge);
+        }
+        assert!(!dot.contains("\"R1\" -> \"P2\""));
+        for constraint in 1..=30 {
+            assert!(dot.contains(&format!("\"C{}\" -> ", constraint)));
+        }
+        assert_eq!(dot.matches(" -> \"C5\"").count(), 1);
+    }
+}
@@ -1 +1,287 @@
+use crate::command::nfdc::name_eq;
+use std::collections::{HashMap, HashSet};
 
+/// Conjunction of evaluations
+///
+/// Undecided (`None`) as soon as one of the evaluations is.
+pub fn all_of<I>(values: I) -> Option<bool>
+where
+    I: IntoIterator<Item = Option<bool>>,
+{
+    values
+        .into_iter()
+        .collect::<Option<Vec<_>>>()
+        .map(|values| values.into_iter().all(|value| value))
+}
+
+/// Conjunction of the evaluations of some tasks, with the tasks that do not hold
+///
+/// The failing tasks are listed even when the conjunction is undecided.
+pub fn all_of_tasks<T, F>(tasks: &[T], value: F) -> (Option<bool>, Vec<T>)
+where
+    T: Clone,
+    F: Fn(&T) -> Option<bool>,
+{
+    let values = tasks
+        .iter()
+        .map(|task| (task, value(task)))
+        .collect::<Vec<_>>();
+    let failed = values
+        .iter()
+        .filter(|(_, value)| *value == Some(false))
+        .map(|(task, _)| (*task).clone())
+        .collect();
+    (all_of(values.into_iter().map(|(_, value)| value)), failed)
+}
+
+/// Whether following the issuers from `identity` reaches one of the trust anchors
+///
+/// `issuers` maps each identity to the identity that signed its certificate. The chain is
+/// broken by a self-signed certificate that is not an anchor, or by an unknown issuer.
+pub fn chains_to_anchor(
+    identity: &str,
+    issuers: &HashMap<String, String>,
+    anchors: &[String],
+) -> bool {
+    let mut current = identity;
+    for _ in 0..=issuers.len() {
+        if anchors.iter().any(|anchor| name_eq(anchor, current)) {
+            return true;
+        }
+        match issuers.get(current) {
+            Some(issuer) if issuer != current => current = issuer,
+            _ => return false,
+        }
+    }
+    false
+}
+
+/// Number of certificates in the signing chain of `identity`, its own included
+///
+/// The issuers are followed up to a self-signed certificate or to an issuer outside of `issuers`,
+/// counted as the last link. A cycle of issuers ends the chain, each identity counting once.
+pub fn chain_depth(identity: &str, issuers: &HashMap<String, String>) -> usize {
+    let mut chain = HashSet::new();
+    let mut current = identity;
+    while chain.insert(current) {
+        match issuers.get(current) {
+            Some(issuer) => current = issuer,
+            None => break,
+        }
+    }
+    chain.len()
+}
+
+/// Median of the values, `None` if there are none
+pub fn median(values: &[i64]) -> Option<f64> {
+    let mut values = values.to_vec();
+    values.sort_unstable();
+    let n = values.len();
+    match n {
+        0 => None,
+        _ if n % 2 == 1 => Some(values[n / 2] as f64),
+        _ => Some((values[n / 2 - 1] + values[n / 2]) as f64 / 2.0),
+    }
+}
+
+/// Whether `value` lies between `lo` and `hi`, bounds included when `inclusive`
+pub fn in_range<T: PartialOrd>(value: T, lo: T, hi: T, inclusive: bool) -> bool {
+    if inclusive {
+        lo <= value && value <= hi
+    } else {
+        lo < value && value < hi
+    }
+}
+
+/// Weighted percentage, from 0 to 100, of the evaluations that are true
+///
+/// Undecided evaluations are left out, and with nothing left to weigh there is no score.
+pub fn weighted_score<I>(evaluations: I) -> Option<f64>
+where
+    I: IntoIterator<Item = (f64, Option<bool>)>,
+{
+    let (passed, total) = evaluations
+        .into_iter()
+        .filter_map(|(weight, value)| value.map(|value| (weight, value)))
+        .fold((0.0, 0.0), |(passed, total), (weight, value)| {
+            (passed + if value { weight } else { 0.0 }, total + weight)
+        });
+    if total > 0.0 {
+        Some(100.0 * passed / total)
+    } else {
+        None
+    }
+}
+
+/// Whether a series grows by more than `threshold` in a sustained way
+///
+/// Growth is sustained when the series rises in most of its steps and falls in at most a quarter
+/// of them, so a single step increase, such as a batch of new neighbors, is not mistaken for a
+/// steady leak, while a leak briefly relieved by a cleanup still is.
+pub fn sustained_growth(values: &[u64], threshold: u64) -> bool {
+    let (first, last) = match (values.first(), values.last()) {
+        (Some(first), Some(last)) => (*first, *last),
+        _ => return false,
+    };
+    let steps = values.len() - 1;
+    let rising = values.windows(2).filter(|w| w[1] > w[0]).count();
+    let falling = values.windows(2).filter(|w| w[1] < w[0]).count();
+    last.saturating_sub(first) > threshold && falling * 4 <= steps && 2 * rising > steps
+}
+
+/// Percentile `p`, from 0 to 100, of the values, interpolating between the closest ranks
+///
+/// `None` without values, while a single value is every percentile.
+pub fn percentile(values: &[f64], p: f64) -> Option<f64> {
+    let mut values = values.to_vec();
+    values.sort_by(|a, b| a.partial_cmp(b).unwrap_or(std::cmp::Ordering::Equal));
+    let last = values.len().checked_sub(1)?;
+    let rank = p.clamp(0.0, 100.0) / 100.0 * last as f64;
+    let (low, high) = (rank.floor() as usize, rank.ceil() as usize);
+    Some(values[low] + (values[high] - values[low]) * (rank - low as f64))
+}
+
+/// Number of values at the end of the series repeating the one before them
+///
+/// A clock stuck for two ticks after advancing, e.g. `[1, 2, 2, 2]`, repeats twice.
+pub fn trailing_repeats<T: PartialEq>(values: &[T]) -> usize {
+    values.windows(2).rev().take_while(|w| w[0] == w[1]).count()
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn conjunction() {
+        assert_eq!(all_of(vec![]), Some(true));
+        assert_eq!(all_of(vec![Some(true), Some(true)]), Some(true));
+        assert_eq!(all_of(vec![Some(true), Some(false)]), Some(false));
+        assert_eq!(all_of(vec![Some(false), None]), None);
+    }
+
+    #[test]
+    fn failing_tasks() {
+        let values = [Some(true), Some(false), None, Some(false)];
+        let (value, failed) = all_of_tasks(&[0, 1, 2, 3], |i| values[*i]);
+        assert_eq!((value, failed), (None, vec![1, 3]));
+        assert_eq!(all_of_tasks(&[0], |i| values[*i]), (Some(true), vec![]));
+    }
+
+    #[test]
+    fn trust_anchor_chains() {
+        let issuers = vec![
+            ("/ndn/unimi/bertof", "/ndn/unimi"),
+            ("/ndn/unimi", "/ndn"),
+            ("/ndn", "/ndn"),
+            ("/test", "/test"),
+            ("/loop/a", "/loop/b"),
+            ("/loop/b", "/loop/a"),
+        ]
+        .into_iter()
+        .map(|(i, s)| (i.to_string(), s.to_string()))
+        .collect::<HashMap<_, _>>();
+        let anchors = vec!["/ndn".to_string()];
+        assert!(chains_to_anchor("/ndn/unimi/bertof", &issuers, &anchors));
+        assert!(chains_to_anchor("/ndn", &issuers, &anchors));
+        assert!(!chains_to_anchor("/test", &issuers, &anchors));
+        assert!(!chains_to_anchor("/loop/a", &issuers, &anchors));
+        assert!(!chains_to_anchor("/unknown", &issuers, &anchors));
+        let anchors = vec!["/ndn/%75nimi".to_string()];
+        assert!(chains_to_anchor("/ndn/unimi/bertof", &issuers, &anchors));
+        assert!(!chains_to_anchor("/ndn", &issuers, &anchors));
+    }
+
+    #[test]
+    fn signing_chain_depths() {
+        let issuers = vec![
+            ("/ndn/unimi/bertof", "/ndn/unimi"),
+            ("/ndn/unimi", "/ndn"),
+            ("/ndn", "/ndn"),
+            ("/site/user", "/site"),
+            ("/loop/a", "/loop/b"),
+            ("/loop/b", "/loop/a"),
+        ]
+        .into_iter()
+        .map(|(i, s)| (i.to_string(), s.to_string()))
+        .collect::<HashMap<_, _>>();
+        assert_eq!(chain_depth("/ndn/unimi/bertof", &issuers), 3);
+        assert_eq!(chain_depth("/ndn", &issuers), 1);
+        assert_eq!(chain_depth("/site/user", &issuers), 2);
+        assert_eq!(chain_depth("/loop/a", &issuers), 2);
+        assert_eq!(chain_depth("/unknown", &issuers), 1);
+    }
+
+    #[test]
+    fn median_of_values() {
+        assert_eq!(median(&[]), None);
+        assert_eq!(median(&[3, 1, 2]), Some(2.0));
+        assert_eq!(median(&[4, 1, 3, 2]), Some(2.5));
+    }
+
+    #[test]
+    fn range_edges() {
+        assert!(in_range(10, 10, 20, true));
+        assert!(in_range(20, 10, 20, true));
+        assert!(!in_range(10, 10, 20, false));
+        assert!(!in_range(20, 10, 20, false));
+        assert!(in_range(3.5, 3.0, 12.0, false));
+        assert!(!in_range(9, 10, u64::MAX, true));
+        assert!(!in_range(f64::NAN, 3.0, 12.0, true));
+    }
+
+    #[test]
+    fn weighted_scores() {
+        assert_eq!(weighted_score(vec![]), None);
+        assert_eq!(weighted_score(vec![(1.0, None)]), None);
+        assert_eq!(
+            weighted_score(vec![(1.0, Some(true)), (1.0, Some(false))]),
+            Some(50.0)
+        );
+        assert_eq!(
+            weighted_score(vec![
+                (3.0, Some(true)),
+                (1.0, Some(false)),
+                (4.0, None),
+                (0.0, Some(false)),
+            ]),
+            Some(75.0)
+        );
+    }
+
+    #[test]
+    fn growth_trends() {
+        let leak = [10, 12, 14, 15, 17, 20, 22];
+        assert!(sustained_growth(&leak, 10));
+        assert!(!sustained_growth(&leak, 12));
+        let relieved = [10, 12, 14, 13, 16, 18, 20];
+        assert!(sustained_growth(&relieved, 5));
+        let step = [10, 10, 10, 30, 30, 30, 30];
+        assert!(!sustained_growth(&step, 10));
+        let churn = [10, 15, 12, 18, 16, 24, 22];
+        assert!(!sustained_growth(&churn, 10));
+        assert!(!sustained_growth(&[], 0));
+        assert!(!sustained_growth(&[10], 0));
+    }
+
+    #[test]
+    fn percentiles() {
+        let values = [15.0, 20.0, 35.0, 40.0, 50.0];
+        assert_eq!(percentile(&values, 50.0), Some(35.0));
+        assert_eq!(percentile(&values, 0.0), Some(15.0));
+        assert_eq!(percentile(&values, 100.0), Some(50.0));
+        assert_eq!(percentile(&values, 25.0), Some(20.0));
+        assert_eq!(percentile(&[40.0, 10.0], 50.0), Some(25.0));
+        assert_eq!(percentile(&[42.0], 95.0), Some(42.0));
+        assert_eq!(percentile(&[], 50.0), None);
+    }
+
+    #[test]
+    fn repeated_tails() {
+        assert_eq!(trailing_repeats(&[1, 2, 2, 2]), 2);
+        assert_eq!(trailing_repeats(&[1, 2, 3]), 0);
+        assert_eq!(trailing_repeats(&[2, 2, 1, 1]), 1);
+        assert_eq!(trailing_repeats(&[7]), 0);
+        assert_eq!(trailing_repeats::<u64>(&[]), 0);
+    }
+}
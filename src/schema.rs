@@ -0,0 +1,170 @@
+//! JSON Schema of the logs file, the [`Table`](crate::task::Table) serialized by the agent
+//!
+//! Downstream tools can check the logs they read against it. The names of the metrics, of the
+//! tasks and of the measured data come from [`Metrics::ALL`] and the DAG of the properties, so
+//! the schema follows the `Data`, `Metrics` and `Tasks` enums. The payload of each measured data
+//! is left open, as it mirrors the parsed command outputs.
+
+use crate::agent::{Metrics, Tasks};
+use serde_json::{json, Value};
+use std::collections::BTreeSet;
+
+/// Every task, found walking the DAG down from the properties
+fn task_names() -> BTreeSet<String> {
+    let mut tasks = BTreeSet::new();
+    let mut pending = vec![Tasks::P1, Tasks::P2, Tasks::P3];
+    while let Some(task) = pending.pop() {
+        pending.extend(task.dependencies().iter().cloned());
+        tasks.insert(format!("{:?}", task));
+    }
+    tasks
+}
+
+fn metric_names() -> Vec<String> {
+    Metrics::ALL.iter().map(|m| format!("{:?}", m)).collect()
+}
+
+/// Object keyed by the names in `keys`, each value being a map matching `entries`
+fn keyed_by(keys: &[String], entries: Value) -> Value {
+    json!({
+        "type": "object",
+        "propertyNames": {"enum": keys},
+        "additionalProperties": entries,
+    })
+}
+
+/// Map from the tick indexes to `value`
+fn by_index(value: Value) -> Value {
+    json!({
+        "type": "object",
+        "propertyNames": {"pattern": "^[0-9]+$"},
+        "additionalProperties": value,
+    })
+}
+
+/// Map from the timestamps to `value`
+fn by_timestamp(value: Value) -> Value {
+    json!({
+        "type": "object",
+        "propertyNames": {"format": "date-time"},
+        "additionalProperties": value,
+    })
+}
+
+/// JSON Schema, draft 7, of the logs file
+pub fn logs_schema() -> Value {
+    let metrics = metric_names();
+    let tasks = task_names().into_iter().collect::<Vec<_>>();
+    let mut data = metrics.clone();
+    data.push("ParseError".to_string());
+    let data_ref = json!({"$ref": "#/definitions/Data"});
+    let value = json!({"type": ["boolean", "null"]});
+    json!({
+        "$schema": "http://json-schema.org/draft-07/schema#",
+        "title": "Logs of the NDN certification agent",
+        "type": "object",
+        "required": [
+            "measurements_index",
+            "measurements_timestamp",
+            "evaluations_index",
+            "evaluations_timestamp",
+            "duration_index",
+        ],
+        "additionalProperties": false,
+        "properties": {
+            "measurements_index": keyed_by(&metrics, by_index(data_ref.clone())),
+            "measurements_timestamp": keyed_by(&metrics, by_timestamp(data_ref)),
+            "evaluations_index": keyed_by(&tasks, by_index(value.clone())),
+            "evaluations_timestamp": keyed_by(&tasks, by_timestamp(value)),
+            "duration_index": by_index(json!({"type": "integer"})),
+            "scores_index": by_index(json!({"type": "number", "minimum": 0, "maximum": 100})),
+            "chain_index": by_index(json!({"type": "string", "pattern": "^[0-9a-f]{64}$"})),
+            "errors_index": by_index(json!({"type": "string"})),
+            "summaries_timestamp": keyed_by(
+                &metrics,
+                by_timestamp(json!({"$ref": "#/definitions/Summary"})),
+            ),
+            "source_digests_index": keyed_by(
+                &metrics,
+                by_index(json!({"type": "string", "pattern": "^[0-9a-f]{64}$"})),
+            ),
+            "completed_at_index": keyed_by(
+                &metrics,
+                by_index(json!({"type": "string", "format": "date-time"})),
+            ),
+        },
+        "definitions": {
+            "Data": {
+                "description": "Measured data, tagged with its metric or ParseError",
+                "type": "object",
+                "minProperties": 1,
+                "maxProperties": 1,
+                "propertyNames": {"enum": data},
+            },
+            "Summary": {
+                "type": "object",
+                "required": ["count", "min", "max", "mean"],
+                "properties": {
+                    "count": {"type": "integer", "minimum": 0},
+                    "min": {"type": ["number", "null"]},
+                    "max": {"type": ["number", "null"]},
+                    "mean": {"type": ["number", "null"]},
+                },
+            },
+        },
+    })
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::agent::{Agent, Verbosity};
+    use crate::command::{ndnsec, nfdc, MockRunner};
+    use crate::policy::Policy;
+    use jsonschema::JSONSchema;
+
+    #[async_std::test]
+    async fn logs_match_schema() {
+        let dump = include_str!("command/ndnsec/dump.txt").to_string();
+        let runner = MockRunner::default()
+            .with_output(
+                &nfdc::NfdcCommand::Status,
+                Ok(include_str!("command/nfdc_report.xml").to_string()),
+            )
+            .with_output(
+                &ndnsec::NdnSecCommand::List,
+                Ok(include_str!("command/ndnsec/list.txt").to_string()),
+            )
+            .with_output(
+                &ndnsec::NdnSecCommand::Dump("/test".to_string()),
+                Ok(dump.clone()),
+            )
+            .with_output(
+                &ndnsec::NdnSecCommand::Dump("/bertof".to_string()),
+                Ok(dump),
+            );
+        let policy = Policy {
+            hash_chain: true,
+            record_source_digests: true,
+            ..Policy::default()
+        };
+        let agent = Agent::with_runner(policy, runner).with_verbosity(Verbosity::Silent);
+        for index in 0..2 {
+            agent.tick(index).await.verdict.unwrap();
+        }
+        let logs = serde_json::to_value(agent.logs().read().unwrap().to_table()).unwrap();
+
+        let schema = JSONSchema::compile(&logs_schema()).unwrap();
+        if let Err(errors) = schema.validate(&logs) {
+            panic!("{:?}", errors.map(|e| e.to_string()).collect::<Vec<_>>());
+        }
+        assert_eq!(task_names().len(), 73);
+
+        let mut altered = logs.clone();
+        altered["evaluations_index"]["C99"] = json!({"0": true});
+        assert!(!schema.is_valid(&altered));
+        let mut altered = logs;
+        altered["duration_index"] = json!({"first": 1});
+        assert!(!schema.is_valid(&altered));
+    }
+}
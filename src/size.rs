@@ -0,0 +1,160 @@
+use nom::{
+    branch::alt,
+    bytes::complete::tag,
+    character::complete::space0,
+    combinator::{all_consuming, opt, verify},
+    number::complete::double,
+    sequence::{pair, preceded},
+    IResult,
+};
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+use std::fmt;
+use std::str::FromStr;
+use thiserror::Error as ThisError;
+
+const UNITS: [(&str, u64); 9] = [
+    ("TiB", 1 << 40),
+    ("GiB", 1 << 30),
+    ("MiB", 1 << 20),
+    ("KiB", 1 << 10),
+    ("TB", 1_000_000_000_000),
+    ("GB", 1_000_000_000),
+    ("MB", 1_000_000),
+    ("kB", 1_000),
+    ("B", 1),
+];
+
+#[derive(Debug, Clone, PartialEq, Eq, ThisError)]
+#[error("Invalid size: {0}")]
+pub struct ParseSizeError(String);
+
+/// Amount of memory in bytes
+///
+/// Parsed from and printed as human-friendly sizes, e.g. `8KiB` or `1GiB`.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct ByteSize(pub u64);
+
+impl ByteSize {
+    pub const fn from_kib(kib: u64) -> Self {
+        ByteSize(kib << 10)
+    }
+
+    pub const fn from_mib(mib: u64) -> Self {
+        ByteSize(mib << 20)
+    }
+
+    pub const fn from_gib(gib: u64) -> Self {
+        ByteSize(gib << 30)
+    }
+
+    pub const fn as_u64(self) -> u64 {
+        self.0
+    }
+
+    pub fn as_mib(self) -> f64 {
+        self.0 as f64 / (1u64 << 20) as f64
+    }
+
+    pub fn as_gib(self) -> f64 {
+        self.0 as f64 / (1u64 << 30) as f64
+    }
+
+    fn parse(input: &str) -> IResult<&str, Self> {
+        let (input, (value, unit)) = pair(
+            verify(double, |v: &f64| v.is_finite() && *v >= 0.0),
+            opt(preceded(
+                space0,
+                alt((
+                    tag("TiB"),
+                    tag("GiB"),
+                    tag("MiB"),
+                    tag("KiB"),
+                    tag("TB"),
+                    tag("GB"),
+                    tag("MB"),
+                    tag("kB"),
+                    tag("B"),
+                )),
+            )),
+        )(input)?;
+        let factor = UNITS
+            .iter()
+            .find(|(name, _)| Some(*name) == unit)
+            .map_or(1, |(_, factor)| *factor);
+        Ok((input, ByteSize((value * factor as f64).round() as u64)))
+    }
+}
+
+impl FromStr for ByteSize {
+    type Err = ParseSizeError;
+    fn from_str(input: &str) -> Result<Self, Self::Err> {
+        let (_, size) = all_consuming(Self::parse)(input.trim())
+            .map_err(|_| ParseSizeError(input.to_string()))?;
+        Ok(size)
+    }
+}
+
+impl fmt::Display for ByteSize {
+    /// Largest binary unit dividing the size exactly
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let (unit, factor) = UNITS
+            .iter()
+            .filter(|(name, _)| name.ends_with("iB") || *name == "B")
+            .find(|(_, factor)| self.0 >= *factor && self.0 % *factor == 0)
+            .unwrap_or(&("B", 1));
+        write!(f, "{}{}", self.0 / factor, unit)
+    }
+}
+
+impl Serialize for ByteSize {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_str(&self.to_string())
+    }
+}
+
+impl<'de> Deserialize<'de> for ByteSize {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        #[derive(Deserialize)]
+        #[serde(untagged)]
+        enum Repr {
+            Bytes(u64),
+            Text(String),
+        }
+        match Repr::deserialize(deserializer)? {
+            Repr::Bytes(bytes) => Ok(ByteSize(bytes)),
+            Repr::Text(text) => text.parse().map_err(serde::de::Error::custom),
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn parse_sizes() {
+        assert_eq!("8192B".parse(), Ok(ByteSize(8192)));
+        assert_eq!("8KiB".parse(), Ok(ByteSize::from_kib(8)));
+        assert_eq!("1GiB".parse(), Ok(ByteSize(1 << 30)));
+        assert_eq!("1.5 MiB".parse(), Ok(ByteSize(3 << 19)));
+        assert_eq!("2kB".parse(), Ok(ByteSize(2000)));
+        assert_eq!("42".parse(), Ok(ByteSize(42)));
+        assert!("8 parsecs".parse::<ByteSize>().is_err());
+        assert!("GiB".parse::<ByteSize>().is_err());
+        assert!("-1GiB".parse::<ByteSize>().is_err());
+    }
+
+    #[test]
+    fn human_friendly_serialization() {
+        assert_eq!(ByteSize::from_gib(8).to_string(), "8GiB");
+        assert_eq!(ByteSize(8193).to_string(), "8193B");
+        assert_eq!(ByteSize(0).to_string(), "0B");
+        assert_eq!(ByteSize::from_mib(512).as_gib(), 0.5);
+        let sizes: Vec<ByteSize> = serde_json::from_str("[\"8GiB\", 4096]").unwrap();
+        assert_eq!(sizes, [ByteSize::from_gib(8), ByteSize::from_kib(4)]);
+        assert_eq!(
+            serde_json::to_string(&sizes).unwrap(),
+            "[\"8GiB\",\"4KiB\"]"
+        );
+    }
+}
@@ -1,7 +1,10 @@
 use crate::command;
-use chrono::{DateTime, Utc};
+use crate::digest::sha256_hex;
+use chrono::{DateTime, Duration, TimeZone, Utc};
 use serde::{Deserialize, Serialize};
-use std::collections::{HashMap, VecDeque};
+use std::cmp::Ordering;
+use std::collections::{BTreeMap, BTreeSet, HashMap, VecDeque};
+use std::fmt;
 use std::hash::Hash;
 use thiserror::Error as ThisError;
 
@@ -17,11 +20,34 @@ pub enum Error {
     EvaluationError(String),
 }
 
+impl Error {
+    /// Whether the error comes from parsing the output of a command
+    pub fn is_parse_error(&self) -> bool {
+        matches!(
+            self,
+            Error::TaskError(
+                command::Error::XmlParsingError(_)
+                    | command::Error::NomParsingError(_)
+                    | command::Error::Base64DecodingError(_)
+                    | command::Error::PublicKeyDecodingError(_)
+            )
+        )
+    }
+}
+
 #[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct Measurement<Data> {
     pub data: Data,
     pub index: u64,
     pub timestamp: DateTime<Utc>,
+    /// SHA-256 digest of the raw command output the measurement was derived from, if recorded
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub source_digest: Option<String>,
+    /// Time the command the measurement was derived from completed, if known
+    ///
+    /// Commands of the same tick complete at different times, earlier than `timestamp`.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub completed_at: Option<DateTime<Utc>>,
 }
 
 impl<Data> Measurement<Data> {
@@ -30,27 +56,151 @@ impl<Data> Measurement<Data> {
             data,
             index,
             timestamp: Utc::now(),
+            source_digest: None,
+            completed_at: None,
         }
     }
+
+    /// Attach the digest of the command output the measurement was derived from
+    pub fn with_source_digest(mut self, source_digest: Option<String>) -> Self {
+        self.source_digest = source_digest;
+        self
+    }
+
+    /// Attach the time the command the measurement was derived from completed
+    pub fn with_completed_at(mut self, completed_at: Option<DateTime<Utc>>) -> Self {
+        self.completed_at = completed_at;
+        self
+    }
+
+    /// Chronological order of two measurements, by index and then by timestamp
+    ///
+    /// The data is not compared, e.g. `measurements.sort_by(Measurement::chronological)`.
+    pub fn chronological(&self, other: &Self) -> Ordering {
+        (self.index, self.timestamp).cmp(&(other.index, other.timestamp))
+    }
+}
+
+impl<Data: Serialize> Measurement<Data> {
+    /// Flat record of the measurement of `metric`, for line-oriented exports
+    pub fn to_record<Metrics: fmt::Debug>(&self, metric: &Metrics) -> Record {
+        let value = match serde_json::to_value(&self.data) {
+            // Enum variants are tagged by their name, keep the content only
+            Ok(serde_json::Value::Object(map)) if map.len() == 1 => {
+                map.into_iter().next().map(|(_, value)| value).unwrap()
+            }
+            Ok(value) => value,
+            Err(e) => serde_json::Value::String(e.to_string()),
+        };
+        Record {
+            metric: format!("{:?}", metric),
+            index: self.index,
+            timestamp: self.timestamp,
+            completed_at: self.completed_at,
+            value: match value {
+                serde_json::Value::String(s) => s,
+                value => value.to_string(),
+            },
+        }
+    }
+}
+
+/// Measurement flattened to a single line, with structured values encoded as JSON
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Record {
+    pub metric: String,
+    pub index: u64,
+    pub timestamp: DateTime<Utc>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub completed_at: Option<DateTime<Utc>>,
+    pub value: String,
 }
 
 #[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct Evaluation {
-    pub value: bool,
+    /// Outcome of the evaluation, `None` when there is not enough data to decide
+    pub value: Option<bool>,
     pub index: u64,
     pub timestamp: DateTime<Utc>,
+    /// Dependencies that did not hold, for the rules and properties combining several of them
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub failed_dependencies: Vec<String>,
 }
 
 impl Evaluation {
-    pub fn new(evaluation: bool, index: u64) -> Self {
+    pub fn new<V: Into<Option<bool>>>(evaluation: V, index: u64) -> Self {
         Self {
-            value: evaluation,
+            value: evaluation.into(),
             index,
             timestamp: Utc::now(),
+            failed_dependencies: Vec::new(),
         }
     }
+
+    pub fn with_failed_dependencies<T: fmt::Debug>(mut self, tasks: &[T]) -> Self {
+        self.failed_dependencies = tasks.iter().map(|task| format!("{:?}", task)).collect();
+        self
+    }
+
+    /// Evaluation withheld for lack of data, ignored by the windowed rules and pass rates
+    pub fn skipped(index: u64) -> Self {
+        Self::new(None, index)
+    }
+
+    pub fn is_skipped(&self) -> bool {
+        self.value.is_none()
+    }
+}
+
+/// How a windowed task combines the evaluations of its dependencies within the window
+#[derive(Debug, Clone, Copy, Default, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum Aggregation {
+    /// Every evaluation is true
+    #[default]
+    AllInWindow,
+    /// The latest evaluation is true
+    LatestOnly,
+    /// At least the given share of the evaluations, between 0 and 1, is true
+    AtLeastFraction(f64),
 }
 
+/// Data with a numeric value that can be summarized when downsampling the logs
+pub trait Numeric {
+    /// Numeric value of the data, `None` for data that cannot be summarized
+    fn as_f64(&self) -> Option<f64>;
+}
+
+/// Summary of the numeric measurements of a metric within a time bucket
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct Summary {
+    pub count: usize,
+    pub min: f64,
+    pub max: f64,
+    pub mean: f64,
+}
+
+impl Summary {
+    fn new(value: f64) -> Self {
+        Summary {
+            count: 1,
+            min: value,
+            max: value,
+            mean: value,
+        }
+    }
+
+    fn add(&mut self, value: f64) {
+        self.min = self.min.min(value);
+        self.max = self.max.max(value);
+        self.count += 1;
+        self.mean += (value - self.mean) / self.count as f64;
+    }
+}
+
+/// Values of the evaluations of a task, keyed by index or timestamp
+pub type EvaluationHistory<K> = VecDeque<(K, Option<bool>)>;
+
 #[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct Logs<Metrics, Tasks, Data>
 where
@@ -59,9 +209,27 @@ where
 {
     pub measurements_index: HashMap<Metrics, VecDeque<(u64, Data)>>,
     pub measurements_timestamp: HashMap<Metrics, VecDeque<(DateTime<Utc>, Data)>>,
-    pub evaluations_index: HashMap<Tasks, VecDeque<(u64, bool)>>,
-    pub evaluations_timestamp: HashMap<Tasks, VecDeque<(DateTime<Utc>, bool)>>,
+    pub evaluations_index: HashMap<Tasks, EvaluationHistory<u64>>,
+    pub evaluations_timestamp: HashMap<Tasks, EvaluationHistory<DateTime<Utc>>>,
     pub duration_index: HashMap<u64, i64>,
+    /// Weighted compliance score of each tick, when some weighted evaluation decided
+    #[serde(default)]
+    pub scores_index: HashMap<u64, f64>,
+    /// Digest of the evaluations of each tick chained to the one of the previous tick
+    #[serde(default)]
+    pub chain_index: HashMap<u64, String>,
+    /// Error of each tick whose properties could not be evaluated
+    #[serde(default)]
+    pub errors_index: HashMap<u64, String>,
+    /// Summaries of the downsampled measurements, keyed by the start of their bucket
+    #[serde(default = "HashMap::new")]
+    pub summaries_timestamp: HashMap<Metrics, VecDeque<(DateTime<Utc>, Summary)>>,
+    /// Digest of the command output each measurement was derived from, where recorded
+    #[serde(default = "HashMap::new")]
+    pub source_digests_index: HashMap<Metrics, HashMap<u64, String>>,
+    /// Time the command each measurement was derived from completed, where known
+    #[serde(default = "HashMap::new")]
+    pub completed_at_index: HashMap<Metrics, HashMap<u64, DateTime<Utc>>>,
 }
 
 impl<Metrics, Tasks, Data> Default for Logs<Metrics, Tasks, Data>
@@ -76,6 +244,12 @@ where
             evaluations_index: HashMap::default(),
             evaluations_timestamp: HashMap::default(),
             duration_index: HashMap::default(),
+            scores_index: HashMap::default(),
+            chain_index: HashMap::default(),
+            errors_index: HashMap::default(),
+            summaries_timestamp: HashMap::default(),
+            source_digests_index: HashMap::default(),
+            completed_at_index: HashMap::default(),
         }
     }
 }
@@ -88,9 +262,21 @@ where
 {
     pub measurements_index: HashMap<Metrics, HashMap<u64, Data>>,
     pub measurements_timestamp: HashMap<Metrics, HashMap<DateTime<Utc>, Data>>,
-    pub evaluations_index: HashMap<Tasks, HashMap<u64, bool>>,
-    pub evaluations_timestamp: HashMap<Tasks, HashMap<DateTime<Utc>, bool>>,
+    pub evaluations_index: HashMap<Tasks, HashMap<u64, Option<bool>>>,
+    pub evaluations_timestamp: HashMap<Tasks, HashMap<DateTime<Utc>, Option<bool>>>,
     pub duration_index: HashMap<u64, i64>,
+    #[serde(default)]
+    pub scores_index: HashMap<u64, f64>,
+    #[serde(default)]
+    pub chain_index: HashMap<u64, String>,
+    #[serde(default)]
+    pub errors_index: HashMap<u64, String>,
+    #[serde(default = "HashMap::new")]
+    pub summaries_timestamp: HashMap<Metrics, HashMap<DateTime<Utc>, Summary>>,
+    #[serde(default = "HashMap::new")]
+    pub source_digests_index: HashMap<Metrics, HashMap<u64, String>>,
+    #[serde(default = "HashMap::new")]
+    pub completed_at_index: HashMap<Metrics, HashMap<u64, DateTime<Utc>>>,
 }
 
 #[derive(Debug, Clone, PartialEq)]
@@ -111,14 +297,7 @@ where
         metric: Metrics,
     ) -> Logs<Metrics, Tasks, Data> {
         let mut res = self.clone();
-        res.measurements_index
-            .entry(metric.clone())
-            .or_insert_with(Default::default)
-            .push_back((measurement.index, measurement.data.clone()));
-        res.measurements_timestamp
-            .entry(metric)
-            .or_insert_with(Default::default)
-            .push_back((measurement.timestamp, measurement.data));
+        res.insert_measurement(measurement, metric);
         res
     }
 
@@ -127,13 +306,25 @@ where
         measurement: Measurement<Data>,
         metric: Metrics,
     ) -> &mut Self {
+        if let Some(source_digest) = measurement.source_digest {
+            self.source_digests_index
+                .entry(metric.clone())
+                .or_default()
+                .insert(measurement.index, source_digest);
+        }
+        if let Some(completed_at) = measurement.completed_at {
+            self.completed_at_index
+                .entry(metric.clone())
+                .or_default()
+                .insert(measurement.index, completed_at);
+        }
         self.measurements_index
             .entry(metric.clone())
-            .or_insert_with(Default::default)
+            .or_default()
             .push_back((measurement.index, measurement.data.clone()));
         self.measurements_timestamp
             .entry(metric)
-            .or_insert_with(Default::default)
+            .or_default()
             .push_back((measurement.timestamp, measurement.data));
         self
     }
@@ -146,11 +337,11 @@ where
         let mut res = self.clone();
         res.evaluations_index
             .entry(task.clone())
-            .or_insert_with(Default::default)
+            .or_default()
             .push_back((evaluation.index, evaluation.value));
         res.evaluations_timestamp
             .entry(task)
-            .or_insert_with(Default::default)
+            .or_default()
             .push_back((evaluation.timestamp, evaluation.value));
         res
     }
@@ -158,11 +349,11 @@ where
     pub fn insert_evaluation(&mut self, evaluation: Evaluation, task: Tasks) -> &mut Self {
         self.evaluations_index
             .entry(task.clone())
-            .or_insert_with(Default::default)
+            .or_default()
             .push_back((evaluation.index, evaluation.value));
         self.evaluations_timestamp
             .entry(task)
-            .or_insert_with(Default::default)
+            .or_default()
             .push_back((evaluation.timestamp, evaluation.value));
         self
     }
@@ -178,6 +369,108 @@ where
         self
     }
 
+    /// Record the compliance score of the tick `index`
+    pub fn insert_score(&mut self, score: f64, index: u64) -> &mut Self {
+        self.scores_index.insert(index, score);
+        self
+    }
+
+    /// Record why the tick `index` failed, so that the gap it leaves can be explained
+    pub fn insert_error(&mut self, error: String, index: u64) -> &mut Self {
+        self.errors_index.insert(index, error);
+        self
+    }
+
+    /// Durations of the last `n` logged executions, oldest first
+    pub fn recent_durations(&self, n: usize) -> Vec<i64> {
+        let mut durations = self.duration_index.iter().collect::<Vec<_>>();
+        durations.sort_by_key(|(index, _)| **index);
+        durations
+            .iter()
+            .rev()
+            .take(n)
+            .rev()
+            .map(|(_, duration)| **duration)
+            .collect()
+    }
+
+    /// Number of evaluations with a value of a task since the given timestamp
+    pub fn samples_since(&self, task: &Tasks, since: DateTime<Utc>) -> usize {
+        self.evaluations_timestamp.get(task).map_or(0, |entries| {
+            entries
+                .iter()
+                .filter(|(timestamp, value)| *timestamp >= since && value.is_some())
+                .count()
+        })
+    }
+
+    /// Number of true/false transitions of a task evaluated since the given timestamp
+    pub fn transitions_since(&self, task: &Tasks, since: DateTime<Utc>) -> usize {
+        let values = self
+            .evaluations_timestamp
+            .get(task)
+            .map(|entries| {
+                entries
+                    .iter()
+                    .filter(|(timestamp, _)| *timestamp >= since)
+                    .filter_map(|(_, value)| *value)
+                    .collect::<Vec<_>>()
+            })
+            .unwrap_or_default();
+        values.windows(2).filter(|w| w[0] != w[1]).count()
+    }
+
+    /// Whether all the evaluations of a task since the given timestamp are true
+    ///
+    /// Evaluations without a value are ignored. With less than `min_samples` evaluations in
+    /// the window there is not enough data to decide, and `None` is returned.
+    pub fn all_true_since(
+        &self,
+        task: &Tasks,
+        since: DateTime<Utc>,
+        min_samples: usize,
+    ) -> Option<bool> {
+        self.holds_since(task, since, min_samples, Aggregation::AllInWindow)
+    }
+
+    /// Aggregate of the evaluations of a task since the given timestamp
+    ///
+    /// As with [`Logs::all_true_since`], evaluations without a value are ignored and `None` is
+    /// returned with less than `min_samples` evaluations in the window.
+    pub fn holds_since(
+        &self,
+        task: &Tasks,
+        since: DateTime<Utc>,
+        min_samples: usize,
+        aggregation: Aggregation,
+    ) -> Option<bool> {
+        let values = self
+            .evaluations_timestamp
+            .get(task)
+            .map(|entries| {
+                entries
+                    .iter()
+                    .rev()
+                    .take_while(|(timestamp, _)| *timestamp >= since)
+                    .filter_map(|(_, value)| *value)
+                    .collect::<Vec<_>>()
+            })
+            .unwrap_or_default();
+        if values.len() < min_samples {
+            return None;
+        }
+        let holds = match aggregation {
+            Aggregation::AllInWindow => values.iter().all(|value| *value),
+            // Values are collected newest first
+            Aggregation::LatestOnly => values.first().cloned().unwrap_or(true),
+            Aggregation::AtLeastFraction(fraction) => {
+                let true_values = values.iter().filter(|value| **value).count();
+                values.is_empty() || true_values as f64 >= fraction * values.len() as f64
+            }
+        };
+        Some(holds)
+    }
+
     pub fn merge(&self, other: &Self) -> Self {
         let mut res = self.clone();
         res.mut_merge(other);
@@ -186,10 +479,7 @@ where
 
     pub fn mut_merge(&mut self, other: &Self) -> &mut Self {
         for (metric, entry) in other.measurements_index.iter() {
-            let metric_measurements = self
-                .measurements_index
-                .entry(metric.clone())
-                .or_insert_with(Default::default);
+            let metric_measurements = self.measurements_index.entry(metric.clone()).or_default();
             let self_back_index = metric_measurements.back().map(|v| v.0);
             let other_back_index = entry.back().map(|v| v.0);
             match (self_back_index, other_back_index) {
@@ -210,7 +500,7 @@ where
             let metric_measurements = self
                 .measurements_timestamp
                 .entry(metric.clone())
-                .or_insert_with(Default::default);
+                .or_default();
             let self_back_timestamp = metric_measurements.back().map(|v| v.0);
             let other_back_timestamp = entry.back().map(|v| v.0);
             match (self_back_timestamp, other_back_timestamp) {
@@ -228,10 +518,7 @@ where
             }
         }
         for (metric, entry) in other.evaluations_index.iter() {
-            let task_evaluations = self
-                .evaluations_index
-                .entry(metric.clone())
-                .or_insert_with(Default::default);
+            let task_evaluations = self.evaluations_index.entry(metric.clone()).or_default();
             let self_back_index = task_evaluations.back().map(|v| v.0);
             let other_back_index = entry.back().map(|v| v.0);
             match (self_back_index, other_back_index) {
@@ -252,7 +539,7 @@ where
             let task_evaluations = self
                 .evaluations_timestamp
                 .entry(metric.clone())
-                .or_insert_with(Default::default);
+                .or_default();
             let self_back_timestamp = task_evaluations.back().map(|v| v.0);
             let other_back_timestamp = entry.back().map(|v| v.0);
             match (self_back_timestamp, other_back_timestamp) {
@@ -269,8 +556,24 @@ where
                 _ => {}
             }
         }
+        for (metric, entry) in other.summaries_timestamp.iter() {
+            let metric_summaries = self.summaries_timestamp.entry(metric.clone()).or_default();
+            let self_back_timestamp = metric_summaries.back().map(|v| v.0);
+            let new_summaries = entry
+                .iter()
+                .filter(|(o, _)| self_back_timestamp.is_none_or(|s| s < *o))
+                .cloned()
+                .collect::<Vec<_>>();
+            metric_summaries.extend(new_summaries);
+        }
         self.duration_index
             .extend(other.duration_index.iter().map(|(k, v)| (*k, *v)));
+        self.scores_index
+            .extend(other.scores_index.iter().map(|(k, v)| (*k, *v)));
+        merge_entries(&mut self.source_digests_index, &other.source_digests_index);
+        merge_entries(&mut self.completed_at_index, &other.completed_at_index);
+        self.chain_index.extend(other.chain_index.clone());
+        self.errors_index.extend(other.errors_index.clone());
         self
     }
 
@@ -311,30 +614,399 @@ where
                 (k, v)
             })
             .collect();
+        let summaries_timestamp = self
+            .summaries_timestamp
+            .iter()
+            .map(|(metric, entries)| {
+                let k = metric.clone();
+                let v = entries.iter().cloned().collect::<HashMap<_, _>>();
+                (k, v)
+            })
+            .collect();
         Table {
             measurements_index,
             measurements_timestamp,
             evaluations_index,
             evaluations_timestamp,
             duration_index: self.duration_index.clone(),
+            scores_index: self.scores_index.clone(),
+            chain_index: self.chain_index.clone(),
+            errors_index: self.errors_index.clone(),
+            summaries_timestamp,
+            source_digests_index: self.source_digests_index.clone(),
+            completed_at_index: self.completed_at_index.clone(),
+        }
+    }
+}
+
+fn sorted_entries<T: Hash + Eq, K: Ord + Clone, V: Clone>(
+    table: HashMap<T, HashMap<K, V>>,
+) -> HashMap<T, VecDeque<(K, V)>> {
+    table
+        .into_iter()
+        .map(|(k, entries)| {
+            let mut entries = entries.into_iter().collect::<Vec<_>>();
+            entries.sort_by(|(a, _), (b, _)| a.cmp(b));
+            (k, entries.into_iter().collect())
+        })
+        .collect()
+}
+
+impl<Metrics, Tasks, Data> From<Table<Metrics, Tasks, Data>> for Logs<Metrics, Tasks, Data>
+where
+    Metrics: Hash + Eq,
+    Tasks: Hash + Eq,
+    Data: Clone,
+{
+    /// Rebuild the logs of a table, with entries in chronological order
+    fn from(table: Table<Metrics, Tasks, Data>) -> Self {
+        Logs {
+            measurements_index: sorted_entries(table.measurements_index),
+            measurements_timestamp: sorted_entries(table.measurements_timestamp),
+            evaluations_index: sorted_entries(table.evaluations_index),
+            evaluations_timestamp: sorted_entries(table.evaluations_timestamp),
+            duration_index: table.duration_index,
+            scores_index: table.scores_index,
+            chain_index: table.chain_index,
+            errors_index: table.errors_index,
+            summaries_timestamp: sorted_entries(table.summaries_timestamp),
+            source_digests_index: table.source_digests_index,
+            completed_at_index: table.completed_at_index,
         }
     }
 }
 
+fn merge_entries<T, K, V>(table: &mut HashMap<T, HashMap<K, V>>, other: &HashMap<T, HashMap<K, V>>)
+where
+    T: Hash + Eq + Clone,
+    K: Hash + Eq + Clone,
+    V: Clone,
+{
+    for (k, entries) in other.iter() {
+        table
+            .entry(k.clone())
+            .or_default()
+            .extend(entries.iter().map(|(k, v)| (k.clone(), v.clone())));
+    }
+}
+
+impl<Metrics, Tasks, Data> Table<Metrics, Tasks, Data>
+where
+    Metrics: Clone + Hash + Eq,
+    Tasks: Clone + Hash + Eq,
+    Data: Clone,
+{
+    pub fn merge(&self, other: &Self) -> Self {
+        let mut res = self.clone();
+        res.mut_merge(other);
+        res
+    }
+
+    /// Add the entries of another table, the ones of `other` replacing the ones with the same
+    /// index or timestamp
+    pub fn mut_merge(&mut self, other: &Self) -> &mut Self {
+        merge_entries(&mut self.measurements_index, &other.measurements_index);
+        merge_entries(
+            &mut self.measurements_timestamp,
+            &other.measurements_timestamp,
+        );
+        merge_entries(&mut self.evaluations_index, &other.evaluations_index);
+        merge_entries(
+            &mut self.evaluations_timestamp,
+            &other.evaluations_timestamp,
+        );
+        merge_entries(&mut self.summaries_timestamp, &other.summaries_timestamp);
+        merge_entries(&mut self.source_digests_index, &other.source_digests_index);
+        merge_entries(&mut self.completed_at_index, &other.completed_at_index);
+        self.duration_index
+            .extend(other.duration_index.iter().map(|(k, v)| (*k, *v)));
+        self.scores_index
+            .extend(other.scores_index.iter().map(|(k, v)| (*k, *v)));
+        self.chain_index.extend(other.chain_index.clone());
+        self.errors_index.extend(other.errors_index.clone());
+        self
+    }
+}
+
+impl<Metrics, Tasks, Data> Logs<Metrics, Tasks, Data>
+where
+    Metrics: Clone + Hash + Eq,
+    Tasks: Hash + Eq,
+    Data: Numeric,
+{
+    /// Replace the measurements taken before `cutoff` with summaries of their numeric values
+    /// over buckets of `bucket`, e.g. one minute, aligned on the Unix epoch
+    ///
+    /// Measurements are logged by index and timestamp together, so as many of the oldest
+    /// entries are dropped by index. Data without a numeric value is dropped without summary.
+    pub fn downsample_older_than(&mut self, cutoff: DateTime<Utc>, bucket: Duration) -> &mut Self {
+        let bucket_ms = bucket.num_milliseconds().max(1);
+        for (metric, entries) in self.measurements_timestamp.iter_mut() {
+            let old = entries
+                .iter()
+                .take_while(|(timestamp, _)| *timestamp < cutoff)
+                .count();
+            if old == 0 {
+                continue;
+            }
+            let summaries = self.summaries_timestamp.entry(metric.clone()).or_default();
+            for (timestamp, data) in entries.drain(..old) {
+                let value = match data.as_f64() {
+                    Some(value) if !value.is_nan() => value,
+                    _ => continue,
+                };
+                let start_ms = timestamp.timestamp_millis().div_euclid(bucket_ms) * bucket_ms;
+                let start = Utc.timestamp_millis(start_ms);
+                match summaries.back_mut() {
+                    Some((last, summary)) if *last == start => summary.add(value),
+                    _ => summaries.push_back((start, Summary::new(value))),
+                }
+            }
+            if let Some(entries) = self.measurements_index.get_mut(metric) {
+                for (index, _) in entries.drain(..old.min(entries.len())) {
+                    if let Some(digests) = self.source_digests_index.get_mut(metric) {
+                        digests.remove(&index);
+                    }
+                    if let Some(completed_at) = self.completed_at_index.get_mut(metric) {
+                        completed_at.remove(&index);
+                    }
+                }
+            }
+        }
+        self
+    }
+}
+
+impl<Metrics, Tasks, Data> Logs<Metrics, Tasks, Data>
+where
+    Metrics: Hash + Eq + Ord + fmt::Debug,
+    Tasks: Hash + Eq,
+    Data: Serialize + Clone,
+{
+    /// Measurements flattened to records, by index and then by metric
+    ///
+    /// Measurements are logged by index and timestamp together, the entries of each metric
+    /// are paired from the most recent ones.
+    pub fn records(&self) -> Vec<Record> {
+        let mut records = Vec::new();
+        for (metric, entries) in self.measurements_index.iter() {
+            let timestamps = match self.measurements_timestamp.get(metric) {
+                Some(timestamps) => timestamps,
+                None => continue,
+            };
+            for ((index, data), (timestamp, _)) in entries.iter().rev().zip(timestamps.iter().rev())
+            {
+                let mut measurement = Measurement::new(data.clone(), *index);
+                measurement.timestamp = *timestamp;
+                measurement.completed_at = self
+                    .completed_at_index
+                    .get(metric)
+                    .and_then(|completed_at| completed_at.get(index))
+                    .cloned();
+                records.push((*index, metric, measurement));
+            }
+        }
+        records.sort_by(|a, b| (a.0, a.1).cmp(&(b.0, b.1)));
+        records
+            .into_iter()
+            .map(|(_, metric, measurement)| measurement.to_record(metric))
+            .collect()
+    }
+}
+
+impl<Metrics, Tasks, Data> Logs<Metrics, Tasks, Data>
+where
+    Metrics: Hash + Eq,
+    Tasks: Hash + Eq + Ord + Serialize,
+{
+    /// Digest of the evaluations of the tick `index` and of the digest of the previous tick
+    fn chain_digest(&self, index: u64, previous: Option<&String>) -> String {
+        let evaluations = self
+            .evaluations_index
+            .iter()
+            .filter_map(|(task, history)| {
+                let (_, value) = history.iter().find(|(i, _)| *i == index)?;
+                Some((task, *value))
+            })
+            .collect::<BTreeMap<_, _>>();
+        let record = serde_json::to_string(&(previous, index, evaluations))
+            .expect("Evaluations are serializable");
+        sha256_hex(record.as_bytes())
+    }
+
+    /// Chain the evaluations of the tick `index` to the ones of the previous ticks
+    pub fn insert_chain_link(&mut self, index: u64) -> &mut Self {
+        let previous = self
+            .chain_index
+            .iter()
+            .filter(|(i, _)| **i < index)
+            .max_by_key(|(i, _)| **i)
+            .map(|(_, digest)| digest.clone());
+        let digest = self.chain_digest(index, previous.as_ref());
+        self.chain_index.insert(index, digest);
+        self
+    }
+
+    /// Check the chained digests against the evaluations, returning the first tick whose
+    /// evaluations or digest were altered
+    pub fn verify_chain(&self) -> Result<(), u64> {
+        let mut indexes = self.chain_index.keys().copied().collect::<Vec<_>>();
+        indexes.sort_unstable();
+        let mut previous = None;
+        for index in indexes {
+            let digest = &self.chain_index[&index];
+            if self.chain_digest(index, previous) != *digest {
+                return Err(index);
+            }
+            previous = Some(digest);
+        }
+        Ok(())
+    }
+}
+
+/// Pass rates of a task in two runs
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct PassRateChange {
+    /// Pass rate in the first run, `None` if the task was never evaluated
+    pub before: Option<f64>,
+    /// Pass rate in the second run, `None` if the task was never evaluated
+    pub after: Option<f64>,
+}
+
+/// Differences between the evaluations of two runs
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ComparisonReport<Tasks>
+where
+    Tasks: Ord,
+{
+    pub pass_rates: BTreeMap<Tasks, PassRateChange>,
+    /// Tasks that never failed in the first run and failed in the second one
+    pub newly_failing: Vec<Tasks>,
+}
+
+impl<Metrics, Tasks, Data> Table<Metrics, Tasks, Data>
+where
+    Metrics: Hash + Eq,
+    Tasks: Hash + Eq + Ord + Clone,
+{
+    /// Ratio of the evaluations of a task that are true, ignoring undecided ones
+    pub fn pass_rate(&self, task: &Tasks) -> Option<f64> {
+        let values = self
+            .evaluations_index
+            .get(task)?
+            .values()
+            .filter_map(|value| *value)
+            .collect::<Vec<_>>();
+        if values.is_empty() {
+            None
+        } else {
+            Some(values.iter().filter(|value| **value).count() as f64 / values.len() as f64)
+        }
+    }
+
+    /// Compare the evaluations of this run with the ones of a later run
+    pub fn compare(&self, other: &Self) -> ComparisonReport<Tasks> {
+        let tasks = self
+            .evaluations_index
+            .keys()
+            .chain(other.evaluations_index.keys())
+            .collect::<BTreeSet<_>>();
+        let pass_rates = tasks
+            .iter()
+            .map(|task| {
+                let change = PassRateChange {
+                    before: self.pass_rate(task),
+                    after: other.pass_rate(task),
+                };
+                ((*task).clone(), change)
+            })
+            .collect::<BTreeMap<_, _>>();
+        let newly_failing = pass_rates
+            .iter()
+            .filter(|(_, change)| {
+                change.before.is_none_or(|rate| rate >= 1.0)
+                    && change.after.is_some_and(|rate| rate < 1.0)
+            })
+            .map(|(task, _)| task.clone())
+            .collect();
+        ComparisonReport {
+            pass_rates,
+            newly_failing,
+        }
+    }
+}
+
+/// Latest measurements of a metric in a baseline run and in a later run
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Drift<Data> {
+    pub baseline: Data,
+    pub current: Data,
+}
+
+impl<Metrics, Tasks, Data> Table<Metrics, Tasks, Data>
+where
+    Metrics: Hash + Eq + Ord + Clone,
+    Tasks: Hash + Eq,
+    Data: Numeric + PartialEq + Clone,
+{
+    /// Measurement of a metric with the highest index
+    pub fn latest_measurement(&self, metric: &Metrics) -> Option<&Data> {
+        self.measurements_index
+            .get(metric)?
+            .iter()
+            .max_by_key(|(index, _)| **index)
+            .map(|(_, data)| data)
+    }
+
+    /// Metrics among `metrics` whose latest measurement in `other` drifted from the one of this
+    /// baseline run
+    ///
+    /// Numeric metrics drift when they move away from the baseline by more than `tolerance`
+    /// times its value, the other ones as soon as they differ. Metrics not measured in either
+    /// run are left out.
+    pub fn drift(
+        &self,
+        other: &Self,
+        metrics: &[Metrics],
+        tolerance: f64,
+    ) -> BTreeMap<Metrics, Drift<Data>> {
+        metrics
+            .iter()
+            .filter_map(|metric| {
+                let baseline = self.latest_measurement(metric)?;
+                let current = other.latest_measurement(metric)?;
+                let drifted = match (baseline.as_f64(), current.as_f64()) {
+                    (Some(before), Some(after)) => {
+                        (after - before).abs() > tolerance * before.abs()
+                    }
+                    _ => baseline != current,
+                };
+                let drift = Drift {
+                    baseline: baseline.clone(),
+                    current: current.clone(),
+                };
+                Some((metric.clone(), drift)).filter(|_| drifted)
+            })
+            .collect()
+    }
+}
+
 pub use crate::command::nfdc::PacketStatistics;
 
 #[cfg(test)]
 mod tests {
     use crate::task::*;
 
-    #[derive(Debug, PartialEq, Eq, Hash, Clone, Copy)]
+    #[derive(Debug, PartialEq, Eq, PartialOrd, Ord, Hash, Clone, Copy)]
     enum Metrics {
         M1,
     }
 
-    #[derive(Debug, PartialEq, Eq, Hash, Clone, Copy)]
+    #[derive(Debug, PartialEq, Eq, PartialOrd, Ord, Hash, Clone, Copy, Serialize)]
     enum Tasks {
         R1,
+        R2,
+        R3,
     }
 
     #[derive(Debug, PartialEq, Clone)]
@@ -376,4 +1048,332 @@ mod tests {
             [0, 1, 2, 3]
         );
     }
+
+    #[test]
+    fn test_merge_tables() {
+        let mut log1: Logs<Metrics, Tasks, Data> = Logs::default();
+        let mut log2: Logs<Metrics, Tasks, Data> = Logs::default();
+        for index in 0..3 {
+            log1.insert_measurement(Measurement::new(Data::M1(index), index), Metrics::M1);
+            log1.insert_evaluation(Evaluation::new(true, index), Tasks::R1);
+            log1.insert_duration(10, index);
+            log1.insert_score(50.0, index);
+        }
+        for index in 2..5 {
+            log2.insert_measurement(Measurement::new(Data::M1(index), index), Metrics::M1);
+            log2.insert_evaluation(Evaluation::new(false, index), Tasks::R1);
+            log2.insert_evaluation(Evaluation::new(true, index), Tasks::R2);
+            log2.insert_duration(20, index);
+            log2.insert_score(100.0, index);
+        }
+
+        let table = log1.to_table().merge(&log2.to_table());
+        assert_eq!(table.measurements_index[&Metrics::M1].len(), 5);
+        // Index 2 was measured at different times in the two runs
+        assert_eq!(table.measurements_timestamp[&Metrics::M1].len(), 6);
+        assert_eq!(table.evaluations_index[&Tasks::R1].len(), 5);
+        assert_eq!(table.evaluations_index[&Tasks::R1][&1], Some(true));
+        assert_eq!(table.evaluations_index[&Tasks::R1][&2], Some(false));
+        assert_eq!(table.evaluations_index[&Tasks::R2].len(), 3);
+        assert_eq!(table.duration_index.len(), 5);
+        assert_eq!(table.duration_index[&0], 10);
+        assert_eq!(table.duration_index[&4], 20);
+        assert_eq!(table.scores_index[&0], 50.0);
+        assert_eq!(table.scores_index[&2], 100.0);
+
+        let logs: Logs<Metrics, Tasks, Data> = table.clone().into();
+        assert_eq!(
+            logs.measurements_index[&Metrics::M1]
+                .iter()
+                .map(|(i, _)| *i)
+                .collect::<Vec<_>>(),
+            [0, 1, 2, 3, 4]
+        );
+        assert_eq!(logs.scores_index, table.scores_index);
+    }
+
+    #[test]
+    fn test_transitions_since() {
+        let mut logs: Logs<Metrics, Tasks, Data> = Logs::default();
+        let start = Utc::now();
+        let values = [true, false, true, false, true, false, true, true];
+        for (i, value) in values.iter().enumerate() {
+            let mut evaluation = Evaluation::new(*value, i as u64);
+            evaluation.timestamp = start + chrono::Duration::seconds(i as i64);
+            logs.insert_evaluation(evaluation, Tasks::R1);
+        }
+        // Flapping more than the default limit of 4 transitions
+        assert_eq!(logs.transitions_since(&Tasks::R1, start), 6);
+        assert_eq!(
+            logs.transitions_since(&Tasks::R1, start + chrono::Duration::seconds(5)),
+            1
+        );
+        assert_eq!(
+            logs.transitions_since(&Tasks::R1, start + chrono::Duration::seconds(10)),
+            0
+        );
+    }
+
+    #[test]
+    fn test_all_true_since() {
+        let mut logs: Logs<Metrics, Tasks, Data> = Logs::default();
+        let start = Utc::now();
+        assert_eq!(logs.all_true_since(&Tasks::R1, start, 0), Some(true));
+        assert_eq!(logs.all_true_since(&Tasks::R1, start, 1), None);
+        let values = [None, Some(true), Some(false), Some(true), Some(true)];
+        for (i, value) in values.iter().enumerate() {
+            let mut evaluation = Evaluation::new(*value, i as u64);
+            evaluation.timestamp = start + chrono::Duration::seconds(i as i64);
+            logs.insert_evaluation(evaluation, Tasks::R1);
+        }
+        assert_eq!(logs.all_true_since(&Tasks::R1, start, 4), Some(false));
+        assert_eq!(logs.all_true_since(&Tasks::R1, start, 5), None);
+        let since = start + chrono::Duration::seconds(3);
+        assert_eq!(logs.all_true_since(&Tasks::R1, since, 2), Some(true));
+        assert_eq!(logs.all_true_since(&Tasks::R1, since, 3), None);
+    }
+
+    impl Numeric for Data {
+        fn as_f64(&self) -> Option<f64> {
+            match self {
+                Data::M1(v) => Some(*v as f64),
+            }
+        }
+    }
+
+    #[test]
+    fn test_downsample() {
+        let start = Utc.ymd(2021, 1, 1).and_hms(0, 0, 0);
+        let mut logs: Logs<Metrics, Tasks, Data> = Logs::default();
+        for index in 0..10 {
+            let mut measurement = Measurement::new(Data::M1(index), index);
+            measurement.timestamp = start + Duration::seconds(20 * index as i64);
+            logs.insert_measurement(measurement, Metrics::M1);
+        }
+        let full = logs.clone();
+        logs.downsample_older_than(start + Duration::seconds(150), Duration::minutes(1));
+
+        let summaries = &logs.summaries_timestamp[&Metrics::M1];
+        let expected = vec![
+            (start, 3, 0.0, 2.0, 1.0),
+            (start + Duration::minutes(1), 3, 3.0, 5.0, 4.0),
+            (start + Duration::minutes(2), 2, 6.0, 7.0, 6.5),
+        ];
+        let actual = summaries
+            .iter()
+            .map(|(t, s)| (*t, s.count, s.min, s.max, s.mean))
+            .collect::<Vec<_>>();
+        assert_eq!(actual, expected);
+        let indexes = logs.measurements_index[&Metrics::M1]
+            .iter()
+            .map(|(index, _)| *index)
+            .collect::<Vec<_>>();
+        assert_eq!(indexes, vec![8, 9]);
+        assert_eq!(logs.measurements_timestamp[&Metrics::M1].len(), 2);
+
+        // Older logs merged back do not restore the downsampled measurements
+        let mut merged = logs.clone();
+        merged.mut_merge(&full);
+        assert_eq!(merged, logs);
+        assert_eq!(Logs::from(logs.to_table()), logs);
+
+        // Later downsampling extends the bucket in progress
+        logs.downsample_older_than(start + Duration::minutes(4), Duration::minutes(1));
+        let summaries = &logs.summaries_timestamp[&Metrics::M1];
+        assert_eq!(summaries.len(), 4);
+        assert_eq!((summaries[2].1.count, summaries[2].1.mean), (3, 7.0));
+        assert!(logs.measurements_timestamp[&Metrics::M1].is_empty());
+    }
+
+    #[test]
+    fn test_hash_chain() {
+        let mut logs: Logs<Metrics, Tasks, Data> = Logs::default();
+        for index in 0..5 {
+            logs.insert_evaluation(Evaluation::new(index % 2 == 0, index), Tasks::R1);
+            logs.insert_evaluation(Evaluation::new(None, index), Tasks::R2);
+            logs.insert_chain_link(index);
+        }
+        assert_eq!(logs.verify_chain(), Ok(()));
+        assert_ne!(logs.chain_index[&1], logs.chain_index[&3]);
+
+        let mut altered = logs.clone();
+        altered.evaluations_index.get_mut(&Tasks::R2).unwrap()[2].1 = Some(true);
+        assert_eq!(altered.verify_chain(), Err(2));
+
+        // Recomputing the altered digest alone still breaks the next link
+        let mut altered = logs.clone();
+        altered.evaluations_index.get_mut(&Tasks::R1).unwrap()[2].1 = Some(false);
+        let digest = altered.chain_digest(2, altered.chain_index.get(&1));
+        altered.chain_index.insert(2, digest);
+        assert_eq!(altered.verify_chain(), Err(3));
+    }
+
+    #[test]
+    fn test_holds_since() {
+        let mut logs: Logs<Metrics, Tasks, Data> = Logs::default();
+        let start = Utc::now();
+        let values = [Some(true), Some(false), None, Some(true), Some(true)];
+        for (i, value) in values.iter().enumerate() {
+            let mut evaluation = Evaluation::new(*value, i as u64);
+            evaluation.timestamp = start + chrono::Duration::seconds(i as i64);
+            logs.insert_evaluation(evaluation, Tasks::R1);
+        }
+        let holds = |logs: &Logs<Metrics, Tasks, Data>, aggregation| {
+            logs.holds_since(&Tasks::R1, start, 4, aggregation)
+        };
+        assert_eq!(holds(&logs, Aggregation::AllInWindow), Some(false));
+        assert_eq!(holds(&logs, Aggregation::LatestOnly), Some(true));
+        assert_eq!(holds(&logs, Aggregation::AtLeastFraction(0.75)), Some(true));
+        assert_eq!(holds(&logs, Aggregation::AtLeastFraction(0.8)), Some(false));
+        assert_eq!(
+            logs.holds_since(&Tasks::R1, start, 5, Aggregation::LatestOnly),
+            None
+        );
+        assert_eq!(logs.samples_since(&Tasks::R1, start), 4);
+        let later = start + chrono::Duration::seconds(2);
+        assert_eq!(logs.samples_since(&Tasks::R1, later), 2);
+        assert_eq!(logs.samples_since(&Tasks::R2, start), 0);
+
+        let mut evaluation = Evaluation::new(false, 5);
+        evaluation.timestamp = start + chrono::Duration::seconds(5);
+        logs.insert_evaluation(evaluation, Tasks::R1);
+        assert_eq!(holds(&logs, Aggregation::LatestOnly), Some(false));
+        assert_eq!(holds(&logs, Aggregation::AtLeastFraction(0.6)), Some(true));
+    }
+
+    #[test]
+    fn test_skipped_evaluations() {
+        let start = Utc::now();
+        let run = |values: &[Option<bool>]| {
+            let mut logs: Logs<Metrics, Tasks, Data> = Logs::default();
+            for (i, value) in values.iter().enumerate() {
+                let mut evaluation = match value {
+                    Some(value) => Evaluation::new(*value, i as u64),
+                    None => Evaluation::skipped(i as u64),
+                };
+                evaluation.timestamp = start + chrono::Duration::seconds(i as i64);
+                logs.insert_evaluation(evaluation, Tasks::R1);
+            }
+            logs
+        };
+        let decided = run(&[Some(true), Some(false), Some(true)]);
+        let interleaved = run(&[None, Some(true), None, Some(false), None, Some(true), None]);
+        assert!(Evaluation::skipped(0).is_skipped());
+        assert_eq!(
+            interleaved.all_true_since(&Tasks::R1, start, 3),
+            decided.all_true_since(&Tasks::R1, start, 3)
+        );
+        assert_eq!(interleaved.all_true_since(&Tasks::R1, start, 4), None);
+        assert_eq!(
+            interleaved.transitions_since(&Tasks::R1, start),
+            decided.transitions_since(&Tasks::R1, start)
+        );
+        assert_eq!(
+            interleaved.to_table().pass_rate(&Tasks::R1),
+            decided.to_table().pass_rate(&Tasks::R1)
+        );
+        assert_eq!(run(&[None, None]).to_table().pass_rate(&Tasks::R1), None);
+    }
+
+    #[test]
+    fn test_compare_tables() {
+        let run = |r1: &[bool], r2: &[bool], r3: &[bool]| {
+            let mut logs: Logs<Metrics, Tasks, Data> = Logs::default();
+            for (task, values) in [(Tasks::R1, r1), (Tasks::R2, r2), (Tasks::R3, r3)].iter() {
+                for (i, value) in values.iter().enumerate() {
+                    logs.insert_evaluation(Evaluation::new(*value, i as u64), *task);
+                }
+            }
+            logs.to_table()
+        };
+        let before = run(&[true, true], &[true, false], &[false, false]);
+        let after = run(&[true, true], &[true, true], &[true, false]);
+        let regressed = run(&[true, false, true, true], &[true, false], &[false, false]);
+
+        let report = before.compare(&after);
+        assert!(report.newly_failing.is_empty());
+        assert_eq!(report.pass_rates[&Tasks::R2].before, Some(0.5));
+        assert_eq!(report.pass_rates[&Tasks::R2].after, Some(1.0));
+
+        let report = before.compare(&regressed);
+        assert_eq!(report.newly_failing, vec![Tasks::R1]);
+        assert_eq!(report.pass_rates[&Tasks::R1].before, Some(1.0));
+        assert_eq!(report.pass_rates[&Tasks::R1].after, Some(0.75));
+        assert_eq!(report.pass_rates[&Tasks::R3].after, Some(0.0));
+    }
+
+    #[test]
+    fn test_drift_from_baseline() {
+        let run = |values: &[u64]| {
+            let mut logs: Logs<Metrics, Tasks, Data> = Logs::default();
+            for (i, value) in values.iter().enumerate() {
+                logs.insert_measurement(Measurement::new(Data::M1(*value), i as u64), Metrics::M1);
+            }
+            logs.to_table()
+        };
+        let baseline = run(&[50, 100]);
+        assert!(baseline
+            .drift(&run(&[200, 105]), &[Metrics::M1], 0.1)
+            .is_empty());
+        let drift = baseline.drift(&run(&[100, 120]), &[Metrics::M1], 0.1);
+        assert_eq!(drift[&Metrics::M1].baseline, Data::M1(100));
+        assert_eq!(drift[&Metrics::M1].current, Data::M1(120));
+        assert!(baseline.drift(&run(&[]), &[Metrics::M1], 0.1).is_empty());
+        assert!(baseline.drift(&run(&[120]), &[], 0.1).is_empty());
+    }
+
+    #[test]
+    fn test_recent_durations() {
+        let mut logs: Logs<Metrics, Tasks, Data> = Logs::default();
+        assert!(logs.recent_durations(3).is_empty());
+        for index in [4, 0, 3, 1, 2].iter() {
+            logs.insert_duration(*index as i64 * 10, *index);
+        }
+        assert_eq!(logs.recent_durations(3), [20, 30, 40]);
+        assert_eq!(logs.recent_durations(10), [0, 10, 20, 30, 40]);
+    }
+
+    #[test]
+    fn test_chronological_measurements() {
+        let start = Utc::now();
+        let measurement = |index: u64, offset: i64| Measurement {
+            data: Data::M1(index),
+            index,
+            timestamp: start + chrono::Duration::seconds(offset),
+            source_digest: None,
+            completed_at: None,
+        };
+        let mut measurements = vec![
+            measurement(3, 0),
+            measurement(0, 2),
+            measurement(2, 1),
+            measurement(0, 1),
+            measurement(1, 5),
+        ];
+        measurements.sort_by(Measurement::chronological);
+        assert_eq!(
+            measurements
+                .iter()
+                .map(|m| (m.index, (m.timestamp - start).num_seconds()))
+                .collect::<Vec<_>>(),
+            [(0, 1), (0, 2), (1, 5), (2, 1), (3, 0)]
+        );
+
+        let mut logs: Logs<Metrics, Tasks, Data> = Logs::default();
+        for m in measurements.into_iter().rev() {
+            logs.insert_measurement(m, Metrics::M1);
+        }
+        let rebuilt = Logs::from(logs.to_table());
+        assert_eq!(
+            rebuilt.measurements_index[&Metrics::M1]
+                .iter()
+                .map(|(i, _)| *i)
+                .collect::<Vec<_>>(),
+            [0, 1, 2, 3]
+        );
+        assert!(rebuilt.measurements_timestamp[&Metrics::M1]
+            .iter()
+            .zip(rebuilt.measurements_timestamp[&Metrics::M1].iter().skip(1))
+            .all(|((a, _), (b, _))| a < b));
+    }
 }
@@ -0,0 +1,146 @@
+use nom::{
+    bytes::complete::tag,
+    character::complete::{alphanumeric1, digit1},
+    combinator::{all_consuming, map_res, opt},
+    sequence::{preceded, tuple},
+    IResult,
+};
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+use std::fmt;
+use std::str::FromStr;
+use thiserror::Error as ThisError;
+
+#[derive(Debug, Clone, PartialEq, Eq, ThisError)]
+#[error("Invalid version: {0}")]
+pub struct ParseVersionError(String);
+
+/// Position of a build after a release tag, as printed by `git describe`
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct GitMetadata {
+    /// Commits on top of the release
+    pub commits: u64,
+    /// Abbreviated hash of the built commit
+    pub hash: String,
+}
+
+/// NFD version, e.g. `0.7.1` or `0.7.1-26-g8d9953e7`
+///
+/// Versions are ordered by release, then by commits on top of it: a development build follows
+/// the release it is based on.
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Version {
+    pub major: u64,
+    pub minor: u64,
+    pub patch: u64,
+    pub git: Option<GitMetadata>,
+}
+
+fn number(input: &str) -> IResult<&str, u64> {
+    map_res(digit1, u64::from_str)(input)
+}
+
+impl Version {
+    pub const fn new(major: u64, minor: u64, patch: u64) -> Self {
+        Version {
+            major,
+            minor,
+            patch,
+            git: None,
+        }
+    }
+
+    /// Release the version is based on, without the git metadata
+    pub fn core(&self) -> Version {
+        Version::new(self.major, self.minor, self.patch)
+    }
+
+    fn parse(input: &str) -> IResult<&str, Self> {
+        let (input, (major, _, minor, _, patch)) =
+            tuple((number, tag("."), number, tag("."), number))(input)?;
+        let (input, git) = opt(tuple((
+            preceded(tag("-"), number),
+            preceded(tag("-g"), alphanumeric1),
+        )))(input)?;
+        let git = git.map(|(commits, hash)| GitMetadata {
+            commits,
+            hash: hash.to_string(),
+        });
+        Ok((
+            input,
+            Version {
+                major,
+                minor,
+                patch,
+                git,
+            },
+        ))
+    }
+}
+
+impl FromStr for Version {
+    type Err = ParseVersionError;
+    fn from_str(input: &str) -> Result<Self, Self::Err> {
+        let (_, version) = all_consuming(Self::parse)(input.trim())
+            .map_err(|_| ParseVersionError(input.to_string()))?;
+        Ok(version)
+    }
+}
+
+impl fmt::Display for Version {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}.{}.{}", self.major, self.minor, self.patch)?;
+        if let Some(git) = &self.git {
+            write!(f, "-{}-g{}", git.commits, git.hash)?;
+        }
+        Ok(())
+    }
+}
+
+impl Serialize for Version {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_str(&self.to_string())
+    }
+}
+
+impl<'de> Deserialize<'de> for Version {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        String::deserialize(deserializer)?
+            .parse()
+            .map_err(serde::de::Error::custom)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn parse_versions() {
+        let release: Version = "0.7.1".parse().unwrap();
+        assert_eq!(release, Version::new(0, 7, 1));
+        let build: Version = "0.7.1-5-gdbc23a5c".parse().unwrap();
+        assert_eq!(
+            build.git,
+            Some(GitMetadata {
+                commits: 5,
+                hash: "dbc23a5c".to_string()
+            })
+        );
+        assert_eq!(build.to_string(), "0.7.1-5-gdbc23a5c");
+        assert!("0.7".parse::<Version>().is_err());
+        assert!("0.7.1-5".parse::<Version>().is_err());
+        assert!("v0.7.1".parse::<Version>().is_err());
+    }
+
+    #[test]
+    fn compare_versions() {
+        let release: Version = "0.7.1".parse().unwrap();
+        let build: Version = "0.7.1-5-gdbc23a5c".parse().unwrap();
+        assert_eq!(release.core(), build.core());
+        assert_ne!(release, build);
+        assert!(release < build);
+        assert!("0.6.6".parse::<Version>().unwrap() < "0.7.0".parse().unwrap());
+        assert!(build < "0.7.2".parse().unwrap());
+        assert!(Version::new(0, 10, 0) > Version::new(0, 9, 9));
+    }
+}